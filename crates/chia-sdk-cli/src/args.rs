@@ -1,9 +1,33 @@
-use clap::Parser;
+use std::{net::IpAddr, ops::Range, path::PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
 pub enum Command {
     Encode(EncodeCommand),
     Decode(DecodeCommand),
+    #[clap(subcommand)]
+    SpendBundle(SpendBundleCommand),
+    #[clap(subcommand)]
+    Offer(OfferCommand),
+    #[clap(subcommand)]
+    Clvm(ClvmCommand),
+    /// Compile a Chialisp file or Rue project.
+    Compile(CompileCommand),
+    #[clap(subcommand)]
+    Keys(KeysCommand),
+    #[clap(subcommand)]
+    Simulator(SimulatorCommand),
+    /// Sign a spend bundle offline with BLS secret keys.
+    Sign(SignCommand),
+    /// Verify the aggregated signature of a spend bundle.
+    Verify(VerifyCommand),
+    /// Query a full node or coinset.org.
+    Rpc(RpcCommand),
+    /// Identify the stack of known puzzle layers in a coin spend.
+    Identify(IdentifyCommand),
+    #[clap(subcommand)]
+    Vault(VaultCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -21,3 +45,473 @@ pub struct DecodeCommand {
     /// The bech32 string to decode.
     pub bech32: String,
 }
+
+#[derive(Debug, Subcommand)]
+pub enum SpendBundleCommand {
+    /// Run every coin spend in a spend bundle and explain what it does.
+    Inspect(InspectSpendBundleCommand),
+}
+
+#[derive(Debug, Parser)]
+pub struct InspectSpendBundleCommand {
+    /// A file containing the hex or JSON encoded spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OfferCommand {
+    /// Decode an offer into its spend bundle.
+    Decode(DecodeOfferCommand),
+    /// Encode a spend bundle as an offer.
+    Encode(EncodeOfferCommand),
+    /// Summarize what an offer offers and requests.
+    Summary(OfferSummaryCommand),
+    /// Decode and re-encode an offer, to normalize offers produced by other tools.
+    Normalize(NormalizeOfferCommand),
+}
+
+#[derive(Debug, Parser)]
+pub struct DecodeOfferCommand {
+    /// An offer string, or a file containing one. Reads from stdin if omitted.
+    pub input: Option<String>,
+
+    /// Output the spend bundle as JSON instead of hex.
+    #[clap(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct EncodeOfferCommand {
+    /// A file containing the hex or JSON encoded spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct OfferSummaryCommand {
+    /// An offer string, or a file containing one. Reads from stdin if omitted.
+    pub input: Option<String>,
+
+    /// Output the summary as JSON.
+    #[clap(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct NormalizeOfferCommand {
+    /// An offer string, or a file containing one. Reads from stdin if omitted.
+    pub input: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ClvmCommand {
+    /// Run a program with a solution and print the result and cost.
+    Run(RunCommand),
+    /// Curry arguments into a program.
+    Curry(CurryCommand),
+    /// Split a curried program into its mod and arguments.
+    Uncurry(UncurryCommand),
+    /// Calculate the tree hash of a program.
+    TreeHash(TreeHashCommand),
+    /// Convert a program from assembly into serialized hex.
+    Assemble(AssembleCommand),
+    /// Convert a program from serialized hex into assembly.
+    Disassemble(DisassembleCommand),
+}
+
+#[derive(Debug, Parser)]
+pub struct RunCommand {
+    /// The program, as hex, assembly, or a file containing either.
+    pub program: String,
+
+    /// The solution, as hex, assembly, or a file containing either.
+    #[clap(default_value = "()")]
+    pub solution: String,
+
+    /// The maximum cost allowed for the run.
+    #[clap(long, default_value_t = 11_000_000_000)]
+    pub max_cost: u64,
+
+    /// Run with mempool restrictions.
+    #[clap(long)]
+    pub mempool: bool,
+
+    /// Output the result as hex instead of assembly.
+    #[clap(long)]
+    pub hex: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct CurryCommand {
+    /// The program, as hex, assembly, or a file containing either.
+    pub program: String,
+
+    /// The arguments to curry, in order.
+    pub args: Vec<String>,
+
+    /// Output the result as hex instead of assembly.
+    #[clap(long)]
+    pub hex: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct UncurryCommand {
+    /// The program, as hex, assembly, or a file containing either.
+    pub program: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct TreeHashCommand {
+    /// The program, as hex, assembly, or a file containing either.
+    pub program: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct AssembleCommand {
+    /// The program, as assembly or a file containing it.
+    pub program: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct DisassembleCommand {
+    /// The program, as hex or a file containing it.
+    pub program: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct CompileCommand {
+    /// The Chialisp file, Rue file, or Rue project directory to compile.
+    #[clap(required_unless_present = "check")]
+    pub path: Option<PathBuf>,
+
+    /// A directory to search for Chialisp includes, defaults to `.` and `include`.
+    #[clap(short = 'i', long = "include")]
+    pub include_paths: Vec<String>,
+
+    /// The Rue export to compile instead of `main`.
+    #[clap(long)]
+    pub export: Option<String>,
+
+    /// Compile Rue with debug information.
+    #[clap(long)]
+    pub debug: bool,
+
+    /// Compile every entry in a manifest and fail if any puzzle hash has changed.
+    #[clap(long, conflicts_with = "path")]
+    pub check: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KeysCommand {
+    /// Generate a new mnemonic and show its keys.
+    Generate(GenerateKeyCommand),
+    /// Show the master, farmer, pool and first wallet keys.
+    Show(ShowKeyCommand),
+    /// Derive wallet keys, puzzle hashes and addresses for a range of indices.
+    Derive(DeriveKeysCommand),
+    /// Print the address for a single derivation index.
+    Address(KeyAddressCommand),
+}
+
+#[derive(Debug, Parser)]
+pub struct KeyInput {
+    /// A file containing the mnemonic, or stdin if omitted.
+    #[clap(long)]
+    pub mnemonic_file: Option<PathBuf>,
+
+    /// A master public key in hex, to derive observer keys without the mnemonic.
+    #[clap(long, conflicts_with = "mnemonic_file")]
+    pub public_key: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct GenerateKeyCommand {
+    /// The number of words in the mnemonic, either 12 or 24.
+    #[clap(long, default_value_t = 24)]
+    pub words: usize,
+
+    /// The bech32 prefix to use.
+    #[clap(short, long, default_value = "xch")]
+    pub prefix: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct ShowKeyCommand {
+    #[clap(flatten)]
+    pub key: KeyInput,
+
+    /// The bech32 prefix to use.
+    #[clap(short, long, default_value = "xch")]
+    pub prefix: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct DeriveKeysCommand {
+    #[clap(flatten)]
+    pub key: KeyInput,
+
+    /// The range of derivation indices, as `start..end` with an exclusive end.
+    #[clap(long, default_value = "0..10", value_parser = parse_index_range)]
+    pub index_range: Range<u32>,
+
+    /// The bech32 prefix to use.
+    #[clap(short, long, default_value = "xch")]
+    pub prefix: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct KeyAddressCommand {
+    #[clap(flatten)]
+    pub key: KeyInput,
+
+    /// The derivation index.
+    #[clap(long, default_value_t = 0)]
+    pub index: u32,
+
+    /// Use hardened derivation, which requires the mnemonic.
+    #[clap(long)]
+    pub hardened: bool,
+
+    /// The bech32 prefix to use.
+    #[clap(short, long, default_value = "xch")]
+    pub prefix: String,
+}
+
+fn parse_index_range(value: &str) -> Result<Range<u32>, String> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| "expected a range such as 0..10".to_string())?;
+
+    let start: u32 = start
+        .parse()
+        .map_err(|_| format!("invalid start index {start}"))?;
+    let end: u32 = end
+        .parse()
+        .map_err(|_| format!("invalid end index {end}"))?;
+
+    if start >= end {
+        return Err("the range must not be empty".to_string());
+    }
+
+    Ok(start..end)
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SimulatorCommand {
+    /// Run a peer simulator that wallets can connect to as if it were a full node.
+    Serve(ServeSimulatorCommand),
+}
+
+#[derive(Debug, Parser)]
+pub struct ServeSimulatorCommand {
+    /// The address to listen on.
+    #[clap(long, default_value = "127.0.0.1")]
+    pub host: IpAddr,
+
+    /// The port to listen on.
+    #[clap(short, long, default_value_t = 58444)]
+    pub port: u16,
+
+    /// The directory to store the TLS certificate and key in, which are reused if they exist.
+    #[clap(long, default_value = "simulator-ssl")]
+    pub ssl_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum NetworkName {
+    Mainnet,
+    Testnet11,
+}
+
+#[derive(Debug, Parser)]
+pub struct AggSigArgs {
+    /// The network whose signature additional data is used.
+    #[clap(long, value_enum, default_value_t = NetworkName::Mainnet)]
+    pub network: NetworkName,
+
+    /// Custom `AGG_SIG_ME` additional data in hex, which overrides the network.
+    #[clap(long)]
+    pub agg_sig_me: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct SignCommand {
+    /// A file containing the hex or JSON encoded spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+
+    /// A file containing hex encoded secret keys, one per line.
+    #[clap(long)]
+    pub secret_key_file: Option<PathBuf>,
+
+    /// A file containing a mnemonic to derive secret keys from.
+    #[clap(long)]
+    pub mnemonic_file: Option<PathBuf>,
+
+    /// The number of wallet keys to derive from the mnemonic, both hardened and unhardened.
+    #[clap(long, default_value_t = 100)]
+    pub derivations: u32,
+
+    /// Sign what is possible even if some required signatures have no matching key.
+    #[clap(long)]
+    pub partial: bool,
+
+    /// Only print the aggregated signature, rather than the signed spend bundle.
+    #[clap(long)]
+    pub signature_only: bool,
+
+    /// Output the signed spend bundle as JSON instead of hex.
+    #[clap(long, conflicts_with = "signature_only")]
+    pub json: bool,
+
+    #[clap(flatten)]
+    pub agg_sig: AggSigArgs,
+}
+
+#[derive(Debug, Parser)]
+pub struct VerifyCommand {
+    /// A file containing the hex or JSON encoded spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub agg_sig: AggSigArgs,
+}
+
+#[derive(Debug, Parser)]
+pub struct RpcCommand {
+    #[clap(subcommand)]
+    pub request: RpcRequest,
+
+    /// The coinset.org network to query, unless a full node or custom URL is given.
+    #[clap(long, global = true, value_enum, default_value_t = NetworkName::Mainnet)]
+    pub network: NetworkName,
+
+    /// A custom base URL for a coinset compatible API.
+    #[clap(long, global = true, conflicts_with = "full_node")]
+    pub coinset_url: Option<String>,
+
+    /// The RPC URL of a full node, such as `https://localhost:8555`.
+    #[clap(long, global = true, requires_all = ["cert", "key"])]
+    pub full_node: Option<String>,
+
+    /// The full node's private RPC certificate, usually `private_full_node.crt`.
+    #[clap(long, global = true)]
+    pub cert: Option<PathBuf>,
+
+    /// The full node's private RPC key, usually `private_full_node.key`.
+    #[clap(long, global = true)]
+    pub key: Option<PathBuf>,
+
+    /// Output the raw response as JSON.
+    #[clap(long, global = true)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RpcRequest {
+    /// Get the coin records with a puzzle hash or address.
+    CoinRecords(CoinRecordsRequest),
+    /// Get the coin record with a coin id.
+    CoinRecord(CoinRecordRequest),
+    /// Get the puzzle reveal and solution of a spent coin.
+    PuzzleAndSolution(PuzzleAndSolutionRequest),
+    /// Get the current state of the blockchain.
+    BlockchainState,
+    /// Submit a spend bundle to the mempool.
+    PushTx(PushTxRequest),
+}
+
+#[derive(Debug, Parser)]
+pub struct CoinRecordsRequest {
+    /// The puzzle hash or address.
+    pub puzzle_hash: String,
+
+    /// Include coins that have already been spent.
+    #[clap(long)]
+    pub include_spent: bool,
+
+    /// The minimum confirmed height.
+    #[clap(long)]
+    pub start_height: Option<u32>,
+
+    /// The maximum confirmed height.
+    #[clap(long)]
+    pub end_height: Option<u32>,
+
+    /// The cursor returned by a previous truncated response.
+    #[clap(long)]
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct CoinRecordRequest {
+    /// The coin id.
+    pub coin_id: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct PuzzleAndSolutionRequest {
+    /// The coin id.
+    pub coin_id: String,
+
+    /// The height the coin was spent at, if known.
+    #[clap(long)]
+    pub height: Option<u32>,
+}
+
+#[derive(Debug, Parser)]
+pub struct PushTxRequest {
+    /// A file containing the hex or JSON encoded spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct IdentifyCommand {
+    /// A file containing the hex or JSON encoded coin spend or spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VaultCommand {
+    /// Print a clear signing summary of a vault transaction for review before co-signing.
+    Review(ReviewVaultCommand),
+}
+
+#[derive(Debug, Parser)]
+pub struct ReviewVaultCommand {
+    /// A file containing the hex or JSON encoded spend bundle, or stdin if omitted.
+    pub input: Option<PathBuf>,
+
+    /// A JSON file with additional reveals (coin spends, clawbacks, p2 puzzles and vault nonces).
+    #[clap(long)]
+    pub reveals: Option<PathBuf>,
+
+    /// The launcher id of the vault, required if the spend bundle contains multiple vaults.
+    #[clap(long)]
+    pub launcher_id: Option<String>,
+
+    /// The delegated puzzle being signed for, if the vault spend isn't in the spend bundle.
+    #[clap(long)]
+    pub delegated_puzzle: Option<String>,
+
+    /// The solution to the delegated puzzle.
+    #[clap(long, default_value = "()", requires = "delegated_puzzle")]
+    pub delegated_solution: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index_range() {
+        assert_eq!(parse_index_range("0..10"), Ok(0..10));
+        assert_eq!(parse_index_range("5..6"), Ok(5..6));
+        assert_eq!(parse_index_range("0..4294967295"), Ok(0..u32::MAX));
+
+        assert!(parse_index_range("10").is_err());
+        assert!(parse_index_range("5..5").is_err());
+        assert!(parse_index_range("6..5").is_err());
+        assert!(parse_index_range("-1..5").is_err());
+        assert!(parse_index_range("0..4294967296").is_err());
+        assert!(parse_index_range("0..=10").is_err());
+    }
+}
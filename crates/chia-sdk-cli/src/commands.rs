@@ -1,5 +1,25 @@
+mod clvm;
+mod compile;
 mod decode;
 mod encode;
+mod identify;
+mod keys;
+mod offer;
+mod rpc;
+mod sign;
+mod simulator;
+mod spend_bundle;
+mod vault;
 
+pub use clvm::*;
+pub use compile::*;
 pub use decode::*;
 pub use encode::*;
+pub use identify::*;
+pub use keys::*;
+pub use offer::*;
+pub use rpc::*;
+pub use sign::*;
+pub use simulator::*;
+pub use spend_bundle::*;
+pub use vault::*;
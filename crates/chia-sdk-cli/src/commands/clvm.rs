@@ -0,0 +1,151 @@
+use anyhow::{Result, bail};
+use chia_wallet_sdk::{
+    clvm_traits::{ClvmDecoder, ClvmEncoder},
+    clvm_utils::CurriedProgram,
+    clvmr::serde::{node_from_bytes_backrefs, node_to_bytes},
+    prelude::*,
+    types::run_puzzle_with_cost,
+};
+use chialisp::classic::clvm_tools::binutils::{assemble, disassemble};
+
+use crate::{
+    args::{
+        AssembleCommand, CurryCommand, DisassembleCommand, RunCommand, TreeHashCommand,
+        UncurryCommand,
+    },
+    input::{parse_program, read_program_text},
+};
+
+pub fn run_program(args: &RunCommand) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let program = parse_program(&mut ctx, &args.program)?;
+    let solution = parse_program(&mut ctx, &args.solution)?;
+
+    let (cost, output) =
+        match run_puzzle_with_cost(&mut ctx, program, solution, args.max_cost, args.mempool) {
+            Ok(reduction) => (reduction.0, reduction.1),
+            Err(error) => bail!("program failed: {error}"),
+        };
+
+    println!("{}", format_program(&ctx, output, args.hex)?);
+    println!("cost = {cost}");
+
+    Ok(())
+}
+
+pub fn curry_program(args: &CurryCommand) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let program = parse_program(&mut ctx, &args.program)?;
+
+    let mut curried_args = Vec::with_capacity(args.args.len());
+
+    for arg in &args.args {
+        curried_args.push(parse_program(&mut ctx, arg)?);
+    }
+
+    let mut args_ptr = ctx.one();
+
+    for arg in curried_args.into_iter().rev() {
+        args_ptr = ctx.encode_curried_arg(arg, args_ptr)?;
+    }
+
+    let curried = ctx.alloc(&CurriedProgram {
+        program,
+        args: args_ptr,
+    })?;
+
+    println!("{}", format_program(&ctx, curried, args.hex)?);
+    println!("tree hash = {}", ctx.tree_hash(curried));
+
+    Ok(())
+}
+
+pub fn uncurry_program(args: &UncurryCommand) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let program = parse_program(&mut ctx, &args.program)?;
+
+    let Some((mod_ptr, curried_args)) = uncurry(&ctx, program) else {
+        bail!("the program is not curried");
+    };
+
+    println!("mod hash = {}", ctx.tree_hash(mod_ptr));
+    println!("mod = {}", disassemble(&ctx, mod_ptr, None));
+
+    for (index, arg) in curried_args.into_iter().enumerate() {
+        println!();
+        println!("arg {index} = {}", disassemble(&ctx, arg, None));
+        println!("arg {index} tree hash = {}", ctx.tree_hash(arg));
+    }
+
+    Ok(())
+}
+
+pub fn tree_hash_program(args: &TreeHashCommand) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let program = parse_program(&mut ctx, &args.program)?;
+
+    println!("{}", ctx.tree_hash(program));
+
+    Ok(())
+}
+
+pub fn assemble_program(args: &AssembleCommand) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let text = read_program_text(&args.program)?;
+
+    let program = match assemble(&mut ctx, &text) {
+        Ok(program) => program,
+        Err(error) => bail!("invalid assembly: {error}"),
+    };
+
+    println!("{}", format_program(&ctx, program, true)?);
+
+    Ok(())
+}
+
+pub fn disassemble_program(args: &DisassembleCommand) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let text = read_program_text(&args.program)?;
+
+    let Ok(bytes) = parse_hex(&text) else {
+        bail!("expected a serialized program in hex");
+    };
+
+    let program = node_from_bytes_backrefs(&mut ctx, &bytes)?;
+
+    println!("{}", format_program(&ctx, program, false)?);
+
+    Ok(())
+}
+
+fn format_program(allocator: &Allocator, ptr: NodePtr, hex: bool) -> Result<String> {
+    if hex {
+        Ok(hex::encode(node_to_bytes(allocator, ptr)?))
+    } else {
+        Ok(disassemble(allocator, ptr, None))
+    }
+}
+
+fn uncurry(allocator: &Allocator, ptr: NodePtr) -> Option<(NodePtr, Vec<NodePtr>)> {
+    let curried = CurriedProgram::<NodePtr, NodePtr>::from_clvm(allocator, ptr).ok()?;
+
+    let mut args = Vec::new();
+    let mut args_ptr = curried.args;
+
+    while let Ok((first, rest)) = allocator.decode_curried_arg(&args_ptr) {
+        args.push(first);
+        args_ptr = rest;
+    }
+
+    if allocator.small_number(args_ptr) != Some(1) {
+        return None;
+    }
+
+    Some((curried.program, args))
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use chia_wallet_sdk::{
+    prelude::*,
+    types::{Compilation, compile_chialisp, compile_rue},
+};
+use serde::Deserialize;
+
+use crate::args::CompileCommand;
+
+/// An entry in a puzzle hash manifest, with paths relative to the manifest itself.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestEntry {
+    path: PathBuf,
+    hash: String,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    export: Option<String>,
+    #[serde(default)]
+    debug: bool,
+}
+
+pub fn compile(args: &CompileCommand) -> Result<()> {
+    if let Some(manifest) = &args.check {
+        return check_manifest(manifest);
+    }
+
+    let Some(path) = &args.path else {
+        bail!("a path is required when not using --check");
+    };
+
+    let compilation = compile_path(
+        path,
+        &args.include_paths,
+        args.export.as_deref(),
+        args.debug,
+    )?;
+
+    println!("{}", hex::encode(&compilation.reveal));
+    println!("tree hash = {}", Bytes32::from(compilation.hash));
+
+    Ok(())
+}
+
+fn check_manifest(path: &Path) -> Result<()> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+
+    let entries: BTreeMap<String, ManifestEntry> = serde_json::from_str(&text)
+        .with_context(|| format!("invalid manifest {}", path.display()))?;
+
+    let base = path.parent().unwrap_or(Path::new("."));
+
+    let mut mismatches = 0;
+
+    for (name, entry) in entries {
+        let expected = Bytes32::try_from(parse_hex(&entry.hash)?)
+            .with_context(|| format!("invalid hash for {name}"))?;
+
+        let include_paths: Vec<String> = entry
+            .include
+            .iter()
+            .map(|include| base.join(include).display().to_string())
+            .collect();
+
+        let compilation = compile_path(
+            &base.join(&entry.path),
+            &include_paths,
+            entry.export.as_deref(),
+            entry.debug,
+        )
+        .with_context(|| format!("could not compile {name}"))?;
+
+        let actual = Bytes32::from(compilation.hash);
+
+        if actual == expected {
+            println!("{name}: ok");
+        } else {
+            println!("{name}: mismatch (expected {expected}, got {actual})");
+            mismatches += 1;
+        }
+    }
+
+    if mismatches > 0 {
+        bail!("{mismatches} puzzle hash(es) no longer match the manifest");
+    }
+
+    Ok(())
+}
+
+fn compile_path(
+    path: &Path,
+    include_paths: &[String],
+    export: Option<&str>,
+    debug: bool,
+) -> Result<Compilation> {
+    let is_rue = path.is_dir() || path.extension().is_some_and(|ext| ext == "rue");
+
+    let compilation = if is_rue {
+        compile_rue(path, debug, export)?
+    } else if export.is_some() {
+        bail!("exports are only supported when compiling Rue");
+    } else if include_paths.is_empty() {
+        compile_chialisp(path, &[".".to_string(), "include".to_string()])?
+    } else {
+        compile_chialisp(path, include_paths)?
+    };
+
+    Ok(compilation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a puzzle and a manifest for it to a new temporary directory.
+    fn write_manifest(name: &str, manifest: impl FnOnce(&str) -> String) -> Result<PathBuf> {
+        let dir = std::env::temp_dir().join(format!("chia-sdk-cli-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+
+        let puzzle = dir.join("puzzle.clsp");
+        fs::write(&puzzle, "(mod (A) (+ A 1))")?;
+
+        let hash = Bytes32::from(compile_path(&puzzle, &[], None, false)?.hash);
+
+        let path = dir.join("manifest.json");
+        fs::write(&path, manifest(&hash.to_string()))?;
+
+        Ok(path)
+    }
+
+    #[test]
+    fn test_check_manifest_match() -> Result<()> {
+        let path = write_manifest("match", |hash| {
+            format!(r#"{{ "puzzle": {{ "path": "puzzle.clsp", "hash": "{hash}" }} }}"#)
+        })?;
+
+        check_manifest(&path)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_manifest_mismatch() -> Result<()> {
+        let path = write_manifest("mismatch", |_| {
+            format!(
+                r#"{{ "puzzle": {{ "path": "puzzle.clsp", "hash": "{}" }} }}"#,
+                Bytes32::default()
+            )
+        })?;
+
+        let error = check_manifest(&path).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "1 puzzle hash(es) no longer match the manifest"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_manifest_missing_entry() -> Result<()> {
+        let path = write_manifest("missing-file", |hash| {
+            format!(r#"{{ "missing": {{ "path": "missing.clsp", "hash": "{hash}" }} }}"#)
+        })?;
+
+        let error = check_manifest(&path).unwrap_err();
+        assert_eq!(error.to_string(), "could not compile missing");
+
+        let path = write_manifest("missing-hash", |_| {
+            r#"{ "puzzle": { "path": "puzzle.clsp" } }"#.to_string()
+        })?;
+
+        let error = check_manifest(&path).unwrap_err();
+        assert!(error.to_string().starts_with("invalid manifest"), "{error}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,662 @@
+use anyhow::Result;
+use chia_wallet_sdk::{
+    driver::{
+        AugmentedConditionLayer, BulletinLayer, CatLayer, DelegatedPuzzleFeederLayer,
+        DelegationLayer, DidLayer, IndexWrapperLayer, NftOwnershipLayer, NftStateLayer,
+        OptionContractLayer, OracleLayer, P2CurriedLayer, P2DelegatedConditionsLayer,
+        P2OneOfManyLayer, P2SingletonLayer, RevocationLayer, RoyaltyTransferLayer, SingletonLayer,
+        SingletonMemberLayer, StreamLayer, WriterLayer,
+    },
+    prelude::*,
+    puzzles::SINGLETON_LAUNCHER_HASH,
+    types::puzzles::DidSolution,
+};
+use chialisp::classic::clvm_tools::binutils::disassemble;
+
+use crate::{
+    args::IdentifyCommand,
+    input::{parse_coin_spends, read_input},
+};
+
+pub fn identify(args: &IdentifyCommand) -> Result<()> {
+    let coin_spends = parse_coin_spends(&read_input(args.input.as_deref())?)?;
+
+    let mut allocator = Allocator::new();
+
+    for (index, coin_spend) in coin_spends.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+
+        let coin = coin_spend.coin;
+
+        println!("Spend {index}");
+        println!("  Coin id: {}", coin.coin_id());
+        println!("  Puzzle hash: {}", coin.puzzle_hash);
+        println!("  Amount: {}", coin.amount);
+
+        let puzzle = coin_spend.puzzle_reveal.to_clvm(&mut allocator)?;
+        let solution = coin_spend.solution.to_clvm(&mut allocator)?;
+
+        let puzzle_hash: Bytes32 = tree_hash(&allocator, puzzle).into();
+
+        if puzzle_hash != coin.puzzle_hash {
+            println!("  Puzzle reveal does not match the puzzle hash (got {puzzle_hash})");
+        }
+
+        let layers = identify_layers(&allocator, puzzle, Some(solution))?;
+
+        println!("  Layers: {}", format_stack(&layers));
+
+        for layer in &layers {
+            println!("  {}", layer.name);
+
+            for (label, value) in &layer.params {
+                println!("    {label}: {value}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A puzzle layer that was recognized, along with its curried parameters.
+#[derive(Debug, Clone)]
+struct IdentifiedLayer {
+    name: &'static str,
+    params: Vec<(&'static str, String)>,
+    inner_puzzle: Option<Puzzle>,
+    inner_solution: Option<NodePtr>,
+}
+
+impl IdentifiedLayer {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            params: Vec::new(),
+            inner_puzzle: None,
+            inner_solution: None,
+        }
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn param(mut self, label: &'static str, value: impl ToString) -> Self {
+        self.params.push((label, value.to_string()));
+        self
+    }
+
+    fn wraps(mut self, inner_puzzle: Puzzle, inner_solution: Option<NodePtr>) -> Self {
+        self.inner_puzzle = Some(inner_puzzle);
+        self.inner_solution = inner_solution;
+        self
+    }
+}
+
+/// Peels off each recognized layer from the outside in, until reaching a leaf or an unknown puzzle.
+/// The solution is optional, and is only used to identify the path revealed by a spend.
+fn identify_layers(
+    allocator: &Allocator,
+    puzzle: NodePtr,
+    solution: Option<NodePtr>,
+) -> Result<Vec<IdentifiedLayer>> {
+    let mut layers = Vec::new();
+    let mut puzzle = Puzzle::parse(allocator, puzzle);
+    let mut solution = solution;
+
+    loop {
+        let Some(layer) = identify_layer(allocator, puzzle, solution)? else {
+            layers.push(IdentifiedLayer::new("Unknown").param("Mod hash", puzzle.mod_hash()));
+            break;
+        };
+
+        let inner = layer
+            .inner_puzzle
+            .map(|inner| (inner, layer.inner_solution));
+
+        layers.push(layer);
+
+        let Some((inner_puzzle, inner_solution)) = inner else {
+            break;
+        };
+
+        puzzle = inner_puzzle;
+        solution = inner_solution;
+    }
+
+    Ok(layers)
+}
+
+fn identify_layer(
+    allocator: &Allocator,
+    puzzle: Puzzle,
+    solution: Option<NodePtr>,
+) -> Result<Option<IdentifiedLayer>> {
+    if let Some(layer) = SingletonLayer::<Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution = inner_solution::<SingletonLayer<Puzzle>>(allocator, solution, |s| {
+            Some(s.inner_solution)
+        });
+
+        return Ok(Some(
+            IdentifiedLayer::new("Singleton")
+                .param("Launcher id", layer.launcher_id)
+                .wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) = CatLayer::<Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution = inner_solution::<CatLayer<Puzzle>>(allocator, solution, |s| {
+            Some(s.inner_puzzle_solution)
+        });
+
+        return Ok(Some(
+            IdentifiedLayer::new("CAT")
+                .param("Asset id", layer.asset_id)
+                .wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) = NftStateLayer::<NodePtr, Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution =
+            inner_solution::<NftStateLayer<NodePtr, Puzzle>>(allocator, solution, |s| {
+                Some(s.inner_solution)
+            });
+
+        return Ok(Some(
+            IdentifiedLayer::new("NFTState")
+                .param("Metadata", disassemble(allocator, layer.metadata, None))
+                .param(
+                    "Metadata updater puzzle hash",
+                    layer.metadata_updater_puzzle_hash,
+                )
+                .wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) = NftOwnershipLayer::<Puzzle, Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution =
+            inner_solution::<NftOwnershipLayer<Puzzle, Puzzle>>(allocator, solution, |s| {
+                Some(s.inner_solution)
+            });
+
+        let mut identified = IdentifiedLayer::new("NFTOwnership")
+            .param("Current owner", format_optional(layer.current_owner));
+
+        if let Some(transfer) = RoyaltyTransferLayer::parse_puzzle(allocator, layer.transfer_layer)?
+        {
+            identified = identified
+                .param("Royalty puzzle hash", transfer.royalty_puzzle_hash)
+                .param("Royalty basis points", transfer.royalty_basis_points);
+        } else {
+            identified = identified.param(
+                "Transfer program hash",
+                layer.transfer_layer.curried_puzzle_hash(),
+            );
+        }
+
+        return Ok(Some(identified.wraps(layer.inner_puzzle, inner_solution)));
+    }
+
+    if let Some(layer) = DidLayer::<NodePtr, Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution =
+            inner_solution::<DidLayer<NodePtr, Puzzle>>(allocator, solution, |s| match s {
+                DidSolution::Spend(inner_solution) => Some(inner_solution),
+                DidSolution::Recover(_) => None,
+            });
+
+        return Ok(Some(
+            IdentifiedLayer::new("DID")
+                .param("Launcher id", layer.launcher_id)
+                .param(
+                    "Recovery list hash",
+                    format_optional(layer.recovery_list_hash),
+                )
+                .param("Verifications required", layer.num_verifications_required)
+                .param("Metadata", disassemble(allocator, layer.metadata, None))
+                .wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) = OptionContractLayer::<Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution =
+            inner_solution::<OptionContractLayer<Puzzle>>(allocator, solution, |s| {
+                Some(s.inner_solution)
+            });
+
+        return Ok(Some(
+            IdentifiedLayer::new("OptionContract")
+                .param("Underlying coin id", layer.underlying_coin_id)
+                .param(
+                    "Underlying delegated puzzle hash",
+                    layer.underlying_delegated_puzzle_hash,
+                )
+                .wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) =
+        AugmentedConditionLayer::<Program, Puzzle>::parse_puzzle(allocator, puzzle)?
+    {
+        let inner_solution =
+            inner_solution::<AugmentedConditionLayer<Program, Puzzle>>(allocator, solution, |s| {
+                Some(s.inner_solution)
+            });
+
+        return Ok(Some(
+            IdentifiedLayer::new("AugmentedCondition")
+                .param("Condition", format!("{:?}", layer.condition))
+                .wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) = BulletinLayer::<Puzzle>::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("Bulletin").wraps(layer.inner_puzzle, solution),
+        ));
+    }
+
+    if let Some(layer) = DelegatedPuzzleFeederLayer::<Puzzle>::parse_puzzle(allocator, puzzle)? {
+        let inner_solution =
+            inner_solution::<DelegatedPuzzleFeederLayer<Puzzle>>(allocator, solution, |s| {
+                Some(s.inner_solution)
+            });
+
+        return Ok(Some(
+            IdentifiedLayer::new("DelegatedPuzzleFeeder").wraps(layer.inner_puzzle, inner_solution),
+        ));
+    }
+
+    if let Some(layer) = IndexWrapperLayer::<usize, Puzzle>::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("IndexWrapper")
+                .param("Nonce", layer.nonce)
+                .wraps(layer.inner_puzzle, solution),
+        ));
+    }
+
+    if let Some(layer) = WriterLayer::<Puzzle>::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("Writer").wraps(layer.inner_puzzle, solution),
+        ));
+    }
+
+    if let Some(layer) = StandardLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(IdentifiedLayer::new("Standard").param(
+            "Synthetic key",
+            hex::encode(layer.synthetic_key.to_bytes()),
+        )));
+    }
+
+    if let Some(layer) = P2DelegatedConditionsLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("P2DelegatedConditions")
+                .param("Public key", hex::encode(layer.public_key.to_bytes())),
+        ));
+    }
+
+    if let Some(layer) = P2CurriedLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("P2Curried").param("Puzzle hash", layer.puzzle_hash),
+        ));
+    }
+
+    if let Some(layer) = P2SingletonLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("P2Singleton").param("Launcher id", layer.launcher_id),
+        ));
+    }
+
+    if let Some(layer) = P2OneOfManyLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(identify_one_of_many(allocator, &layer, solution)?));
+    }
+
+    if let Some(layer) = StreamLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("Stream")
+                .param("Recipient", layer.recipient)
+                .param("Clawback puzzle hash", format_optional(layer.clawback_ph))
+                .param("End time", layer.end_time)
+                .param("Last payment time", layer.last_payment_time),
+        ));
+    }
+
+    if let Some(layer) = RevocationLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("Revocation")
+                .param("Hidden puzzle hash", layer.hidden_puzzle_hash)
+                .param("Inner puzzle hash", layer.inner_puzzle_hash),
+        ));
+    }
+
+    if SettlementLayer::parse_puzzle(allocator, puzzle)?.is_some() {
+        return Ok(Some(IdentifiedLayer::new("Settlement")));
+    }
+
+    if let Some(layer) = SingletonMemberLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("SingletonMember").param("Launcher id", layer.launcher_id),
+        ));
+    }
+
+    if let Some(layer) = DelegationLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("Delegation")
+                .param("Launcher id", layer.launcher_id)
+                .param("Owner puzzle hash", layer.owner_puzzle_hash)
+                .param("Merkle root", layer.merkle_root),
+        ));
+    }
+
+    if let Some(layer) = OracleLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("Oracle")
+                .param("Oracle puzzle hash", layer.oracle_puzzle_hash)
+                .param("Oracle fee", layer.oracle_fee),
+        ));
+    }
+
+    if puzzle.curried_puzzle_hash() == SINGLETON_LAUNCHER_HASH.into() {
+        return Ok(Some(IdentifiedLayer::new("SingletonLauncher")));
+    }
+
+    if let Some(layer) = RoyaltyTransferLayer::parse_puzzle(allocator, puzzle)? {
+        return Ok(Some(
+            IdentifiedLayer::new("RoyaltyTransfer")
+                .param("Launcher id", layer.launcher_id)
+                .param("Royalty puzzle hash", layer.royalty_puzzle_hash)
+                .param("Royalty basis points", layer.royalty_basis_points),
+        ));
+    }
+
+    Ok(None)
+}
+
+/// The puzzle of a 1 of N coin only commits to a merkle root, so the spent path is identified
+/// from the solution instead. Clawbacks are 1 of N coins whose paths are time locked.
+fn identify_one_of_many(
+    allocator: &Allocator,
+    layer: &P2OneOfManyLayer,
+    solution: Option<NodePtr>,
+) -> Result<IdentifiedLayer> {
+    let Some(solution) =
+        solution.and_then(|solution| P2OneOfManyLayer::parse_solution(allocator, solution).ok())
+    else {
+        return Ok(IdentifiedLayer::new("P2OneOfMany").param("Merkle root", layer.merkle_root));
+    };
+
+    let path = identify_layers(allocator, solution.puzzle, Some(solution.solution))?;
+
+    let clawback_path = timelock(allocator, solution.puzzle)
+        .or_else(|| push_through_timelock(allocator, solution.puzzle));
+
+    let mut identified = IdentifiedLayer::new(if clawback_path.is_some() {
+        "Clawback"
+    } else {
+        "P2OneOfMany"
+    })
+    .param("Merkle root", layer.merkle_root)
+    .param("Spent path", format_stack(&path));
+
+    if let Some(timelock) = clawback_path {
+        identified = identified.param("Spent path timelock", timelock);
+    }
+
+    Ok(identified)
+}
+
+/// Returns a description of the time lock enforced by an augmented condition path, if any.
+fn timelock(allocator: &Allocator, puzzle: NodePtr) -> Option<String> {
+    let layer = AugmentedConditionLayer::<Program, Puzzle>::parse_puzzle(
+        allocator,
+        Puzzle::parse(allocator, puzzle),
+    )
+    .ok()??;
+
+    match layer.condition {
+        Condition::AssertSecondsAbsolute(condition) => {
+            Some(format!("after {} (absolute)", condition.seconds))
+        }
+        Condition::AssertSecondsRelative(condition) => {
+            Some(format!("after {} seconds (relative)", condition.seconds))
+        }
+        Condition::AssertBeforeSecondsAbsolute(condition) => {
+            Some(format!("before {} (absolute)", condition.seconds))
+        }
+        Condition::AssertBeforeSecondsRelative(condition) => {
+            Some(format!("before {} seconds (relative)", condition.seconds))
+        }
+        _ => None,
+    }
+}
+
+/// The push through path of a clawback is a quoted list of conditions that pays the receiver.
+fn push_through_timelock(allocator: &Allocator, puzzle: NodePtr) -> Option<String> {
+    let (quote, conditions) = <(u8, Vec<Condition<Program>>)>::from_clvm(allocator, puzzle).ok()?;
+
+    if quote != 1 || !conditions.iter().any(|c| c.as_create_coin().is_some()) {
+        return None;
+    }
+
+    conditions.iter().find_map(|condition| match condition {
+        Condition::AssertSecondsAbsolute(condition) => Some(format!(
+            "after {} (absolute, push through)",
+            condition.seconds
+        )),
+        _ => None,
+    })
+}
+
+fn inner_solution<L>(
+    allocator: &Allocator,
+    solution: Option<NodePtr>,
+    f: impl FnOnce(L::Solution) -> Option<NodePtr>,
+) -> Option<NodePtr>
+where
+    L: Layer,
+{
+    solution
+        .and_then(|solution| L::parse_solution(allocator, solution).ok())
+        .and_then(f)
+}
+
+fn format_stack(layers: &[IdentifiedLayer]) -> String {
+    layers
+        .iter()
+        .map(|layer| layer.name)
+        .collect::<Vec<_>>()
+        .join(" > ")
+}
+
+fn format_optional(value: Option<Bytes32>) -> String {
+    value.map_or_else(|| "None".to_string(), |value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use chia_wallet_sdk::{chia::puzzle_types::Memos, driver::SpendableAsset};
+
+    use super::*;
+
+    fn finish(
+        ctx: &mut SpendContext,
+        spends: Spends,
+        deltas: &Deltas,
+        key: &BlsPairWithCoin,
+    ) -> Result<Outputs> {
+        Ok(spends.finish_with_keys(
+            ctx,
+            deltas,
+            Relation::None,
+            &[(key.puzzle_hash, key.pk)].into_iter().collect(),
+        )?)
+    }
+
+    /// Identifies the layers of the coin spend in the context that spends the coin.
+    fn identify_coin_spend(ctx: &mut SpendContext, coin: Coin) -> Result<Vec<IdentifiedLayer>> {
+        let coin_spends = ctx.take();
+
+        let coin_spend = coin_spends
+            .iter()
+            .find(|coin_spend| coin_spend.coin == coin)
+            .expect("missing coin spend");
+
+        let puzzle = coin_spend.puzzle_reveal.to_clvm(&mut **ctx)?;
+        let solution = coin_spend.solution.to_clvm(&mut **ctx)?;
+
+        identify_layers(ctx, puzzle, Some(solution))
+    }
+
+    fn param<'a>(layer: &'a IdentifiedLayer, label: &str) -> Option<&'a str> {
+        layer
+            .params
+            .iter()
+            .find(|(name, _)| *name == label)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn test_identify_nft() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::mint_empty_nft()])?;
+        let nft = finish(&mut ctx, spends, &deltas, &alice)?.nfts[&Id::New(0)];
+        ctx.take();
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(nft);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send(
+                Id::Existing(nft.info.launcher_id),
+                alice.puzzle_hash,
+                1,
+                Memos::None,
+            )],
+        )?;
+        finish(&mut ctx, spends, &deltas, &alice)?;
+
+        let layers = identify_coin_spend(&mut ctx, nft.coin)?;
+
+        assert_eq!(
+            format_stack(&layers),
+            "Singleton > NFTState > NFTOwnership > Standard"
+        );
+        assert_eq!(
+            param(&layers[0], "Launcher id"),
+            Some(nft.info.launcher_id.to_string().as_str())
+        );
+        assert_eq!(param(&layers[2], "Current owner"), Some("None"));
+        assert_eq!(
+            param(&layers[3], "Synthetic key"),
+            Some(hex::encode(alice.pk.to_bytes()).as_str())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_identify_cat() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::single_issue_cat(None, 1)])?;
+        let cat = finish(&mut ctx, spends, &deltas, &alice)?.cats[&Id::New(0)][0];
+        ctx.take();
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(cat);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send(
+                Id::Existing(cat.info.asset_id),
+                alice.puzzle_hash,
+                1,
+                Memos::None,
+            )],
+        )?;
+        finish(&mut ctx, spends, &deltas, &alice)?;
+
+        let layers = identify_coin_spend(&mut ctx, cat.coin)?;
+
+        assert_eq!(format_stack(&layers), "CAT > Standard");
+        assert_eq!(
+            param(&layers[0], "Asset id"),
+            Some(cat.info.asset_id.to_string().as_str())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_identify_clawback() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send_with_clawback(Id::Xch, bob.puzzle_hash, 100, 1)],
+        )?;
+        let outputs = finish(&mut ctx, spends, &deltas, &alice)?;
+        ctx.take();
+
+        let clawback = outputs.clawbacks[0];
+        let coin = outputs.xch[0];
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::claw_back(clawback, SpendableAsset::Xch(coin))],
+        )?;
+        finish(&mut ctx, spends, &deltas, &alice)?;
+
+        let layers = identify_coin_spend(&mut ctx, coin)?;
+
+        assert_eq!(format_stack(&layers), "Clawback");
+        assert_eq!(
+            param(&layers[0], "Spent path"),
+            Some("AugmentedCondition > Standard")
+        );
+        assert_eq!(
+            param(&layers[0], "Spent path timelock"),
+            Some("before 100 (absolute)")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_identify_unknown() -> Result<()> {
+        let mut allocator = Allocator::new();
+
+        let puzzle = Program::from(vec![0xff, 0x01, 0xff, 0x02, 0x80]).to_clvm(&mut allocator)?;
+        let mod_hash = tree_hash(&allocator, puzzle);
+
+        let layers = identify_layers(&allocator, puzzle, None)?;
+
+        assert_eq!(format_stack(&layers), "Unknown");
+        assert_eq!(
+            param(&layers[0], "Mod hash"),
+            Some(mod_hash.to_string().as_str())
+        );
+
+        Ok(())
+    }
+}
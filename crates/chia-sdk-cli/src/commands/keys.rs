@@ -0,0 +1,296 @@
+use anyhow::{Result, bail};
+use bip39::Mnemonic;
+use chia_wallet_sdk::{
+    chia::{
+        bls::{master_to_wallet_hardened, master_to_wallet_unhardened},
+        puzzle_types::{DeriveSynthetic, standard::StandardArgs},
+    },
+    prelude::*,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::{
+    args::{DeriveKeysCommand, GenerateKeyCommand, KeyAddressCommand, KeyInput, ShowKeyCommand},
+    input::read_mnemonic,
+};
+
+/// The master key a command operates on. Hardened derivation is only possible with the secret key.
+enum MasterKey {
+    Secret(SecretKey),
+    Public(PublicKey),
+}
+
+impl MasterKey {
+    fn public_key(&self) -> PublicKey {
+        match self {
+            Self::Secret(sk) => sk.public_key(),
+            Self::Public(pk) => *pk,
+        }
+    }
+
+    fn secret_key(&self) -> Option<&SecretKey> {
+        match self {
+            Self::Secret(sk) => Some(sk),
+            Self::Public(_) => None,
+        }
+    }
+}
+
+pub fn generate_key(args: &GenerateKeyCommand) -> Result<()> {
+    let mut rng = ChaCha20Rng::from_os_rng();
+
+    let mnemonic = match args.words {
+        12 => {
+            let entropy: [u8; 16] = rng.random();
+            Mnemonic::from_entropy(&entropy)?
+        }
+        24 => {
+            let entropy: [u8; 32] = rng.random();
+            Mnemonic::from_entropy(&entropy)?
+        }
+        words => bail!("expected 12 or 24 words, got {words}"),
+    };
+
+    println!("mnemonic = {mnemonic}");
+
+    let master_sk = SecretKey::from_seed(&mnemonic.to_seed(""));
+
+    print_key(&MasterKey::Secret(master_sk), &args.prefix)
+}
+
+pub fn show_key(args: &ShowKeyCommand) -> Result<()> {
+    print_key(&read_master_key(&args.key)?, &args.prefix)
+}
+
+pub fn derive_keys(args: &DeriveKeysCommand) -> Result<()> {
+    let master = read_master_key(&args.key)?;
+
+    for index in args.index_range.clone() {
+        let pk = wallet_public_key(&master, index, false)?;
+        let puzzle_hash = standard_puzzle_hash(pk);
+
+        println!("index {index}");
+        println!("  observer public key = {}", hex::encode(pk.to_bytes()));
+        println!("  observer puzzle hash = {puzzle_hash}");
+        println!(
+            "  observer address = {}",
+            Address::new(puzzle_hash, args.prefix.clone()).encode()?
+        );
+
+        if master.secret_key().is_some() {
+            let pk = wallet_public_key(&master, index, true)?;
+            let puzzle_hash = standard_puzzle_hash(pk);
+
+            println!("  hardened public key = {}", hex::encode(pk.to_bytes()));
+            println!("  hardened puzzle hash = {puzzle_hash}");
+            println!(
+                "  hardened address = {}",
+                Address::new(puzzle_hash, args.prefix.clone()).encode()?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn key_address(args: &KeyAddressCommand) -> Result<()> {
+    let master = read_master_key(&args.key)?;
+
+    let pk = wallet_public_key(&master, args.index, args.hardened)?;
+    let address = Address::new(standard_puzzle_hash(pk), args.prefix.clone()).encode()?;
+
+    println!("{address}");
+
+    Ok(())
+}
+
+/// Derives the wallet public key at an index, using the `m/12381/8444/2/index` path.
+fn wallet_public_key(master: &MasterKey, index: u32, hardened: bool) -> Result<PublicKey> {
+    if !hardened {
+        return Ok(master_to_wallet_unhardened(&master.public_key(), index));
+    }
+
+    let Some(master_sk) = master.secret_key() else {
+        bail!("hardened derivation requires the mnemonic");
+    };
+
+    Ok(master_to_wallet_hardened(master_sk, index).public_key())
+}
+
+fn read_master_key(input: &KeyInput) -> Result<MasterKey> {
+    if let Some(public_key) = &input.public_key {
+        let bytes: [u8; 48] = parse_hex(public_key)?
+            .to_vec()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("expected a 48 byte public key"))?;
+        return Ok(MasterKey::Public(PublicKey::from_bytes(&bytes)?));
+    }
+
+    let mnemonic = read_mnemonic(input.mnemonic_file.as_deref())?;
+
+    Ok(MasterKey::Secret(SecretKey::from_seed(
+        &mnemonic.to_seed(""),
+    )))
+}
+
+fn print_key(master: &MasterKey, prefix: &str) -> Result<()> {
+    let master_pk = master.public_key();
+
+    println!("fingerprint = {}", master_pk.get_fingerprint());
+    println!("master public key = {}", hex::encode(master_pk.to_bytes()));
+
+    if let Some(master_sk) = master.secret_key() {
+        let farmer_pk = derive_hardened_path(master_sk, &[12381, 8444, 0, 0]).public_key();
+        let pool_pk = derive_hardened_path(master_sk, &[12381, 8444, 1, 0]).public_key();
+
+        println!("farmer public key = {}", hex::encode(farmer_pk.to_bytes()));
+        println!("pool public key = {}", hex::encode(pool_pk.to_bytes()));
+    }
+
+    let wallet_pk = master_to_wallet_unhardened(&master_pk, 0);
+    let address = Address::new(standard_puzzle_hash(wallet_pk), prefix.to_string()).encode()?;
+
+    println!("wallet public key = {}", hex::encode(wallet_pk.to_bytes()));
+    println!("first address = {address}");
+
+    Ok(())
+}
+
+fn derive_hardened_path(sk: &SecretKey, path: &[u32]) -> SecretKey {
+    path.iter()
+        .fold(sk.clone(), |sk, &index| sk.derive_hardened(index))
+}
+
+fn standard_puzzle_hash(pk: PublicKey) -> Bytes32 {
+    StandardArgs::curry_tree_hash(pk.derive_synthetic()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use chia_wallet_sdk::chia::bls::DerivableKey;
+    use hex_literal::hex;
+
+    use super::*;
+
+    // The seed and master secret key test vector from `chia.util.keychain`.
+    const SEED: [u8; 64] = hex!(
+        "fc795be0c3f18c50dddb34e72179dc597d64055497ecc1e69e2e56a5409651bc139aae8070d4df0ea14d8d2a518a9a00bb1cc6e92e053fe34051f6821df9164c"
+    );
+    const MASTER_SK: [u8; 32] =
+        hex!("52d75c4707e39595b27314547f9723e5530c01198af3fc5849d9a7af65631efb");
+
+    fn master_sk() -> SecretKey {
+        let master_sk = SecretKey::from_seed(&SEED);
+        assert_eq!(master_sk.to_bytes(), MASTER_SK);
+        master_sk
+    }
+
+    fn wallet_address(master: &MasterKey, index: u32, hardened: bool) -> Result<String> {
+        let pk = wallet_public_key(master, index, hardened)?;
+        Ok(Address::new(standard_puzzle_hash(pk), "xch".to_string()).encode()?)
+    }
+
+    #[test]
+    fn test_wallet_key_paths() -> Result<()> {
+        let master_sk = master_sk();
+        let master = MasterKey::Secret(master_sk.clone());
+
+        for index in [0, 1, 100, u32::MAX] {
+            let unhardened = [12381, 8444, 2, index]
+                .iter()
+                .fold(master_sk.clone(), |sk, &index| sk.derive_unhardened(index));
+
+            let hardened = derive_hardened_path(&master_sk, &[12381, 8444, 2, index]);
+
+            assert_eq!(
+                wallet_public_key(&master, index, false)?,
+                unhardened.public_key()
+            );
+            assert_eq!(
+                wallet_public_key(&master, index, true)?,
+                hardened.public_key()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_observer_keys_match_mnemonic() -> Result<()> {
+        let master_sk = master_sk();
+        let secret = MasterKey::Secret(master_sk.clone());
+        let public = MasterKey::Public(master_sk.public_key());
+
+        for index in 0..10 {
+            assert_eq!(
+                wallet_public_key(&public, index, false)?,
+                wallet_public_key(&secret, index, false)?
+            );
+        }
+
+        assert!(wallet_public_key(&public, 0, true).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wallet_key_vectors() -> Result<()> {
+        let master = MasterKey::Secret(master_sk());
+
+        // Pinned keys and addresses for the master key above, so that changes to the derivation
+        // path, synthetic key or address encoding are caught.
+        // (index, unhardened public key, unhardened address, hardened public key, hardened address)
+        let test_cases = [
+            (
+                0,
+                hex!(
+                    "a9fc17690c6f80ef09f2ef1978318bef528ef772de956ad2f66150e3a9bc9499fe2b538fc326c66256d1e6eb9c519c2c"
+                ),
+                "xch1kdlnhxvp2u5e5hfr7eh9hczhwkhj99x4gvu732t5kt6tt2uf5czq82qf32",
+                hex!(
+                    "b9581b86b7adfaa54c883e6f093653d136987b453862c5d6be8c8bc0fa3990b1e7e7c757e91550cb2821d5fd62c17140"
+                ),
+                "xch1aa6mncmsng3quhdulz8gyg68lcasmtwk0277hhp0zce3x3r3l0kqew5w5w",
+            ),
+            (
+                1,
+                hex!(
+                    "8c35ce9328706692e79a0430b78af32fe75bb78a6452db13cc6a7ea9e328ecc5c649b0ab80b6c902ec75d3689f708edb"
+                ),
+                "xch1uquakyp2uwfvvcc78xp5pn9cmlg8jyhhe3wh6evry2v5ukjv79wq9aafs4",
+                hex!(
+                    "b3ff12cbeab1fef17b16f18b189fb09e7de6d5dbda46cdfa86c5806a2115fb04ff471a020439a120817ef459ed9d2194"
+                ),
+                "xch1avu7347kcqgga4ma3lea2grk65rgapdcxfjwffywwf25cjgwy9xsgv7z27",
+            ),
+            (
+                100,
+                hex!(
+                    "a36fabe5ee0a2182c5bab9cd65de464a37c841457cca6284f2848a4db274d9f18d37d7408c0b77b506e3713f9723986f"
+                ),
+                "xch1n25f9skxtypqpa839fpsezfvc2pxjtsca357kp0ve7qhswfrwd3qagd6d7",
+                hex!(
+                    "860c945486e95fe944aea1a59fffdf04620a2b76b6660db3c1ddda3482a05e2278a8afe29f8fa3e1c2a2ec932cf84060"
+                ),
+                "xch17tdcfvynf0vq3l0wuxvut0rmv05pwv27ewa9h846w00m76ezu9uqmy5rsf",
+            ),
+        ];
+
+        for (index, unhardened_pk, unhardened_address, hardened_pk, hardened_address) in test_cases
+        {
+            assert_eq!(
+                wallet_public_key(&master, index, false)?.to_bytes(),
+                unhardened_pk
+            );
+            assert_eq!(wallet_address(&master, index, false)?, unhardened_address);
+            assert_eq!(
+                wallet_public_key(&master, index, true)?.to_bytes(),
+                hardened_pk
+            );
+            assert_eq!(wallet_address(&master, index, true)?, hardened_address);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,480 @@
+use anyhow::Result;
+use chia_wallet_sdk::{
+    chia::{protocol::Bytes32, puzzle_types::offer::NotarizedPayment, traits::Streamable},
+    driver::{decode_offer as decode_offer_text, encode_offer as encode_offer_text},
+    prelude::*,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    args::{DecodeOfferCommand, EncodeOfferCommand, NormalizeOfferCommand, OfferSummaryCommand},
+    input::{parse_spend_bundle, read_input, read_offer},
+};
+
+pub fn decode_offer(args: &DecodeOfferCommand) -> Result<()> {
+    let spend_bundle = decode_offer_text(&read_offer(args.input.as_deref())?)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&spend_bundle)?);
+    } else {
+        println!("{}", hex::encode(spend_bundle.to_bytes()?));
+    }
+
+    Ok(())
+}
+
+pub fn encode_offer(args: &EncodeOfferCommand) -> Result<()> {
+    let spend_bundle = parse_spend_bundle(&read_input(args.input.as_deref())?)?;
+
+    println!("{}", encode_offer_text(&spend_bundle)?);
+
+    Ok(())
+}
+
+pub fn normalize_offer(args: &NormalizeOfferCommand) -> Result<()> {
+    let spend_bundle = decode_offer_text(&read_offer(args.input.as_deref())?)?;
+
+    println!("{}", encode_offer_text(&spend_bundle)?);
+
+    Ok(())
+}
+
+pub fn offer_summary(args: &OfferSummaryCommand) -> Result<()> {
+    let spend_bundle = decode_offer_text(&read_offer(args.input.as_deref())?)?;
+
+    let mut allocator = Allocator::new();
+    let offer = Offer::from_spend_bundle(&mut allocator, &spend_bundle)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summary_json(&offer))?);
+    } else {
+        print_summary(&offer);
+    }
+
+    Ok(())
+}
+
+fn print_summary(offer: &Offer) {
+    let offered = offer.offered_coins();
+    let requested = offer.requested_payments();
+
+    println!("Offered");
+
+    if !offered.xch.is_empty() {
+        println!("  XCH: {}", offered.amounts().xch);
+
+        for coin in &offered.xch {
+            println!("    {} {}", coin.coin_id(), coin.amount);
+        }
+    }
+
+    for (asset_id, cats) in &offered.cats {
+        println!(
+            "  CAT {asset_id}: {}",
+            cats.iter().map(|cat| cat.coin.amount).sum::<u64>()
+        );
+
+        for cat in cats {
+            println!("    {} {}", cat.coin.coin_id(), cat.coin.amount);
+        }
+    }
+
+    for (launcher_id, nft) in &offered.nfts {
+        println!("  NFT {launcher_id} (coin {})", nft.coin.coin_id());
+    }
+
+    for (launcher_id, option) in &offered.options {
+        println!("  Option {launcher_id} (coin {})", option.coin.coin_id());
+    }
+
+    println!("  Fee: {}", offered.fee);
+
+    println!();
+    println!("Requested");
+
+    if !requested.xch.is_empty() {
+        println!("  XCH: {}", requested.amounts().xch);
+        print_notarized_payments(&requested.xch);
+    }
+
+    for (asset_id, notarized_payments) in &requested.cats {
+        println!(
+            "  CAT {asset_id}: {}",
+            requested.amounts().cats.get(asset_id).unwrap_or(&0)
+        );
+        print_notarized_payments(notarized_payments);
+    }
+
+    for (launcher_id, notarized_payments) in &requested.nfts {
+        println!("  NFT {launcher_id}");
+        print_notarized_payments(notarized_payments);
+    }
+
+    for (launcher_id, notarized_payments) in &requested.options {
+        println!("  Option {launcher_id}");
+        print_notarized_payments(notarized_payments);
+    }
+
+    println!();
+    println!("Offered royalties");
+    print_royalties(&offer.offered_royalties(), &offer.offered_royalty_amounts());
+
+    println!();
+    println!("Requested royalties");
+    print_royalties(
+        &offer.requested_royalties(),
+        &offer.requested_royalty_amounts(),
+    );
+
+    let arbitrage = offer.arbitrage();
+
+    println!();
+    println!("Arbitrage");
+    println!("  Offered side");
+    print_arbitrage_side(&arbitrage.offered);
+    println!("  Requested side");
+    print_arbitrage_side(&arbitrage.requested);
+
+    let asset_info = offer.asset_info();
+
+    println!();
+    println!("Asset info");
+
+    for asset_id in sorted(asset_info.cats()) {
+        let Some(info) = asset_info.cat(asset_id) else {
+            continue;
+        };
+
+        match info.hidden_puzzle_hash {
+            Some(hidden_puzzle_hash) => {
+                println!("  CAT {asset_id}: hidden puzzle hash {hidden_puzzle_hash}");
+            }
+            None => println!("  CAT {asset_id}: no hidden puzzle"),
+        }
+    }
+
+    for launcher_id in sorted(asset_info.nfts()) {
+        let Some(info) = asset_info.nft(launcher_id) else {
+            continue;
+        };
+
+        println!("  NFT {launcher_id}");
+        println!("    Metadata hash: {}", info.metadata.tree_hash());
+        println!(
+            "    Metadata updater puzzle hash: {}",
+            info.metadata_updater_puzzle_hash
+        );
+        println!("    Royalty puzzle hash: {}", info.royalty_puzzle_hash);
+        println!("    Royalty basis points: {}", info.royalty_basis_points);
+    }
+
+    for launcher_id in sorted(asset_info.options()) {
+        let Some(info) = asset_info.option(launcher_id) else {
+            continue;
+        };
+
+        println!("  Option {launcher_id}");
+        println!("    Underlying coin id: {}", info.underlying_coin_id);
+        println!(
+            "    Underlying delegated puzzle hash: {}",
+            info.underlying_delegated_puzzle_hash
+        );
+    }
+}
+
+fn print_notarized_payments(notarized_payments: &[NotarizedPayment]) {
+    for notarized_payment in notarized_payments {
+        for payment in &notarized_payment.payments {
+            println!(
+                "    {} {} (nonce {})",
+                payment.puzzle_hash, payment.amount, notarized_payment.nonce
+            );
+        }
+    }
+}
+
+fn print_royalties(royalties: &[RoyaltyInfo], amounts: &OfferAmounts) {
+    if royalties.is_empty() {
+        println!("  None");
+        return;
+    }
+
+    for royalty in royalties {
+        println!(
+            "  NFT {} pays {} basis points to {}",
+            royalty.launcher_id, royalty.basis_points, royalty.puzzle_hash
+        );
+    }
+
+    if amounts.xch > 0 {
+        println!("  XCH: {}", amounts.xch);
+    }
+
+    for (asset_id, amount) in &amounts.cats {
+        println!("  CAT {asset_id}: {amount}");
+    }
+}
+
+fn print_arbitrage_side(side: &ArbitrageSide) {
+    println!("    XCH: {}", side.xch);
+
+    for (asset_id, amount) in &side.cats {
+        println!("    CAT {asset_id}: {amount}");
+    }
+
+    for launcher_id in &side.nfts {
+        println!("    NFT {launcher_id}");
+    }
+
+    for launcher_id in &side.options {
+        println!("    Option {launcher_id}");
+    }
+}
+
+fn summary_json(offer: &Offer) -> Value {
+    let offered = offer.offered_coins();
+    let requested = offer.requested_payments();
+    let asset_info = offer.asset_info();
+
+    let offered_json = json!({
+        "xch": offered.xch.iter().map(|coin| json!({
+            "coin_id": coin.coin_id().to_string(),
+            "amount": coin.amount,
+        })).collect::<Vec<_>>(),
+        "cats": offered.cats.iter().map(|(asset_id, cats)| json!({
+            "asset_id": asset_id.to_string(),
+            "coins": cats.iter().map(|cat| json!({
+                "coin_id": cat.coin.coin_id().to_string(),
+                "amount": cat.coin.amount,
+            })).collect::<Vec<_>>(),
+        })).collect::<Vec<_>>(),
+        "nfts": offered.nfts.iter().map(|(launcher_id, nft)| json!({
+            "launcher_id": launcher_id.to_string(),
+            "coin_id": nft.coin.coin_id().to_string(),
+        })).collect::<Vec<_>>(),
+        "options": offered.options.iter().map(|(launcher_id, option)| json!({
+            "launcher_id": launcher_id.to_string(),
+            "coin_id": option.coin.coin_id().to_string(),
+        })).collect::<Vec<_>>(),
+        "fee": offered.fee,
+    });
+
+    let requested_json = json!({
+        "xch": notarized_payments_json(&requested.xch),
+        "cats": requested.cats.iter().map(|(asset_id, notarized_payments)| json!({
+            "asset_id": asset_id.to_string(),
+            "payments": notarized_payments_json(notarized_payments),
+        })).collect::<Vec<_>>(),
+        "nfts": requested.nfts.iter().map(|(launcher_id, notarized_payments)| json!({
+            "launcher_id": launcher_id.to_string(),
+            "payments": notarized_payments_json(notarized_payments),
+        })).collect::<Vec<_>>(),
+        "options": requested.options.iter().map(|(launcher_id, notarized_payments)| json!({
+            "launcher_id": launcher_id.to_string(),
+            "payments": notarized_payments_json(notarized_payments),
+        })).collect::<Vec<_>>(),
+    });
+
+    let arbitrage = offer.arbitrage();
+
+    let asset_info_json = json!({
+        "cats": sorted(asset_info.cats()).into_iter().filter_map(|asset_id| {
+            asset_info.cat(asset_id).map(|info| json!({
+                "asset_id": asset_id.to_string(),
+                "hidden_puzzle_hash": info.hidden_puzzle_hash.map(|hash| hash.to_string()),
+            }))
+        }).collect::<Vec<_>>(),
+        "nfts": sorted(asset_info.nfts()).into_iter().filter_map(|launcher_id| {
+            asset_info.nft(launcher_id).map(|info| json!({
+                "launcher_id": launcher_id.to_string(),
+                "metadata_hash": info.metadata.tree_hash().to_string(),
+                "metadata_updater_puzzle_hash": info.metadata_updater_puzzle_hash.to_string(),
+                "royalty_puzzle_hash": info.royalty_puzzle_hash.to_string(),
+                "royalty_basis_points": info.royalty_basis_points,
+            }))
+        }).collect::<Vec<_>>(),
+        "options": sorted(asset_info.options()).into_iter().filter_map(|launcher_id| {
+            asset_info.option(launcher_id).map(|info| json!({
+                "launcher_id": launcher_id.to_string(),
+                "underlying_coin_id": info.underlying_coin_id.to_string(),
+                "underlying_delegated_puzzle_hash":
+                    info.underlying_delegated_puzzle_hash.to_string(),
+            }))
+        }).collect::<Vec<_>>(),
+    });
+
+    json!({
+        "offered": offered_json,
+        "requested": requested_json,
+        "offered_royalties": royalties_json(
+            &offer.offered_royalties(),
+            &offer.offered_royalty_amounts(),
+        ),
+        "requested_royalties": royalties_json(
+            &offer.requested_royalties(),
+            &offer.requested_royalty_amounts(),
+        ),
+        "arbitrage": {
+            "offered": arbitrage_side_json(&arbitrage.offered),
+            "requested": arbitrage_side_json(&arbitrage.requested),
+        },
+        "asset_info": asset_info_json,
+    })
+}
+
+fn notarized_payments_json(notarized_payments: &[NotarizedPayment]) -> Value {
+    notarized_payments
+        .iter()
+        .flat_map(|notarized_payment| {
+            notarized_payment.payments.iter().map(|payment| {
+                json!({
+                    "nonce": notarized_payment.nonce.to_string(),
+                    "puzzle_hash": payment.puzzle_hash.to_string(),
+                    "amount": payment.amount,
+                })
+            })
+        })
+        .collect()
+}
+
+fn royalties_json(royalties: &[RoyaltyInfo], amounts: &OfferAmounts) -> Value {
+    json!({
+        "royalties": royalties.iter().map(|royalty| json!({
+            "launcher_id": royalty.launcher_id.to_string(),
+            "puzzle_hash": royalty.puzzle_hash.to_string(),
+            "basis_points": royalty.basis_points,
+        })).collect::<Vec<_>>(),
+        "amounts": amounts_json(amounts),
+    })
+}
+
+fn amounts_json(amounts: &OfferAmounts) -> Value {
+    json!({
+        "xch": amounts.xch,
+        "cats": amounts.cats.iter().map(|(asset_id, amount)| json!({
+            "asset_id": asset_id.to_string(),
+            "amount": amount,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+fn arbitrage_side_json(side: &ArbitrageSide) -> Value {
+    let mut value = amounts_json(&side.amounts());
+    value["nfts"] = side.nfts.iter().map(ToString::to_string).collect();
+    value["options"] = side.options.iter().map(ToString::to_string).collect();
+    value
+}
+
+fn sorted<'a>(ids: impl Iterator<Item = &'a Bytes32>) -> Vec<Bytes32> {
+    let mut ids: Vec<Bytes32> = ids.copied().collect();
+    ids.sort();
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use chia_wallet_sdk::{
+        chia::puzzle_types::Memos,
+        driver::{OfferExpiration, RequestedAsset, make_offer},
+        puzzles::SETTLEMENT_PAYMENT_HASH,
+    };
+
+    use super::*;
+
+    /// Makes an offer for 1000 mojos of XCH with a fee of 10, requesting 500 of a CAT. The offer is
+    /// returned along with the maker's puzzle hash.
+    fn test_offer(asset_id: Bytes32) -> Result<(String, Bytes32)> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1010);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![
+                Action::send(Id::Xch, SETTLEMENT_PAYMENT_HASH.into(), 1000, Memos::None),
+                Action::fee(10),
+            ],
+            &[RequestedAsset::cat(asset_id, None, 500)],
+            OfferExpiration::default(),
+            |ctx, spends, deltas| {
+                spends.finish_with_keys(
+                    ctx,
+                    deltas,
+                    Relation::AssertConcurrent,
+                    &[(alice.puzzle_hash, alice.pk)].into_iter().collect(),
+                )?;
+
+                Ok::<_, anyhow::Error>(SpendBundle::new(ctx.take(), Signature::default()))
+            },
+        )?;
+
+        Ok((
+            encode_offer_text(&offer.to_spend_bundle(&mut ctx)?)?,
+            alice.puzzle_hash,
+        ))
+    }
+
+    #[test]
+    fn test_offer_summary() -> Result<()> {
+        let asset_id = Bytes32::new([1; 32]);
+        let (text, maker_puzzle_hash) = test_offer(asset_id)?;
+
+        let spend_bundle = decode_offer_text(&read_offer(Some(&text))?)?;
+        let mut allocator = Allocator::new();
+        let offer = Offer::from_spend_bundle(&mut allocator, &spend_bundle)?;
+
+        let summary = summary_json(&offer);
+
+        let offered = &summary["offered"];
+        assert_eq!(offered["xch"].as_array().map(Vec::len), Some(1));
+        assert_eq!(offered["xch"][0]["amount"], 1000);
+        assert_eq!(offered["cats"], json!([]));
+        assert_eq!(offered["nfts"], json!([]));
+        assert_eq!(offered["fee"], 10);
+
+        let requested = &summary["requested"];
+        assert_eq!(requested["xch"], json!([]));
+        assert_eq!(requested["cats"][0]["asset_id"], asset_id.to_string());
+        assert_eq!(
+            requested["cats"][0]["payments"][0]["puzzle_hash"],
+            maker_puzzle_hash.to_string()
+        );
+        assert_eq!(requested["cats"][0]["payments"][0]["amount"], 500);
+
+        assert_eq!(summary["offered_royalties"]["royalties"], json!([]));
+        assert_eq!(summary["arbitrage"]["requested"]["xch"], 1000);
+        assert_eq!(summary["arbitrage"]["offered"]["cats"][0]["amount"], 500);
+        assert_eq!(
+            summary["asset_info"]["cats"][0]["hidden_puzzle_hash"],
+            Value::Null
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_invalid_offer() -> Result<()> {
+        let (text, _) = test_offer(Bytes32::default())?;
+        let spend_bundle = decode_offer_text(&text)?;
+
+        // The spend bundle must be compressed, even if it's encoded with the offer prefix
+        let uncompressed = Bech32::new(spend_bundle.to_bytes()?.into(), "offer".to_string());
+        assert!(decode_offer_text(&uncompressed.encode()?).is_err());
+        assert!(decode_offer_text(&hex::encode(spend_bundle.to_bytes()?)).is_err());
+
+        // A single changed character fails the checksum
+        let mut corrupted = text.clone().into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+        assert!(decode_offer_text(&String::from_utf8(corrupted)?).is_err());
+
+        assert!(decode_offer_text("offer1").is_err());
+
+        Ok(())
+    }
+}
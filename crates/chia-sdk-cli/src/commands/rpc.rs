@@ -0,0 +1,413 @@
+use std::fs;
+
+use anyhow::{Context, Result, bail};
+use chia_wallet_sdk::{
+    coinset::{ChiaRpcClient, CoinRecord, CoinsetClient, FullNodeClient},
+    prelude::*,
+};
+use serde::Serialize;
+
+use crate::{
+    args::{NetworkName, RpcCommand, RpcRequest},
+    input::{parse_puzzle_hash, parse_spend_bundle, read_input},
+};
+
+pub fn rpc(args: &RpcCommand) -> Result<()> {
+    tokio::runtime::Runtime::new()?.block_on(async {
+        if let Some(url) = &args.full_node {
+            let (Some(cert_path), Some(key_path)) = (&args.cert, &args.key) else {
+                bail!("a full node requires both --cert and --key");
+            };
+
+            let cert = fs::read(cert_path)
+                .with_context(|| format!("could not read {}", cert_path.display()))?;
+            let key = fs::read(key_path)
+                .with_context(|| format!("could not read {}", key_path.display()))?;
+
+            let client =
+                FullNodeClient::with_base_url(url.trim_end_matches('/').to_string(), &cert, &key)?;
+
+            send_request(&client, args).await
+        } else if let Some(url) = &args.coinset_url {
+            let client = CoinsetClient::new(url.trim_end_matches('/').to_string());
+            send_request(&client, args).await
+        } else {
+            let client = match args.network {
+                NetworkName::Mainnet => CoinsetClient::mainnet(),
+                NetworkName::Testnet11 => CoinsetClient::testnet11(),
+            };
+            send_request(&client, args).await
+        }
+    })
+}
+
+async fn send_request<C>(client: &C, args: &RpcCommand) -> Result<()>
+where
+    C: ChiaRpcClient,
+    C::Error: std::error::Error + Send + Sync + 'static,
+{
+    match &args.request {
+        RpcRequest::CoinRecords(request) => {
+            let response = client
+                .get_coin_records_by_puzzle_hash(
+                    parse_puzzle_hash(&request.puzzle_hash)?,
+                    request.start_height,
+                    request.end_height,
+                    Some(request.include_spent),
+                    request.cursor.clone(),
+                )
+                .await?;
+
+            if print_json(args.json, &response)? {
+                return Ok(());
+            }
+
+            check_response(response.success, response.error.as_deref())?;
+
+            let coin_records = response.coin_records.unwrap_or_default();
+
+            for coin_record in &coin_records {
+                print_coin_record(coin_record);
+                println!();
+            }
+
+            let total: u128 = coin_records
+                .iter()
+                .map(|record| u128::from(record.coin.amount))
+                .sum();
+
+            println!("{} coin record(s) totaling {total}", coin_records.len());
+
+            if let Some(next_cursor) = response.next_cursor {
+                println!("The response was truncated, continue with --cursor {next_cursor}");
+            }
+        }
+        RpcRequest::CoinRecord(request) => {
+            let response = client
+                .get_coin_record_by_name(parse_coin_id(&request.coin_id)?)
+                .await?;
+
+            if print_json(args.json, &response)? {
+                return Ok(());
+            }
+
+            check_response(response.success, response.error.as_deref())?;
+
+            let Some(coin_record) = response.coin_record else {
+                bail!("the coin record was not found");
+            };
+
+            print_coin_record(&coin_record);
+        }
+        RpcRequest::PuzzleAndSolution(request) => {
+            let response = client
+                .get_puzzle_and_solution(parse_coin_id(&request.coin_id)?, request.height)
+                .await?;
+
+            if print_json(args.json, &response)? {
+                return Ok(());
+            }
+
+            check_response(response.success, response.error.as_deref())?;
+
+            let Some(coin_spend) = response.coin_solution else {
+                bail!("the coin spend was not found");
+            };
+
+            println!("Coin id: {}", coin_spend.coin.coin_id());
+            println!("Puzzle reveal: {}", hex::encode(&coin_spend.puzzle_reveal));
+            println!("Solution: {}", hex::encode(&coin_spend.solution));
+        }
+        RpcRequest::BlockchainState => {
+            let response = client.get_blockchain_state().await?;
+
+            if print_json(args.json, &response)? {
+                return Ok(());
+            }
+
+            check_response(response.success, response.error.as_deref())?;
+
+            let Some(state) = response.blockchain_state else {
+                bail!("the blockchain state is missing from the response");
+            };
+
+            println!("Peak height: {}", state.peak.height);
+            println!("Peak header hash: {}", state.peak.header_hash);
+            println!(
+                "Sync: {}",
+                if state.sync.synced {
+                    "synced".to_string()
+                } else if state.sync.sync_mode {
+                    format!(
+                        "syncing {}/{}",
+                        state.sync.sync_progress_height, state.sync.sync_tip_height
+                    )
+                } else {
+                    "not synced".to_string()
+                }
+            );
+            println!("Difficulty: {}", state.difficulty);
+            println!("Estimated space: {} bytes", state.space);
+            println!("Average block time: {}s", state.average_block_time);
+            println!("Mempool size: {}", state.mempool_size);
+            println!(
+                "Mempool cost: {} / {}",
+                state.mempool_cost, state.mempool_max_total_cost
+            );
+            println!("Mempool fees: {}", state.mempool_fees);
+        }
+        RpcRequest::PushTx(request) => {
+            let spend_bundle = parse_spend_bundle(&read_input(request.input.as_deref())?)?;
+            let transaction_id = spend_bundle.name();
+
+            let response = client.push_tx(spend_bundle).await?;
+
+            if print_json(args.json, &response)? {
+                return Ok(());
+            }
+
+            check_response(response.success, response.error.as_deref())?;
+
+            println!("Transaction id: {transaction_id}");
+            println!(
+                "Status: {}",
+                response.status.as_deref().unwrap_or("unknown")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the response as JSON if requested, and returns whether it did.
+fn print_json(json: bool, response: &impl Serialize) -> Result<bool> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(response)?);
+    }
+    Ok(json)
+}
+
+fn check_response(success: bool, error: Option<&str>) -> Result<()> {
+    if !success {
+        bail!("{}", error.unwrap_or("the request failed"));
+    }
+    Ok(())
+}
+
+fn parse_coin_id(input: &str) -> Result<Bytes32> {
+    Bytes32::try_from(parse_hex(input)?).context("expected a 32 byte coin id")
+}
+
+fn print_coin_record(coin_record: &CoinRecord) {
+    let coin = coin_record.coin;
+
+    println!("Coin id: {}", coin.coin_id());
+    println!("  Parent coin id: {}", coin.parent_coin_info);
+    println!("  Puzzle hash: {}", coin.puzzle_hash);
+    println!("  Amount: {}", coin.amount);
+    println!(
+        "  Confirmed at height: {}",
+        coin_record.confirmed_block_index
+    );
+
+    if coin_record.spent {
+        println!("  Spent at height: {}", coin_record.spent_block_index);
+    } else {
+        println!("  Unspent");
+    }
+
+    println!("  Timestamp: {}", coin_record.timestamp);
+
+    if coin_record.coinbase {
+        println!("  Coinbase: yes");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use clap::Parser;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+
+    use crate::args::Command;
+
+    use super::*;
+
+    /// Records the requests that are made, and responds to each endpoint with a fixed response.
+    struct MockClient {
+        requests: Mutex<Vec<(String, Value)>>,
+        responses: HashMap<&'static str, Value>,
+    }
+
+    impl MockClient {
+        fn new(responses: HashMap<&'static str, Value>) -> Self {
+            Self {
+                requests: Mutex::new(Vec::new()),
+                responses,
+            }
+        }
+
+        fn requests(&self) -> Vec<(String, Value)> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl ChiaRpcClient for MockClient {
+        type Error = serde_json::Error;
+
+        fn base_url(&self) -> &'static str {
+            "http://localhost"
+        }
+
+        async fn make_post_request<R, B>(&self, endpoint: &str, body: B) -> Result<R, Self::Error>
+        where
+            B: Serialize + Send,
+            R: DeserializeOwned + Send,
+        {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((endpoint.to_string(), serde_json::to_value(body)?));
+
+            serde_json::from_value(
+                self.responses
+                    .get(endpoint)
+                    .cloned()
+                    .unwrap_or_else(|| json!({ "success": false, "error": "unknown endpoint" })),
+            )
+        }
+    }
+
+    fn parse_rpc(args: &[&str]) -> Result<RpcCommand, clap::Error> {
+        let args = ["chia-sdk-cli", "rpc"].iter().chain(args);
+
+        match Command::try_parse_from(args)? {
+            Command::Rpc(command) => Ok(command),
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_rpc_args() -> Result<()> {
+        let command = parse_rpc(&["blockchain-state"])?;
+        assert!(matches!(command.request, RpcRequest::BlockchainState));
+        assert!(matches!(command.network, NetworkName::Mainnet));
+        assert!(!command.json);
+
+        // The global options can be given after the request
+        let command = parse_rpc(&["coin-record", "00", "--network", "testnet11", "--json"])?;
+        assert!(matches!(command.network, NetworkName::Testnet11));
+        assert!(command.json);
+
+        let command = parse_rpc(&[
+            "--full-node",
+            "https://localhost:8555",
+            "--cert",
+            "node.crt",
+            "--key",
+            "node.key",
+            "blockchain-state",
+        ])?;
+        assert_eq!(command.full_node.as_deref(), Some("https://localhost:8555"));
+
+        // A full node needs a certificate and key, and can't be combined with a coinset URL
+        assert!(parse_rpc(&["--full-node", "https://localhost:8555", "blockchain-state"]).is_err());
+        assert!(
+            parse_rpc(&[
+                "--full-node",
+                "https://localhost:8555",
+                "--cert",
+                "node.crt",
+                "--key",
+                "node.key",
+                "--coinset-url",
+                "https://api.coinset.org",
+                "blockchain-state",
+            ])
+            .is_err()
+        );
+
+        assert!(parse_rpc(&["coin-records"]).is_err());
+        assert!(parse_rpc(&["coin-records", "00", "--start-height", "-1"]).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_coin_records_request() -> Result<()> {
+        let puzzle_hash = Bytes32::new([1; 32]);
+
+        let client = MockClient::new(HashMap::from([(
+            "get_coin_records_by_puzzle_hash",
+            json!({ "success": true, "coin_records": [] }),
+        )]));
+
+        let command = parse_rpc(&[
+            "coin-records",
+            &puzzle_hash.to_string(),
+            "--include-spent",
+            "--start-height",
+            "10",
+            "--cursor",
+            "abc",
+        ])?;
+
+        send_request(&client, &command).await?;
+
+        assert_eq!(
+            client.requests(),
+            [(
+                "get_coin_records_by_puzzle_hash".to_string(),
+                json!({
+                    "puzzle_hash": format!("0x{puzzle_hash}"),
+                    "start_height": 10,
+                    "end_height": null,
+                    "include_spent_coins": true,
+                    "cursor": "abc",
+                })
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_coin_id_requests() -> Result<()> {
+        let coin_id = Bytes32::new([2; 32]);
+
+        let client = MockClient::new(HashMap::from([(
+            "get_coin_record_by_name",
+            json!({ "success": false, "error": "coin not found" }),
+        )]));
+
+        let command = parse_rpc(&["coin-record", &format!("0x{coin_id}")])?;
+        let error = send_request(&client, &command).await.unwrap_err();
+        assert_eq!(error.to_string(), "coin not found");
+
+        let command = parse_rpc(&["puzzle-and-solution", &coin_id.to_string(), "--height", "5"])?;
+        assert!(send_request(&client, &command).await.is_err());
+
+        assert_eq!(
+            client.requests(),
+            [
+                (
+                    "get_coin_record_by_name".to_string(),
+                    json!({ "name": format!("0x{coin_id}") })
+                ),
+                (
+                    "get_puzzle_and_solution".to_string(),
+                    json!({ "coin_id": format!("0x{coin_id}"), "height": 5 })
+                ),
+            ]
+        );
+
+        // Coin ids must be 32 bytes, and are checked before the request is made
+        let command = parse_rpc(&["coin-record", "0x00"])?;
+        assert!(send_request(&client, &command).await.is_err());
+        assert_eq!(client.requests().len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,452 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use chia_wallet_sdk::{
+    chia::{
+        bls::{aggregate_verify, master_to_wallet_hardened, master_to_wallet_unhardened, sign},
+        puzzle_types::DeriveSynthetic,
+        traits::Streamable,
+    },
+    prelude::*,
+};
+
+use crate::{
+    args::{SignCommand, VerifyCommand},
+    input::{agg_sig_constants, parse_spend_bundle, read_input, read_mnemonic},
+};
+
+/// Searching for missing signatures tries every subset of public keys, so it's capped.
+const MAX_SEARCH_KEYS: usize = 12;
+
+pub fn sign_spend_bundle(args: &SignCommand) -> Result<()> {
+    let mut spend_bundle = parse_spend_bundle(&read_input(args.input.as_deref())?)?;
+    let constants = agg_sig_constants(&args.agg_sig)?;
+
+    let mut secret_keys = HashMap::new();
+
+    if let Some(path) = &args.secret_key_file {
+        for sk in read_secret_keys(path)? {
+            secret_keys.insert(sk.public_key(), sk);
+        }
+    }
+
+    if let Some(path) = &args.mnemonic_file {
+        let master_sk = SecretKey::from_seed(&read_mnemonic(Some(path))?.to_seed(""));
+
+        for index in 0..args.derivations {
+            for sk in [
+                master_to_wallet_unhardened(&master_sk, index),
+                master_to_wallet_hardened(&master_sk, index),
+            ] {
+                let synthetic_sk = sk.derive_synthetic();
+                secret_keys.insert(synthetic_sk.public_key(), synthetic_sk);
+                secret_keys.insert(sk.public_key(), sk);
+            }
+        }
+
+        secret_keys.insert(master_sk.public_key(), master_sk);
+    }
+
+    if secret_keys.is_empty() {
+        bail!("no secret keys were provided, use --secret-key-file or --mnemonic-file");
+    }
+
+    let missing = sign_with_keys(&mut spend_bundle, &secret_keys, &constants)?;
+
+    if missing > 0 && !args.partial {
+        bail!("{missing} required signature(s) could not be signed, use --partial to allow this");
+    }
+
+    if args.signature_only {
+        println!(
+            "{}",
+            hex::encode(spend_bundle.aggregated_signature.to_bytes())
+        );
+    } else if args.json {
+        println!("{}", serde_json::to_string_pretty(&spend_bundle)?);
+    } else {
+        println!("{}", hex::encode(spend_bundle.to_bytes()?));
+    }
+
+    Ok(())
+}
+
+pub fn verify_spend_bundle(args: &VerifyCommand) -> Result<()> {
+    let spend_bundle = parse_spend_bundle(&read_input(args.input.as_deref())?)?;
+    let constants = agg_sig_constants(&args.agg_sig)?;
+
+    let mut allocator = Allocator::new();
+
+    let required_signatures =
+        RequiredSignature::from_coin_spends(&mut allocator, &spend_bundle.coin_spends, &constants)?;
+
+    let mut signers: Vec<(PublicKey, Vec<Vec<u8>>)> = Vec::new();
+    let mut secp_count = 0;
+
+    println!("Required signatures:");
+
+    for required in required_signatures {
+        let RequiredSignature::Bls(required) = required else {
+            secp_count += 1;
+            continue;
+        };
+
+        let message = required.message();
+
+        println!(
+            "  {} signs {}",
+            hex::encode(required.public_key.to_bytes()),
+            hex::encode(&message)
+        );
+
+        add_signer_message(&mut signers, required.public_key, message);
+    }
+
+    if signers.is_empty() {
+        println!("  None");
+    }
+
+    if secp_count > 0 {
+        println!("  {secp_count} secp signature(s), which are checked when the puzzle is run");
+    }
+
+    println!();
+
+    let all_signers: Vec<usize> = (0..signers.len()).collect();
+
+    if verify_signers(&spend_bundle.aggregated_signature, &signers, &all_signers) {
+        println!("The aggregated signature is valid.");
+        return Ok(());
+    }
+
+    println!("The aggregated signature is not valid.");
+
+    if signers.len() > MAX_SEARCH_KEYS {
+        bail!(
+            "the aggregated signature is not valid, and there are too many signers to find which are missing"
+        );
+    }
+
+    let Some(present) = find_present_signers(&spend_bundle.aggregated_signature, &signers) else {
+        bail!("the aggregated signature doesn't match any subset of the required signatures");
+    };
+
+    println!("Missing signatures from:");
+
+    for (index, (public_key, messages)) in signers.iter().enumerate() {
+        if present.contains(&index) {
+            continue;
+        }
+
+        println!(
+            "  {} ({} message(s))",
+            hex::encode(public_key.to_bytes()),
+            messages.len()
+        );
+    }
+
+    bail!("the aggregated signature is not valid")
+}
+
+/// Signs every required signature that one of the secret keys can sign, and returns the number of
+/// required signatures that couldn't be signed.
+///
+/// Signers that are already covered by the aggregated signature are skipped, so that a partially
+/// signed spend bundle can be signed again without adding the same signatures twice.
+fn sign_with_keys(
+    spend_bundle: &mut SpendBundle,
+    secret_keys: &HashMap<PublicKey, SecretKey>,
+    constants: &AggSigConstants,
+) -> Result<usize> {
+    let mut allocator = Allocator::new();
+
+    let required_signatures =
+        RequiredSignature::from_coin_spends(&mut allocator, &spend_bundle.coin_spends, constants)?;
+
+    let mut signers: Vec<(PublicKey, Vec<Vec<u8>>)> = Vec::new();
+
+    for required in required_signatures {
+        // Secp signatures are part of the solution, so there's nothing to aggregate.
+        let RequiredSignature::Bls(required) = required else {
+            continue;
+        };
+
+        add_signer_message(&mut signers, required.public_key, required.message());
+    }
+
+    let present = if spend_bundle.aggregated_signature == Signature::default() {
+        Vec::new()
+    } else {
+        let all_signers: Vec<usize> = (0..signers.len()).collect();
+
+        if verify_signers(&spend_bundle.aggregated_signature, &signers, &all_signers) {
+            all_signers
+        } else if signers.len() > MAX_SEARCH_KEYS {
+            bail!(
+                "the spend bundle is partially signed, and there are too many signers to find which signatures are present"
+            );
+        } else if let Some(present) =
+            find_present_signers(&spend_bundle.aggregated_signature, &signers)
+        {
+            present
+        } else {
+            bail!(
+                "the existing aggregated signature doesn't match any subset of the required signatures"
+            );
+        }
+    };
+
+    let mut missing = 0;
+
+    for (index, (public_key, messages)) in signers.iter().enumerate() {
+        if present.contains(&index) {
+            continue;
+        }
+
+        let Some(sk) = secret_keys.get(public_key) else {
+            for message in messages {
+                eprintln!(
+                    "no secret key for {} to sign {}",
+                    hex::encode(public_key.to_bytes()),
+                    hex::encode(message)
+                );
+            }
+            missing += messages.len();
+            continue;
+        };
+
+        for message in messages {
+            spend_bundle.aggregated_signature += &sign(sk, message);
+        }
+    }
+
+    Ok(missing)
+}
+
+/// Groups the messages by public key, since signatures are usually present or missing per signer.
+fn add_signer_message(
+    signers: &mut Vec<(PublicKey, Vec<Vec<u8>>)>,
+    public_key: PublicKey,
+    message: Vec<u8>,
+) {
+    if let Some((_, messages)) = signers
+        .iter_mut()
+        .find(|(existing, _)| *existing == public_key)
+    {
+        messages.push(message);
+    } else {
+        signers.push((public_key, vec![message]));
+    }
+}
+
+/// Finds the largest set of signers that the aggregated signature is valid for.
+fn find_present_signers(
+    signature: &Signature,
+    signers: &[(PublicKey, Vec<Vec<u8>>)],
+) -> Option<Vec<usize>> {
+    let mut masks: Vec<u32> = (0..(1u32 << signers.len())).collect();
+    masks.sort_by_key(|mask| std::cmp::Reverse(mask.count_ones()));
+
+    masks.into_iter().find_map(|mask| {
+        let indices: Vec<usize> = (0..signers.len())
+            .filter(|index| mask & (1 << index) != 0)
+            .collect();
+
+        verify_signers(signature, signers, &indices).then_some(indices)
+    })
+}
+
+fn verify_signers(
+    signature: &Signature,
+    signers: &[(PublicKey, Vec<Vec<u8>>)],
+    indices: &[usize],
+) -> bool {
+    aggregate_verify(
+        signature,
+        indices.iter().flat_map(|&index| {
+            let (public_key, messages) = &signers[index];
+            messages
+                .iter()
+                .map(move |message| (public_key, message.as_slice()))
+        }),
+    )
+}
+
+fn read_secret_keys(path: &Path) -> Result<Vec<SecretKey>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))?;
+
+    let mut secret_keys = Vec::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let bytes: [u8; 32] = parse_hex(line)?
+            .to_vec()
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("expected a 32 byte secret key"))?;
+
+        secret_keys.push(SecretKey::from_bytes(&bytes)?);
+    }
+
+    Ok(secret_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use chia_wallet_sdk::chia::puzzle_types::Memos;
+
+    use super::*;
+
+    struct TestSpend {
+        sim: Simulator,
+        spend_bundle: SpendBundle,
+        alice: SecretKey,
+        bob: SecretKey,
+        constants: AggSigConstants,
+    }
+
+    fn test_spend() -> Result<TestSpend> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(1);
+
+        for key in [&alice, &bob] {
+            StandardLayer::new(key.pk).spend(
+                &mut ctx,
+                key.coin,
+                Conditions::new().create_coin(key.puzzle_hash, 1, Memos::None),
+            )?;
+        }
+
+        Ok(TestSpend {
+            sim,
+            spend_bundle: SpendBundle::new(ctx.take(), Signature::default()),
+            alice: alice.sk,
+            bob: bob.sk,
+            constants: AggSigConstants::new(TESTNET11_CONSTANTS.agg_sig_me_additional_data),
+        })
+    }
+
+    fn keys(secret_keys: &[&SecretKey]) -> HashMap<PublicKey, SecretKey> {
+        secret_keys
+            .iter()
+            .map(|&sk| (sk.public_key(), sk.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_sign_spend_bundle() -> Result<()> {
+        let TestSpend {
+            mut sim,
+            mut spend_bundle,
+            alice,
+            bob,
+            constants,
+        } = test_spend()?;
+
+        let missing = sign_with_keys(&mut spend_bundle, &keys(&[&alice, &bob]), &constants)?;
+        assert_eq!(missing, 0);
+
+        sim.new_transaction(spend_bundle)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sign_partially_signed_spend_bundle() -> Result<()> {
+        let TestSpend {
+            mut sim,
+            mut spend_bundle,
+            alice,
+            bob,
+            constants,
+        } = test_spend()?;
+
+        let missing = sign_with_keys(&mut spend_bundle, &keys(&[&alice]), &constants)?;
+        assert_eq!(missing, 1);
+
+        // Signing with both keys only adds the missing signature
+        let missing = sign_with_keys(&mut spend_bundle, &keys(&[&alice, &bob]), &constants)?;
+        assert_eq!(missing, 0);
+
+        // Signing a fully signed spend bundle leaves it unchanged
+        let signature = spend_bundle.aggregated_signature.clone();
+        let missing = sign_with_keys(&mut spend_bundle, &keys(&[&alice, &bob]), &constants)?;
+        assert_eq!(missing, 0);
+        assert_eq!(spend_bundle.aggregated_signature, signature);
+
+        sim.new_transaction(spend_bundle)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sign_invalid_signature() -> Result<()> {
+        let TestSpend {
+            mut spend_bundle,
+            alice,
+            constants,
+            ..
+        } = test_spend()?;
+
+        spend_bundle.aggregated_signature = sign(&alice, b"unrelated message");
+
+        assert!(sign_with_keys(&mut spend_bundle, &keys(&[&alice]), &constants).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_present_signers() -> Result<()> {
+        let TestSpend {
+            mut spend_bundle,
+            alice,
+            bob,
+            constants,
+            ..
+        } = test_spend()?;
+
+        let mut allocator = Allocator::new();
+        let mut signers = Vec::new();
+
+        for required in RequiredSignature::from_coin_spends(
+            &mut allocator,
+            &spend_bundle.coin_spends,
+            &constants,
+        )? {
+            let RequiredSignature::Bls(required) = required else {
+                continue;
+            };
+            add_signer_message(&mut signers, required.public_key, required.message());
+        }
+
+        assert_eq!(signers.len(), 2);
+        assert_eq!(signers[0].0, alice.public_key());
+
+        assert_eq!(
+            find_present_signers(&spend_bundle.aggregated_signature, &signers),
+            Some(vec![])
+        );
+
+        sign_with_keys(&mut spend_bundle, &keys(&[&bob]), &constants)?;
+
+        assert_eq!(
+            find_present_signers(&spend_bundle.aggregated_signature, &signers),
+            Some(vec![1])
+        );
+
+        sign_with_keys(&mut spend_bundle, &keys(&[&alice]), &constants)?;
+
+        assert_eq!(
+            find_present_signers(&spend_bundle.aggregated_signature, &signers),
+            Some(vec![0, 1])
+        );
+
+        Ok(())
+    }
+}
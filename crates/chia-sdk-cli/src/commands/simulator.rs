@@ -0,0 +1,238 @@
+use std::{fs, net::SocketAddr};
+
+use anyhow::{Context, Result, bail};
+use chia_wallet_sdk::{
+    client::load_ssl_cert,
+    test::{PeerSimulator, PeerSimulatorConfig},
+};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::{args::ServeSimulatorCommand, input::parse_puzzle_hash};
+
+const HELP: &str = "\
+commands:
+  fund <puzzle hash or address> <amount>  create a coin
+  time <seconds>                          advance the timestamp and create a block
+  block [count]                           create empty blocks
+  height                                  print the current peak
+  reset                                   reset the simulator to its initial state
+  help                                    print this message
+  exit                                    stop the simulator";
+
+pub fn serve_simulator(args: &ServeSimulatorCommand) -> Result<()> {
+    tokio::runtime::Runtime::new()?.block_on(serve(args))
+}
+
+async fn serve(args: &ServeSimulatorCommand) -> Result<()> {
+    fs::create_dir_all(&args.ssl_dir)
+        .with_context(|| format!("could not create {}", args.ssl_dir.display()))?;
+
+    let cert_path = args.ssl_dir.join("simulator.crt");
+    let key_path = args.ssl_dir.join("simulator.key");
+
+    let cert = load_ssl_cert(
+        cert_path.to_str().context("invalid certificate path")?,
+        key_path.to_str().context("invalid key path")?,
+    )?;
+
+    let config = PeerSimulatorConfig {
+        bind_addr: SocketAddr::new(args.host, args.port),
+        ..Default::default()
+    };
+
+    let sim = PeerSimulator::with_tls(config, &cert).await?;
+
+    println!("listening on wss://{}/ws", sim.addr());
+    println!("certificate = {}", cert_path.display());
+    println!("key = {}", key_path.display());
+    println!("genesis challenge = {}", sim.config().genesis_challenge);
+    println!("{HELP}");
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    // Keep serving if stdin is closed, such as when running in the background.
+                    tokio::signal::ctrl_c().await?;
+                    break;
+                };
+
+                match run_admin_command(&sim, &line).await {
+                    Ok(true) => {}
+                    Ok(false) => break,
+                    Err(error) => println!("error: {error:#}"),
+                }
+            }
+            result = tokio::signal::ctrl_c() => {
+                result?;
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a single admin command, returning `false` if the simulator should stop.
+async fn run_admin_command(sim: &PeerSimulator, line: &str) -> Result<bool> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+
+    match parts.as_slice() {
+        [] => {}
+        ["fund", destination, amount] => {
+            let puzzle_hash = parse_puzzle_hash(destination)?;
+            let amount: u64 = amount.parse().context("invalid amount")?;
+            let coin = sim.new_coin(puzzle_hash, amount).await?;
+
+            println!("coin id = {}", coin.coin_id());
+        }
+        ["time", seconds] => {
+            let seconds: u64 = seconds.parse().context("invalid number of seconds")?;
+            sim.pass_time(seconds).await?;
+            print_peak(sim).await;
+        }
+        ["block"] => {
+            sim.create_block().await?;
+            print_peak(sim).await;
+        }
+        ["block", count] => {
+            let count: u32 = count.parse().context("invalid block count")?;
+            for _ in 0..count {
+                sim.create_block().await?;
+            }
+            print_peak(sim).await;
+        }
+        ["height"] => print_peak(sim).await,
+        ["reset"] => {
+            sim.reset().await?;
+            print_peak(sim).await;
+        }
+        ["help"] => println!("{HELP}"),
+        ["exit" | "quit"] => return Ok(false),
+        _ => bail!("unknown command, type `help` for a list of commands"),
+    }
+
+    Ok(true)
+}
+
+async fn print_peak(sim: &PeerSimulator) {
+    let sim = sim.lock().await;
+    println!(
+        "height = {}, header hash = {}, next timestamp = {}",
+        sim.height(),
+        sim.header_hash(),
+        sim.next_timestamp()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::Ipv4Addr, path::Path};
+
+    use chia_wallet_sdk::prelude::*;
+    use clap::Parser;
+
+    use crate::args::{Command, SimulatorCommand};
+
+    use super::*;
+
+    fn parse_serve(args: &[&str]) -> Result<ServeSimulatorCommand, clap::Error> {
+        let args = ["chia-sdk-cli", "simulator", "serve"].iter().chain(args);
+
+        match Command::try_parse_from(args)? {
+            Command::Simulator(SimulatorCommand::Serve(command)) => Ok(command),
+            command => panic!("unexpected command {command:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_serve_args() -> Result<()> {
+        let command = parse_serve(&[])?;
+        assert_eq!(command.host, Ipv4Addr::LOCALHOST);
+        assert_eq!(command.port, 58444);
+        assert_eq!(command.ssl_dir, Path::new("simulator-ssl"));
+
+        let command = parse_serve(&["--host", "0.0.0.0", "-p", "8444", "--ssl-dir", "ssl"])?;
+        assert_eq!(command.host, Ipv4Addr::UNSPECIFIED);
+        assert_eq!(command.port, 8444);
+        assert_eq!(command.ssl_dir, Path::new("ssl"));
+
+        assert!(parse_serve(&["--host", "localhost"]).is_err());
+        assert!(parse_serve(&["--port", "65536"]).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_admin_commands() -> Result<()> {
+        let sim = PeerSimulator::new().await?;
+        let puzzle_hash = Bytes32::new([1; 32]);
+
+        assert!(run_admin_command(&sim, &format!("fund {puzzle_hash} 1000")).await?);
+        assert_eq!(
+            sim.lock()
+                .await
+                .unspent_coins(puzzle_hash, false)
+                .iter()
+                .map(|coin| coin.amount)
+                .collect::<Vec<_>>(),
+            [1000]
+        );
+
+        let height = sim.lock().await.height();
+        assert!(run_admin_command(&sim, "block 3").await?);
+        assert_eq!(sim.lock().await.height(), height + 3);
+
+        let timestamp = sim.lock().await.next_timestamp();
+        assert!(run_admin_command(&sim, "time 100").await?);
+        assert!(sim.lock().await.next_timestamp() >= timestamp + 100);
+
+        assert!(run_admin_command(&sim, "  ").await?);
+        assert!(run_admin_command(&sim, "height").await?);
+
+        assert!(run_admin_command(&sim, "reset").await?);
+        assert!(
+            sim.lock()
+                .await
+                .unspent_coins(puzzle_hash, false)
+                .is_empty()
+        );
+
+        assert!(!run_admin_command(&sim, "exit").await?);
+        assert!(!run_admin_command(&sim, "quit").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_admin_commands() -> Result<()> {
+        let sim = PeerSimulator::new().await?;
+        let puzzle_hash = Bytes32::new([1; 32]);
+
+        assert!(
+            run_admin_command(&sim, &format!("fund {puzzle_hash} -1"))
+                .await
+                .is_err()
+        );
+        assert!(
+            run_admin_command(&sim, "fund xch1invalid 1000")
+                .await
+                .is_err()
+        );
+        assert!(run_admin_command(&sim, "fund").await.is_err());
+        assert!(run_admin_command(&sim, "block many").await.is_err());
+        assert!(run_admin_command(&sim, "time 1 2").await.is_err());
+        assert!(run_admin_command(&sim, "unknown").await.is_err());
+
+        assert!(
+            sim.lock()
+                .await
+                .unspent_coins(puzzle_hash, false)
+                .is_empty()
+        );
+
+        Ok(())
+    }
+}
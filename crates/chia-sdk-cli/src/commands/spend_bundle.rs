@@ -0,0 +1,313 @@
+use std::{collections::HashSet, fmt::Write};
+
+use anyhow::{Result, bail};
+use chia_wallet_sdk::{driver::spend_bundle_cost, prelude::*, test::announcement_report};
+
+use crate::{
+    args::InspectSpendBundleCommand,
+    input::{parse_spend_bundle, read_input},
+};
+
+pub fn inspect_spend_bundle(args: &InspectSpendBundleCommand) -> Result<()> {
+    let spend_bundle = parse_spend_bundle(&read_input(args.input.as_deref())?)?;
+
+    let mut report = String::new();
+    let is_valid = write_report(&mut report, &spend_bundle)?;
+    print!("{report}");
+
+    if !is_valid {
+        bail!("the spend bundle is not valid");
+    }
+
+    Ok(())
+}
+
+/// Writes the spends, coins, announcements and cost of a spend bundle, and returns whether it's valid.
+fn write_report(out: &mut String, spend_bundle: &SpendBundle) -> Result<bool> {
+    let coin_spends = &spend_bundle.coin_spends;
+
+    let mut allocator = Allocator::new();
+    let mut removals = Vec::new();
+    let mut additions = Vec::new();
+    let mut reserved_fee = 0;
+    let mut failed = false;
+
+    for (index, coin_spend) in coin_spends.iter().enumerate() {
+        let coin = coin_spend.coin;
+        removals.push(coin);
+
+        writeln!(out, "Spend {index}")?;
+        writeln!(out, "  Coin id: {}", coin.coin_id())?;
+        writeln!(out, "  Parent coin id: {}", coin.parent_coin_info)?;
+        writeln!(out, "  Puzzle hash: {}", coin.puzzle_hash)?;
+        writeln!(out, "  Amount: {}", coin.amount)?;
+
+        let puzzle = coin_spend.puzzle_reveal.to_clvm(&mut allocator)?;
+        let solution = coin_spend.solution.to_clvm(&mut allocator)?;
+
+        let puzzle_hash: Bytes32 = tree_hash(&allocator, puzzle).into();
+
+        if puzzle_hash != coin.puzzle_hash {
+            writeln!(
+                out,
+                "  Puzzle reveal does not match the puzzle hash (got {puzzle_hash})"
+            )?;
+            failed = true;
+        }
+
+        let output = match run_puzzle(&mut allocator, puzzle, solution) {
+            Ok(output) => output,
+            Err(error) => {
+                writeln!(out, "  Failed to run puzzle: {error}")?;
+                failed = true;
+                continue;
+            }
+        };
+
+        let conditions = Vec::<Condition<Program>>::from_clvm(&allocator, output)?;
+
+        writeln!(out, "  Conditions:")?;
+
+        for condition in conditions {
+            if let Some(create_coin) = condition.as_create_coin() {
+                additions.push(Coin::new(
+                    coin.coin_id(),
+                    create_coin.puzzle_hash,
+                    create_coin.amount,
+                ));
+            }
+
+            if let Some(reserve_fee) = condition.as_reserve_fee() {
+                reserved_fee += u128::from(reserve_fee.amount);
+            }
+
+            writeln!(out, "    {condition:?}")?;
+        }
+    }
+
+    let removal_ids: HashSet<Bytes32> = removals.iter().map(Coin::coin_id).collect();
+    let addition_ids: HashSet<Bytes32> = additions.iter().map(Coin::coin_id).collect();
+
+    writeln!(out)?;
+    writeln!(out, "Removals")?;
+
+    for coin in &removals {
+        let ephemeral = if addition_ids.contains(&coin.coin_id()) {
+            " (ephemeral)"
+        } else {
+            ""
+        };
+
+        writeln!(
+            out,
+            "  {} {} {}{ephemeral}",
+            coin.coin_id(),
+            coin.puzzle_hash,
+            coin.amount
+        )?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Additions")?;
+
+    for coin in &additions {
+        let ephemeral = if removal_ids.contains(&coin.coin_id()) {
+            " (ephemeral)"
+        } else {
+            ""
+        };
+
+        writeln!(
+            out,
+            "  {} {} {}{ephemeral}",
+            coin.coin_id(),
+            coin.puzzle_hash,
+            coin.amount
+        )?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Announcements")?;
+
+    match announcement_report(coin_spends) {
+        Ok(report) => {
+            if report.lines.is_empty() {
+                writeln!(out, "  None")?;
+            }
+
+            for line in &report.lines {
+                writeln!(out, "  {line}")?;
+            }
+
+            if !report.is_valid {
+                writeln!(
+                    out,
+                    "  Asserted announcements do not match created announcements"
+                )?;
+                failed = true;
+            }
+        }
+        Err(error) => {
+            writeln!(out, "  Could not extract announcements: {error}")?;
+            failed = true;
+        }
+    }
+
+    let removed: u128 = removals.iter().map(|coin| u128::from(coin.amount)).sum();
+    let added: u128 = additions.iter().map(|coin| u128::from(coin.amount)).sum();
+
+    writeln!(out)?;
+    writeln!(out, "Summary")?;
+    writeln!(out, "  Coin spends: {}", coin_spends.len())?;
+    writeln!(out, "  Removed amount: {removed}")?;
+    writeln!(out, "  Added amount: {added}")?;
+
+    if let Some(fee) = removed.checked_sub(added) {
+        writeln!(out, "  Fee: {fee}")?;
+    } else {
+        writeln!(out, "  Fee: outputs exceed inputs by {}", added - removed)?;
+        failed = true;
+    }
+
+    writeln!(out, "  Reserved fee: {reserved_fee}")?;
+
+    match spend_bundle_cost(coin_spends) {
+        Ok(cost) => writeln!(out, "  Cost: {cost}")?,
+        Err(error) => writeln!(out, "  Cost: could not be calculated ({error})")?,
+    }
+
+    writeln!(
+        out,
+        "  Aggregated signature: {}",
+        hex::encode(spend_bundle.aggregated_signature.to_bytes())
+    )?;
+
+    Ok(!failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use chia_wallet_sdk::{chia::puzzle_types::Memos, types::announcement_id};
+    use hex_literal::hex;
+
+    use super::*;
+
+    // The tree hash of the `1` puzzle, which returns its solution as the conditions.
+    const IDENTITY_PUZZLE_HASH: [u8; 32] =
+        hex!("9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2");
+
+    fn identity_spend(
+        ctx: &mut SpendContext,
+        coin: Coin,
+        conditions: &Conditions,
+    ) -> Result<CoinSpend> {
+        Ok(CoinSpend::new(
+            coin,
+            Program::from(vec![1]),
+            ctx.serialize(conditions)?,
+        ))
+    }
+
+    #[test]
+    fn test_spend_bundle_report() -> Result<()> {
+        let mut ctx = SpendContext::new();
+
+        let puzzle_hash = Bytes32::new(IDENTITY_PUZZLE_HASH);
+        let parent = Coin::new(Bytes32::default(), puzzle_hash, 3);
+        let child = Coin::new(parent.coin_id(), puzzle_hash, 2);
+
+        let spend_bundle = SpendBundle::new(
+            vec![
+                identity_spend(
+                    &mut ctx,
+                    parent,
+                    &Conditions::new()
+                        .create_coin(puzzle_hash, 2, Memos::None)
+                        .create_coin_announcement(b"hello".to_vec().into()),
+                )?,
+                identity_spend(
+                    &mut ctx,
+                    child,
+                    &Conditions::new()
+                        .assert_coin_announcement(announcement_id(parent.coin_id(), b"hello"))
+                        .reserve_fee(2),
+                )?,
+            ],
+            Signature::default(),
+        );
+
+        let mut report = String::new();
+        assert!(write_report(&mut report, &spend_bundle)?);
+
+        assert_eq!(
+            report.lines().collect::<Vec<_>>(),
+            [
+                "Spend 0",
+                "  Coin id: adecf3df0cc0c64fb5e206407871645f61266152075628350a2b17fcca40bf6e",
+                "  Parent coin id: 0000000000000000000000000000000000000000000000000000000000000000",
+                "  Puzzle hash: 9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2",
+                "  Amount: 3",
+                "  Conditions:",
+                "    CreateCoin(CreateCoin { puzzle_hash: 9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2, amount: 2, memos: None })",
+                "    CreateCoinAnnouncement(CreateCoinAnnouncement { message: 68656c6c6f })",
+                "Spend 1",
+                "  Coin id: bd1b7b86a20cc6849ab57918a30f13a4efb897cbc3ce62a7e458f5b2fccf24d8",
+                "  Parent coin id: adecf3df0cc0c64fb5e206407871645f61266152075628350a2b17fcca40bf6e",
+                "  Puzzle hash: 9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2",
+                "  Amount: 2",
+                "  Conditions:",
+                "    AssertCoinAnnouncement(AssertCoinAnnouncement { announcement_id: 2432357cef024a08fba07ceb3e6c0e9430c69734bf0aa8a5773afd83e873c1db })",
+                "    ReserveFee(ReserveFee { amount: 2 })",
+                "",
+                "Removals",
+                "  adecf3df0cc0c64fb5e206407871645f61266152075628350a2b17fcca40bf6e 9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2 3",
+                "  bd1b7b86a20cc6849ab57918a30f13a4efb897cbc3ce62a7e458f5b2fccf24d8 9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2 2 (ephemeral)",
+                "",
+                "Additions",
+                "  bd1b7b86a20cc6849ab57918a30f13a4efb897cbc3ce62a7e458f5b2fccf24d8 9dcf97a184f32623d11a73124ceb99a5709b083721e878a16d78f596718ba7b2 2 (ephemeral)",
+                "",
+                "Announcements",
+                "  spend 1 asserted coin announcement created by spend 0: 2432357c",
+                "",
+                "Summary",
+                "  Coin spends: 2",
+                "  Removed amount: 5",
+                "  Added amount: 2",
+                "  Fee: 3",
+                "  Reserved fee: 2",
+                "  Cost: 3984088",
+                "  Aggregated signature: c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_spend_bundle_report_unasserted_announcement() -> Result<()> {
+        let mut ctx = SpendContext::new();
+
+        let puzzle_hash = Bytes32::new(IDENTITY_PUZZLE_HASH);
+        let coin = Coin::new(Bytes32::default(), puzzle_hash, 1);
+
+        let spend_bundle = SpendBundle::new(
+            vec![identity_spend(
+                &mut ctx,
+                coin,
+                &Conditions::new().create_coin_announcement(b"hello".to_vec().into()),
+            )?],
+            Signature::default(),
+        );
+
+        let mut report = String::new();
+        assert!(!write_report(&mut report, &spend_bundle)?);
+
+        assert!(report.contains(
+            "Announcements\n  spend 0 created coin announcement 68656c6c but it was not asserted\n  \
+             Asserted announcements do not match created announcements\n"
+        ));
+        assert!(report.contains("  Fee: 1\n  Reserved fee: 0\n"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,807 @@
+use std::{fmt::Write, path::Path};
+
+use anyhow::{Context, Result, bail};
+use chia_wallet_sdk::{
+    driver::{
+        AssertedPayment, ClawbackInfo, ClawbackPath, ClearSigningAsset, CustodyInfo,
+        DelegatedPuzzleFeederLayer, IndexWrapperLayer, IssuanceKind, P2ConditionsOrSingleton,
+        ParsedAsset, ParsedChild, Reveals, SingletonLayer, TransferType, VaultTransaction,
+        parse_vault_transaction,
+    },
+    prelude::*,
+    types::puzzles::{AddDelegatedPuzzleWrapper, AddDelegatedPuzzleWrapperSolution},
+};
+use serde::Deserialize;
+
+use crate::{
+    args::ReviewVaultCommand,
+    input::{parse_program, parse_puzzle_hash, parse_spend_bundle, read_input},
+};
+
+type VaultLayers = SingletonLayer<IndexWrapperLayer<usize, DelegatedPuzzleFeederLayer<Puzzle>>>;
+
+/// Additional information about the transaction that can't be derived from the spend bundle alone.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RevealsFile {
+    #[serde(default)]
+    coin_spends: Vec<CoinSpend>,
+    #[serde(default)]
+    clawbacks: Vec<ClawbackReveal>,
+    #[serde(default)]
+    p2_conditions_or_singletons: Vec<P2ConditionsOrSingletonReveal>,
+    #[serde(default)]
+    vault_nonces: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClawbackReveal {
+    sender_puzzle_hash: Bytes32,
+    receiver_puzzle_hash: Bytes32,
+    seconds: u64,
+    amount: u64,
+    #[serde(default)]
+    hinted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct P2ConditionsOrSingletonReveal {
+    launcher_id: Option<Bytes32>,
+    nonce: usize,
+    fixed_conditions_hash: Bytes32,
+}
+
+pub fn review_vault_transaction(args: &ReviewVaultCommand) -> Result<()> {
+    let spend_bundle = parse_spend_bundle(&read_input(args.input.as_deref())?)?;
+
+    let reveals_file = match &args.reveals {
+        Some(path) => read_reveals(path)?,
+        None => RevealsFile::default(),
+    };
+
+    let mut report = String::new();
+    write_review(&mut report, args, &spend_bundle, &reveals_file)?;
+    print!("{report}");
+
+    Ok(())
+}
+
+/// Verifies the vault transaction in the spend bundle and writes what is being signed for.
+fn write_review(
+    out: &mut String,
+    args: &ReviewVaultCommand,
+    spend_bundle: &SpendBundle,
+    reveals_file: &RevealsFile,
+) -> Result<()> {
+    let mut ctx = SpendContext::new();
+
+    let launcher_id = args
+        .launcher_id
+        .as_deref()
+        .map(parse_puzzle_hash)
+        .transpose()?;
+
+    let vault_spend = find_vault_spend(&mut ctx, &spend_bundle.coin_spends, launcher_id)?;
+
+    let (launcher_id, delegated_spend) = match (&args.delegated_puzzle, vault_spend) {
+        (Some(delegated_puzzle), _) => {
+            let Some(launcher_id) = launcher_id.or(vault_spend.map(|(id, _)| id)) else {
+                bail!("the vault launcher id must be provided if the vault spend isn't included");
+            };
+
+            let puzzle = parse_program(&mut ctx, delegated_puzzle)?;
+            let solution = parse_program(&mut ctx, &args.delegated_solution)?;
+
+            (launcher_id, Spend::new(puzzle, solution))
+        }
+        (None, Some((launcher_id, coin_spend))) => {
+            let delegated_spend = extract_delegated_spend(&mut ctx, coin_spend)?;
+            (launcher_id, delegated_spend)
+        }
+        (None, None) => {
+            bail!("no vault spend was found, so the delegated puzzle must be provided")
+        }
+    };
+
+    let mut reveals = Reveals::default();
+
+    for coin_spend in spend_bundle
+        .coin_spends
+        .iter()
+        .chain(&reveals_file.coin_spends)
+    {
+        reveals.reveal_coin_spend(&mut ctx, coin_spend)?;
+    }
+
+    for clawback in &reveals_file.clawbacks {
+        reveals.reveal_clawback(ClawbackV2::new(
+            clawback.sender_puzzle_hash,
+            clawback.receiver_puzzle_hash,
+            clawback.seconds,
+            clawback.amount,
+            clawback.hinted,
+        ));
+    }
+
+    for p2 in &reveals_file.p2_conditions_or_singletons {
+        reveals.reveal_p2_conditions_or_singleton(P2ConditionsOrSingleton::new(
+            p2.launcher_id.unwrap_or(launcher_id),
+            p2.nonce,
+            p2.fixed_conditions_hash,
+        ));
+    }
+
+    for &nonce in &reveals_file.vault_nonces {
+        reveals.reveal_vault_nonce(nonce);
+    }
+
+    let tx = parse_vault_transaction(reveals, &mut ctx, launcher_id, delegated_spend)
+        .context("the vault transaction could not be verified")?;
+
+    write_vault_transaction(out, &mut ctx, launcher_id, &tx)
+}
+
+fn read_reveals(path: &Path) -> Result<RevealsFile> {
+    let text = read_input(Some(path))?;
+    serde_json::from_str(&text).with_context(|| format!("invalid reveals in {}", path.display()))
+}
+
+/// Finds the vault singleton spend in the spend bundle, optionally restricted to a specific launcher id.
+fn find_vault_spend<'a>(
+    allocator: &mut Allocator,
+    coin_spends: &'a [CoinSpend],
+    launcher_id: Option<Bytes32>,
+) -> Result<Option<(Bytes32, &'a CoinSpend)>> {
+    let mut found = None;
+
+    for coin_spend in coin_spends {
+        let puzzle = coin_spend.puzzle_reveal.to_clvm(allocator)?;
+        let puzzle = Puzzle::parse(allocator, puzzle);
+
+        let Some(vault) = VaultLayers::parse_puzzle(allocator, puzzle)? else {
+            continue;
+        };
+
+        if launcher_id.is_some_and(|launcher_id| launcher_id != vault.launcher_id) {
+            continue;
+        }
+
+        if found.is_some() {
+            bail!("multiple vault spends were found, so the launcher id must be provided");
+        }
+
+        found = Some((vault.launcher_id, coin_spend));
+    }
+
+    Ok(found)
+}
+
+/// Extracts the delegated spend from the vault's solution, removing any delegated puzzle wrappers
+/// added by restrictions so that the quoted conditions being signed for are revealed.
+fn extract_delegated_spend(ctx: &mut SpendContext, coin_spend: &CoinSpend) -> Result<Spend> {
+    let solution = coin_spend.solution.to_clvm(ctx)?;
+    let solution = VaultLayers::parse_solution(ctx, solution)?;
+
+    let mut delegated_spend = Spend::new(
+        solution.inner_solution.delegated_puzzle,
+        solution.inner_solution.delegated_solution,
+    );
+
+    while let Some(curried) = Puzzle::parse(ctx, delegated_spend.puzzle).as_curried() {
+        if curried.mod_hash != AddDelegatedPuzzleWrapper::<NodePtr, NodePtr>::mod_hash() {
+            break;
+        }
+
+        let args = AddDelegatedPuzzleWrapper::<NodePtr, NodePtr>::from_clvm(ctx, curried.args)?;
+        let solution = AddDelegatedPuzzleWrapperSolution::<NodePtr, NodePtr>::from_clvm(
+            ctx,
+            delegated_spend.solution,
+        )?;
+
+        delegated_spend = Spend::new(args.delegated_puzzle, solution.delegated_solution);
+    }
+
+    Ok(delegated_spend)
+}
+
+fn write_vault_transaction(
+    out: &mut String,
+    allocator: &mut Allocator,
+    launcher_id: Bytes32,
+    tx: &VaultTransaction,
+) -> Result<()> {
+    writeln!(out, "Vault")?;
+    writeln!(out, "  Launcher id: {launcher_id}")?;
+
+    writeln!(out)?;
+    writeln!(out, "Vault child")?;
+
+    if let Some(child) = &tx.vault_child {
+        writeln!(out, "  Custody hash: {}", child.custody_hash)?;
+        writeln!(out, "  Amount: {}", child.amount)?;
+    } else {
+        writeln!(out, "  None (the vault is melted)")?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Drop coins")?;
+
+    if tx.drop_coins.is_empty() {
+        writeln!(out, "  None")?;
+    }
+
+    for drop_coin in &tx.drop_coins {
+        writeln!(out, "  {} {}", drop_coin.puzzle_hash, drop_coin.amount)?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Spends")?;
+
+    if tx.spends.is_empty() {
+        writeln!(out, "  None")?;
+    }
+
+    for (index, spend) in tx.spends.iter().enumerate() {
+        writeln!(out, "  Spend {index}")?;
+        writeln!(out, "    Asset: {}", format_parsed_asset(&spend.asset))?;
+        writeln!(out, "    Coin id: {}", spend.asset.coin().coin_id())?;
+        writeln!(out, "    Amount: {}", spend.asset.coin().amount)?;
+        writeln!(out, "    Custody: {}", format_custody(&spend.custody))?;
+
+        for condition in custody_conditions(&spend.custody) {
+            writeln!(out, "      {}", format_condition(allocator, condition)?)?;
+        }
+
+        if let Some(clawback) = &spend.clawback {
+            writeln!(out, "    Clawback: {}", format_clawback(clawback))?;
+        }
+
+        if spend.revoked {
+            writeln!(out, "    Revoked: yes")?;
+        }
+
+        writeln!(out, "    Children:")?;
+
+        if spend.children.is_empty() {
+            writeln!(out, "      None")?;
+        }
+
+        for child in &spend.children {
+            write_child(out, allocator, child)?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Issuances")?;
+
+    if tx.issuances.is_empty() {
+        writeln!(out, "  None")?;
+    }
+
+    for issuance in &tx.issuances {
+        writeln!(out, "  Asset id: {}", issuance.asset_id)?;
+        writeln!(out, "    Coin id: {}", issuance.coin_id)?;
+        writeln!(out, "    Extra delta: {}", issuance.extra_delta)?;
+
+        match &issuance.kind {
+            IssuanceKind::EverythingWithSingleton {
+                singleton_struct_hash,
+                nonce,
+            } => {
+                writeln!(out, "    Kind: everything with singleton")?;
+                writeln!(out, "    Singleton struct hash: {singleton_struct_hash}")?;
+                writeln!(out, "    Nonce: {}", hex::encode(nonce))?;
+            }
+            IssuanceKind::Unknown => writeln!(out, "    Kind: unknown")?,
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Asset flows")?;
+
+    if tx.asset_flows.is_empty() {
+        writeln!(out, "  None")?;
+    }
+
+    for flow in &tx.asset_flows {
+        writeln!(out, "  {}", format_asset(&flow.asset))?;
+        writeln!(out, "    Input: {}", flow.input_amount)?;
+        writeln!(out, "    Output: {}", flow.output_amount)?;
+        writeln!(out, "    Issued: {}", flow.issued_amount)?;
+        writeln!(out, "    Melted: {}", flow.melted_amount)?;
+        writeln!(out, "    Received: {}", flow.received_amount)?;
+        writeln!(out, "    Paid: {}", flow.paid_amount)?;
+        writeln!(out, "    Unaccounted: {}", flow.unaccounted_amount)?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Asserted payments")?;
+
+    if tx.asserted_payments.is_empty() {
+        writeln!(out, "  None")?;
+    }
+
+    for asserted in &tx.asserted_payments {
+        writeln!(out, "  {}", format_asset(&asserted.asset))?;
+        writeln!(out, "    Nonce: {}", asserted.notarized_payment.nonce)?;
+
+        for payment in &asserted.notarized_payment.payments {
+            writeln!(out, "    {} {}", payment.puzzle_hash, payment.amount)?;
+        }
+    }
+
+    write_payments(out, "Received payments", &tx.received_payments)?;
+    write_payments(out, "External payments", &tx.external_payments)?;
+
+    writeln!(out)?;
+    writeln!(out, "Linked offer")?;
+
+    if let Some(offer) = &tx.linked_offer {
+        writeln!(out, "  Reserved fee: {}", offer.reserved_fee)?;
+        write_payment_lines(out, "  Received payments", &offer.received_payments)?;
+        write_payment_lines(out, "  External payments", &offer.external_payments)?;
+    } else {
+        writeln!(out, "  None")?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Vault p2 puzzle hashes")?;
+
+    for p2_puzzle_hash in &tx.p2_puzzle_hashes {
+        writeln!(out, "  {p2_puzzle_hash}")?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Reserved fee: {}", tx.reserved_fee)?;
+    writeln!(
+        out,
+        "Delegated puzzle hash to sign: {}",
+        tx.delegated_puzzle_hash
+    )?;
+
+    Ok(())
+}
+
+fn write_child(out: &mut String, allocator: &mut Allocator, child: &ParsedChild) -> Result<()> {
+    writeln!(
+        out,
+        "      {} {} {}",
+        child.asset.coin().coin_id(),
+        format_parsed_asset(&child.asset),
+        child.asset.coin().amount
+    )?;
+    writeln!(
+        out,
+        "        Transfer: {}",
+        format_transfer_type(&child.transfer_type)
+    )?;
+    writeln!(
+        out,
+        "        P2 puzzle hash: {}",
+        child.memos.p2_puzzle_hash
+    )?;
+
+    if let Some(clawback) = &child.memos.clawback {
+        writeln!(
+            out,
+            "        Clawback: sender {}, receiver {}, seconds {}",
+            clawback.sender_puzzle_hash, clawback.receiver_puzzle_hash, clawback.seconds
+        )?;
+    }
+
+    for memo in &child.memos.human_readable_memos {
+        writeln!(out, "        Memo: {memo:?}")?;
+    }
+
+    if let Some(fixed_conditions) = &child.memos.fixed_conditions {
+        writeln!(out, "        Fixed conditions:")?;
+
+        for condition in fixed_conditions {
+            writeln!(out, "          {}", format_condition(allocator, condition)?)?;
+        }
+    }
+
+    if let TransferType::OfferPreSplit(info) = &child.transfer_type {
+        writeln!(out, "        Offer launcher id: {}", info.launcher_id)?;
+        writeln!(out, "        Offer nonce: {}", info.nonce)?;
+        writeln!(out, "        Settlement amount: {}", info.settlement_amount)?;
+        writeln!(out, "        Offer fixed conditions:")?;
+
+        for condition in &info.fixed_conditions {
+            writeln!(out, "          {}", format_condition(allocator, condition)?)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_payments(out: &mut String, title: &str, payments: &[AssertedPayment]) -> Result<()> {
+    writeln!(out)?;
+    writeln!(out, "{title}")?;
+
+    if payments.is_empty() {
+        writeln!(out, "  None")?;
+    }
+
+    for payment in payments {
+        writeln!(out, "  {}", format_payment(payment))?;
+    }
+
+    Ok(())
+}
+
+fn write_payment_lines(out: &mut String, title: &str, payments: &[AssertedPayment]) -> Result<()> {
+    writeln!(out, "{title}:")?;
+
+    if payments.is_empty() {
+        writeln!(out, "    None")?;
+    }
+
+    for payment in payments {
+        writeln!(out, "    {}", format_payment(payment))?;
+    }
+
+    Ok(())
+}
+
+fn format_payment(payment: &AssertedPayment) -> String {
+    let royalty = payment
+        .royalty_basis_points
+        .map(|basis_points| format!(" (royalty {basis_points} basis points)"))
+        .unwrap_or_default();
+
+    format!(
+        "{} {} {} nonce {}{royalty}",
+        format_asset(&payment.asset),
+        payment.payment.puzzle_hash,
+        payment.payment.amount,
+        payment.nonce
+    )
+}
+
+fn format_asset(asset: &ClearSigningAsset) -> String {
+    match asset {
+        ClearSigningAsset::Xch => "XCH".to_string(),
+        ClearSigningAsset::Cat {
+            asset_id,
+            hidden_puzzle_hash,
+        } => match hidden_puzzle_hash {
+            Some(hidden_puzzle_hash) => {
+                format!("CAT {asset_id} (revocable by {hidden_puzzle_hash})")
+            }
+            None => format!("CAT {asset_id}"),
+        },
+        ClearSigningAsset::Nft {
+            launcher_id,
+            metadata,
+            metadata_updater_puzzle_hash,
+            royalty_puzzle_hash,
+            royalty_basis_points,
+        } => format!(
+            "NFT {launcher_id} (metadata hash {}, updater {metadata_updater_puzzle_hash}, royalty {royalty_basis_points} basis points to {royalty_puzzle_hash})",
+            metadata.tree_hash()
+        ),
+    }
+}
+
+fn format_parsed_asset(asset: &ParsedAsset) -> String {
+    match asset {
+        ParsedAsset::Xch(_) => "XCH".to_string(),
+        ParsedAsset::Cat(cat) => match cat.info.hidden_puzzle_hash {
+            Some(hidden_puzzle_hash) => {
+                format!(
+                    "CAT {} (revocable by {hidden_puzzle_hash})",
+                    cat.info.asset_id
+                )
+            }
+            None => format!("CAT {}", cat.info.asset_id),
+        },
+        ParsedAsset::Nft(nft) => format!("NFT {}", nft.info.launcher_id),
+        ParsedAsset::Bulletin(bulletin) => {
+            format!(
+                "Bulletin (hidden puzzle hash {})",
+                bulletin.hidden_puzzle_hash
+            )
+        }
+    }
+}
+
+fn format_custody(custody: &CustodyInfo) -> String {
+    match custody {
+        CustodyInfo::P2Singleton(info) => format!(
+            "p2 singleton {} (nonce {}, p2 puzzle hash {})",
+            info.launcher_id, info.nonce, info.p2_puzzle_hash
+        ),
+        CustodyInfo::P2ConditionsOrSingleton(info) => format!(
+            "p2 conditions or singleton {} (nonce {}, p2 puzzle hash {})",
+            info.launcher_id, info.nonce, info.p2_puzzle_hash
+        ),
+        CustodyInfo::DelegatedConditions(_) => "delegated conditions".to_string(),
+    }
+}
+
+fn custody_conditions(custody: &CustodyInfo) -> &[Condition] {
+    match custody {
+        CustodyInfo::P2Singleton(info) => &info.conditions,
+        CustodyInfo::P2ConditionsOrSingleton(info) => &info.conditions,
+        CustodyInfo::DelegatedConditions(conditions) => conditions,
+    }
+}
+
+fn format_clawback(info: &ClawbackInfo) -> String {
+    let path = match info.path {
+        ClawbackPath::Sender => "sender",
+        ClawbackPath::Receiver => "receiver",
+        ClawbackPath::PushThrough => "push through",
+    };
+
+    format!(
+        "{path} path (sender {}, receiver {}, seconds {})",
+        info.clawback.sender_puzzle_hash, info.clawback.receiver_puzzle_hash, info.clawback.seconds
+    )
+}
+
+fn format_transfer_type(transfer_type: &TransferType) -> &'static str {
+    match transfer_type {
+        TransferType::Sent => "sent",
+        TransferType::Burned => "burned",
+        TransferType::Offered => "offered",
+        TransferType::OfferPreSplit(_) => "offer pre-split",
+    }
+}
+
+fn format_condition(allocator: &mut Allocator, condition: &Condition) -> Result<String> {
+    let ptr = condition.to_clvm(allocator)?;
+    let condition = Condition::<Program>::from_clvm(allocator, ptr)?;
+    Ok(format!("{condition:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use chia_wallet_sdk::{
+        chia::puzzle_types::Memos,
+        driver::{InnerPuzzleSpend, Launcher, MipsSpend, MofN, P2Singleton, mips_puzzle_hash},
+        test::BlsPair,
+        types::{MessageFlags, MessageSide, conditions::SendMessage, puzzles::BlsMember},
+    };
+
+    use super::*;
+
+    /// Mints a vault custodied by `required` of the keys (or just the key if there's only one),
+    /// funds its p2 singleton with 1000 mojos, and spends both to send them to `puzzle_hash`.
+    fn vault_spend_bundle(
+        required: usize,
+        keys: &[BlsPair],
+        puzzle_hash: Bytes32,
+    ) -> Result<SpendBundle> {
+        let mut sim = Simulator::new();
+        let ctx = &mut SpendContext::new();
+
+        let members: Vec<BlsMember> = keys.iter().map(|key| BlsMember::new(key.pk)).collect();
+
+        let single_member = members.len() == 1;
+
+        let member_hashes: Vec<TreeHash> = members
+            .iter()
+            .map(|member| mips_puzzle_hash(0, vec![], member.curry_tree_hash(), single_member))
+            .collect();
+
+        let (custody_hash, custody_spend) = if single_member {
+            (member_hashes[0], None)
+        } else {
+            let custody = MofN::new(required, member_hashes.clone());
+
+            (
+                mips_puzzle_hash(0, vec![], custody.inner_puzzle_hash(), true),
+                Some(InnerPuzzleSpend::m_of_n(
+                    0,
+                    vec![],
+                    required,
+                    member_hashes.clone(),
+                )),
+            )
+        };
+
+        let alice = sim.bls(1001);
+        let (mut conditions, vault) =
+            Launcher::new(alice.coin.coin_id(), 1).mint_vault(ctx, custody_hash, ())?;
+
+        let p2_singleton = P2Singleton::new(vault.info.launcher_id, 0);
+        let p2_coin = Coin::new(alice.coin.coin_id(), p2_singleton.tree_hash().into(), 1000);
+        conditions.push(CreateCoin::new(p2_coin.puzzle_hash, 1000, Memos::None));
+
+        StandardLayer::new(alice.pk).spend(ctx, alice.coin, conditions)?;
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let delegated_spend =
+            ctx.delegated_spend(Conditions::new().create_coin(puzzle_hash, 1000, Memos::None))?;
+        let delegated_puzzle_hash = ctx.tree_hash(delegated_spend.puzzle);
+        let p2_spend = p2_singleton.spend(ctx, custody_hash.into(), 1, delegated_spend)?;
+        ctx.spend(p2_coin, p2_spend)?;
+
+        let coin_id = ctx.alloc(&p2_coin.coin_id())?;
+        let conditions = Conditions::new()
+            .with(SendMessage::new(
+                MessageFlags::PUZZLE.encode(MessageSide::Sender)
+                    | MessageFlags::COIN.encode(MessageSide::Receiver),
+                delegated_puzzle_hash.to_vec().into(),
+                vec![coin_id],
+            ))
+            .create_coin(custody_hash.into(), 1, Memos::None);
+
+        let mut mips_spend = MipsSpend::new(ctx.delegated_spend(conditions)?);
+
+        if let Some(custody_spend) = custody_spend {
+            mips_spend.members.insert(custody_hash, custody_spend);
+        }
+
+        for (member, &member_hash) in members.iter().zip(&member_hashes).take(required) {
+            let puzzle = ctx.curry(*member)?;
+
+            mips_spend.members.insert(
+                member_hash,
+                InnerPuzzleSpend::new(0, vec![], Spend::new(puzzle, NodePtr::NIL)),
+            );
+        }
+
+        vault.spend(ctx, &mips_spend)?;
+
+        let coin_spends = ctx.take();
+        let secret_keys: Vec<_> = keys[..required].iter().map(|key| key.sk.clone()).collect();
+        sim.spend_coins(coin_spends.clone(), &secret_keys)?;
+
+        Ok(SpendBundle::new(coin_spends, Signature::default()))
+    }
+
+    fn review(spend_bundle: &SpendBundle) -> Result<String> {
+        let args = ReviewVaultCommand {
+            input: None,
+            reveals: None,
+            launcher_id: None,
+            delegated_puzzle: None,
+            delegated_solution: "()".to_string(),
+        };
+
+        let mut report = String::new();
+        write_review(&mut report, &args, spend_bundle, &RevealsFile::default())?;
+        Ok(report)
+    }
+
+    #[test]
+    fn test_review_single_member_vault() -> Result<()> {
+        let keys = BlsPair::range_vec(1);
+        let spend_bundle = vault_spend_bundle(1, &keys, Bytes32::new([1; 32]))?;
+        let report = review(&spend_bundle)?;
+
+        assert_eq!(
+            report.lines().collect::<Vec<_>>(),
+            [
+                "Vault",
+                "  Launcher id: d671f9cf28d50a101a9bda2755e0069ddca931d125f6e651a5db28f6c0b1fe23",
+                "",
+                "Vault child",
+                "  Custody hash: 309c7c39e669c5a0d845be7b9e31e6e484ab724846cdae8504049ddb6ee2c242",
+                "  Amount: 1",
+                "",
+                "Drop coins",
+                "  None",
+                "",
+                "Spends",
+                "  Spend 0",
+                "    Asset: XCH",
+                "    Coin id: 59c8e3d29e81fe12131a0b14cc6624b1ab8513467fcd9175db9312c929a516c6",
+                "    Amount: 1000",
+                "    Custody: p2 singleton d671f9cf28d50a101a9bda2755e0069ddca931d125f6e651a5db28f6c0b1fe23 (nonce 0, p2 puzzle hash 140c8f090233765d7e9b98e49f25ed3c157e9b6849debf9d21a77cd362ecced6)",
+                "      CreateCoin(CreateCoin { puzzle_hash: 0101010101010101010101010101010101010101010101010101010101010101, amount: 1000, memos: None })",
+                "    Children:",
+                "      ee38be6a32c3587ca55f487bb0d8d50a17d1bd78cf20046b57c7e44927f37ba3 XCH 1000",
+                "        Transfer: sent",
+                "        P2 puzzle hash: 0101010101010101010101010101010101010101010101010101010101010101",
+                "",
+                "Issuances",
+                "  None",
+                "",
+                "Asset flows",
+                "  XCH",
+                "    Input: 1000",
+                "    Output: 1000",
+                "    Issued: 0",
+                "    Melted: 0",
+                "    Received: 0",
+                "    Paid: 0",
+                "    Unaccounted: 0",
+                "",
+                "Asserted payments",
+                "  None",
+                "",
+                "Received payments",
+                "  None",
+                "",
+                "External payments",
+                "  None",
+                "",
+                "Linked offer",
+                "  None",
+                "",
+                "Vault p2 puzzle hashes",
+                "  140c8f090233765d7e9b98e49f25ed3c157e9b6849debf9d21a77cd362ecced6",
+                "",
+                "Reserved fee: 0",
+                "Delegated puzzle hash to sign: b0df1c466cf544f9f992b7aabd4211dcb7a775cf388169cd55430a684808d6d2",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_review_m_of_n_vault() -> Result<()> {
+        let keys = BlsPair::range_vec(3);
+        let spend_bundle = vault_spend_bundle(2, &keys, Bytes32::new([1; 32]))?;
+        let report = review(&spend_bundle)?;
+
+        assert_eq!(
+            report.lines().collect::<Vec<_>>(),
+            [
+                "Vault",
+                "  Launcher id: d671f9cf28d50a101a9bda2755e0069ddca931d125f6e651a5db28f6c0b1fe23",
+                "",
+                "Vault child",
+                "  Custody hash: 23e34921f275ed48711c591478a803be63d9e591f510515f726a2cc958188e14",
+                "  Amount: 1",
+                "",
+                "Drop coins",
+                "  None",
+                "",
+                "Spends",
+                "  Spend 0",
+                "    Asset: XCH",
+                "    Coin id: 59c8e3d29e81fe12131a0b14cc6624b1ab8513467fcd9175db9312c929a516c6",
+                "    Amount: 1000",
+                "    Custody: p2 singleton d671f9cf28d50a101a9bda2755e0069ddca931d125f6e651a5db28f6c0b1fe23 (nonce 0, p2 puzzle hash 140c8f090233765d7e9b98e49f25ed3c157e9b6849debf9d21a77cd362ecced6)",
+                "      CreateCoin(CreateCoin { puzzle_hash: 0101010101010101010101010101010101010101010101010101010101010101, amount: 1000, memos: None })",
+                "    Children:",
+                "      ee38be6a32c3587ca55f487bb0d8d50a17d1bd78cf20046b57c7e44927f37ba3 XCH 1000",
+                "        Transfer: sent",
+                "        P2 puzzle hash: 0101010101010101010101010101010101010101010101010101010101010101",
+                "",
+                "Issuances",
+                "  None",
+                "",
+                "Asset flows",
+                "  XCH",
+                "    Input: 1000",
+                "    Output: 1000",
+                "    Issued: 0",
+                "    Melted: 0",
+                "    Received: 0",
+                "    Paid: 0",
+                "    Unaccounted: 0",
+                "",
+                "Asserted payments",
+                "  None",
+                "",
+                "Received payments",
+                "  None",
+                "",
+                "External payments",
+                "  None",
+                "",
+                "Linked offer",
+                "  None",
+                "",
+                "Vault p2 puzzle hashes",
+                "  140c8f090233765d7e9b98e49f25ed3c157e9b6849debf9d21a77cd362ecced6",
+                "",
+                "Reserved fee: 0",
+                "Delegated puzzle hash to sign: 51a3dfcda290499578388c1fa7ba1abe269274a06ca5c848bf79b0f7a42afe26",
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result, bail};
+use bip39::Mnemonic;
+use chia_wallet_sdk::{
+    chia::traits::Streamable,
+    clvmr::serde::{node_from_bytes_backrefs, serialized_length_from_bytes},
+    prelude::*,
+};
+use chialisp::classic::clvm_tools::binutils::assemble;
+
+use crate::args::{AggSigArgs, NetworkName};
+
+/// Reads the contents of a file, or stdin if no path (or `-`) is given.
+pub fn read_input(path: Option<&Path>) -> Result<String> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            fs::read_to_string(path).with_context(|| format!("could not read {}", path.display()))
+        }
+        _ => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+/// Parses a spend bundle that is either hex encoded or in the JSON format used by the RPC.
+pub fn parse_spend_bundle(input: &str) -> Result<SpendBundle> {
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        return serde_json::from_str(input).context("invalid spend bundle JSON");
+    }
+
+    let bytes = parse_hex(input).context("spend bundle is neither JSON nor hex")?;
+    Ok(SpendBundle::from_bytes(&bytes)?)
+}
+
+/// Parses either a single coin spend or a spend bundle, in hex or JSON, into its coin spends.
+pub fn parse_coin_spends(input: &str) -> Result<Vec<CoinSpend>> {
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        if let Ok(coin_spend) = serde_json::from_str::<CoinSpend>(input) {
+            return Ok(vec![coin_spend]);
+        }
+
+        let spend_bundle: SpendBundle =
+            serde_json::from_str(input).context("expected a coin spend or spend bundle")?;
+        return Ok(spend_bundle.coin_spends);
+    }
+
+    let bytes = parse_hex(input).context("input is neither JSON nor hex")?;
+
+    if let Ok(coin_spend) = CoinSpend::from_bytes(&bytes) {
+        return Ok(vec![coin_spend]);
+    }
+
+    let spend_bundle =
+        SpendBundle::from_bytes(&bytes).context("expected a coin spend or spend bundle")?;
+    Ok(spend_bundle.coin_spends)
+}
+
+/// Reads an offer string, which can either be passed directly or read from a file or stdin.
+pub fn read_offer(input: Option<&str>) -> Result<String> {
+    let text = match input {
+        Some(input) if input.trim().starts_with("offer1") => input.to_string(),
+        input => read_input(input.map(Path::new))?,
+    };
+
+    Ok(text.trim().to_string())
+}
+
+/// Reads a program argument, which is either the program itself or the path to a file containing it.
+pub fn read_program_text(input: &str) -> Result<String> {
+    let path = Path::new(input);
+
+    let text = if path.is_file() {
+        read_input(Some(path))?
+    } else {
+        input.to_string()
+    };
+
+    Ok(text.trim().to_string())
+}
+
+/// Parses a program from either serialized hex or assembly, with the following precedence:
+///
+/// 1. Input with a `0x` prefix is always serialized hex, and must be a valid program.
+/// 2. Decimal numbers are always assembly, so that `10` is the atom 10 rather than the byte 0x10.
+/// 3. Other hex is serialized hex if it deserializes into exactly one program.
+/// 4. Everything else, including hex that isn't a valid program, is assembly.
+pub fn parse_program(allocator: &mut Allocator, input: &str) -> Result<NodePtr> {
+    let text = read_program_text(input)?;
+    parse_program_text(allocator, &text)
+}
+
+fn parse_program_text(allocator: &mut Allocator, text: &str) -> Result<NodePtr> {
+    if text.starts_with("0x") {
+        let bytes = parse_hex(text).context("invalid hex")?;
+        return deserialize_program(allocator, &bytes)
+            .context("hex is not a valid serialized program");
+    }
+
+    let is_decimal = text
+        .trim_start_matches('-')
+        .bytes()
+        .all(|c| c.is_ascii_digit());
+
+    if !is_decimal
+        && let Ok(bytes) = parse_hex(text)
+        && let Ok(program) = deserialize_program(allocator, &bytes)
+    {
+        return Ok(program);
+    }
+
+    assemble(allocator, text).map_err(|error| anyhow::anyhow!("invalid assembly: {error}"))
+}
+
+/// Deserializes a program, making sure that there are no bytes left over.
+fn deserialize_program(allocator: &mut Allocator, bytes: &[u8]) -> Result<NodePtr> {
+    if serialized_length_from_bytes(bytes)? != bytes.len() as u64 {
+        bail!("unexpected bytes after the end of the program");
+    }
+
+    Ok(node_from_bytes_backrefs(allocator, bytes)?)
+}
+
+/// Parses a puzzle hash from either hex or a bech32 address.
+pub fn parse_puzzle_hash(input: &str) -> Result<Bytes32> {
+    if let Ok(address) = Address::decode(input) {
+        return Ok(address.puzzle_hash);
+    }
+
+    Bytes32::try_from(parse_hex(input)?).context("expected a 32 byte puzzle hash")
+}
+
+/// Reads and validates a mnemonic from a file, or stdin if no path is given.
+pub fn read_mnemonic(path: Option<&Path>) -> Result<Mnemonic> {
+    let text = read_input(path)?;
+    Mnemonic::from_str(text.trim()).context("invalid mnemonic")
+}
+
+/// Determines the signature additional data to use, either from a known network or a custom value.
+pub fn agg_sig_constants(args: &AggSigArgs) -> Result<AggSigConstants> {
+    if let Some(agg_sig_me) = &args.agg_sig_me {
+        let agg_sig_me = Bytes32::try_from(parse_hex(agg_sig_me)?)
+            .context("expected 32 bytes of AGG_SIG_ME additional data")?;
+        return Ok(AggSigConstants::new(agg_sig_me));
+    }
+
+    Ok(match args.network {
+        NetworkName::Mainnet => AggSigConstants::from(&*MAINNET_CONSTANTS),
+        NetworkName::Testnet11 => AggSigConstants::from(&*TESTNET11_CONSTANTS),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(allocator: &mut Allocator, text: &str) -> Result<SExp> {
+        let program = parse_program_text(allocator, text)?;
+        Ok(allocator.sexp(program))
+    }
+
+    fn atom(allocator: &mut Allocator, text: &str) -> Result<Vec<u8>> {
+        let program = parse_program_text(allocator, text)?;
+        Ok(allocator.atom(program).as_ref().to_vec())
+    }
+
+    #[test]
+    fn test_parse_prefixed_hex() -> Result<()> {
+        let mut allocator = Allocator::new();
+
+        assert_eq!(atom(&mut allocator, "0x80")?, Vec::<u8>::new());
+        assert!(matches!(
+            parse(&mut allocator, "0xff01ff0280")?,
+            SExp::Pair(..)
+        ));
+
+        // Prefixed hex must be a complete program, without falling back to assembly
+        assert!(parse(&mut allocator, "0xff01").is_err());
+        assert!(parse(&mut allocator, "0x8080").is_err());
+        assert!(parse(&mut allocator, "0xzz").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_unprefixed_hex() -> Result<()> {
+        let mut allocator = Allocator::new();
+
+        assert!(matches!(
+            parse(&mut allocator, "ff01ff0280")?,
+            SExp::Pair(..)
+        ));
+        assert_eq!(atom(&mut allocator, "8200ff")?, vec![0x00, 0xff]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_decimal_as_assembly() -> Result<()> {
+        let mut allocator = Allocator::new();
+
+        assert_eq!(atom(&mut allocator, "10")?, vec![10]);
+        assert_eq!(atom(&mut allocator, "80")?, vec![80]);
+        assert_eq!(atom(&mut allocator, "-1")?, vec![0xff]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid_hex_as_assembly() -> Result<()> {
+        let mut allocator = Allocator::new();
+
+        // Valid hex, but not a valid program, so it's a symbol
+        assert_eq!(atom(&mut allocator, "abcd")?, b"abcd".to_vec());
+
+        // Valid hex with bytes left over after the program
+        assert_eq!(atom(&mut allocator, "ff0180ab")?, b"ff0180ab".to_vec());
+
+        assert!(matches!(parse(&mut allocator, "(q . 1)")?, SExp::Pair(..)));
+
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 mod args;
 mod commands;
+mod input;
 
 use anyhow::Result;
 use clap::Parser;
 
-use crate::args::Command;
+use crate::args::{
+    ClvmCommand, Command, KeysCommand, OfferCommand, SimulatorCommand, SpendBundleCommand,
+    VaultCommand,
+};
 
 fn main() -> Result<()> {
     let command = Command::parse();
@@ -12,6 +16,30 @@ fn main() -> Result<()> {
     match command {
         Command::Encode(args) => commands::encode(&args)?,
         Command::Decode(args) => commands::decode(&args)?,
+        Command::SpendBundle(SpendBundleCommand::Inspect(args)) => {
+            commands::inspect_spend_bundle(&args)?;
+        }
+        Command::Offer(OfferCommand::Decode(args)) => commands::decode_offer(&args)?,
+        Command::Offer(OfferCommand::Encode(args)) => commands::encode_offer(&args)?,
+        Command::Offer(OfferCommand::Summary(args)) => commands::offer_summary(&args)?,
+        Command::Offer(OfferCommand::Normalize(args)) => commands::normalize_offer(&args)?,
+        Command::Clvm(ClvmCommand::Run(args)) => commands::run_program(&args)?,
+        Command::Clvm(ClvmCommand::Curry(args)) => commands::curry_program(&args)?,
+        Command::Clvm(ClvmCommand::Uncurry(args)) => commands::uncurry_program(&args)?,
+        Command::Clvm(ClvmCommand::TreeHash(args)) => commands::tree_hash_program(&args)?,
+        Command::Clvm(ClvmCommand::Assemble(args)) => commands::assemble_program(&args)?,
+        Command::Clvm(ClvmCommand::Disassemble(args)) => commands::disassemble_program(&args)?,
+        Command::Compile(args) => commands::compile(&args)?,
+        Command::Keys(KeysCommand::Generate(args)) => commands::generate_key(&args)?,
+        Command::Keys(KeysCommand::Show(args)) => commands::show_key(&args)?,
+        Command::Keys(KeysCommand::Derive(args)) => commands::derive_keys(&args)?,
+        Command::Keys(KeysCommand::Address(args)) => commands::key_address(&args)?,
+        Command::Simulator(SimulatorCommand::Serve(args)) => commands::serve_simulator(&args)?,
+        Command::Sign(args) => commands::sign_spend_bundle(&args)?,
+        Command::Verify(args) => commands::verify_spend_bundle(&args)?,
+        Command::Rpc(args) => commands::rpc(&args)?,
+        Command::Identify(args) => commands::identify(&args)?,
+        Command::Vault(VaultCommand::Review(args)) => commands::review_vault_transaction(&args)?,
     }
 
     Ok(())
@@ -55,4 +55,7 @@ pub enum ClientError {
 
     #[error("The peer is banned")]
     BannedPeer,
+
+    #[error("The request was rejected by the peer")]
+    Rejected,
 }
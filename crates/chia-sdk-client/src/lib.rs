@@ -1,6 +1,7 @@
 mod error;
 mod network;
 mod peer;
+mod peer_coin_source;
 mod rate_limiter;
 mod rate_limits;
 mod request_map;
@@ -9,6 +10,7 @@ mod tls;
 pub use error::*;
 pub use network::*;
 pub use peer::*;
+pub use peer_coin_source::*;
 pub use rate_limiter::*;
 pub use rate_limits::*;
 pub use tls::*;
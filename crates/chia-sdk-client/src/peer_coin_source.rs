@@ -0,0 +1,110 @@
+use chia_protocol::{Bytes32, Coin, CoinSpend, CoinState, CoinStateFilters};
+use chia_sdk_utils::CoinSource;
+
+use crate::{ClientError, Peer};
+
+/// A [`CoinSource`] backed by a full node [`Peer`], using the light wallet protocol.
+///
+/// The genesis challenge of the network is required, since it's used as the header hash
+/// when requesting coin states from the start of the blockchain.
+#[derive(Debug, Clone)]
+pub struct PeerCoinSource {
+    pub peer: Peer,
+    pub genesis_challenge: Bytes32,
+}
+
+impl PeerCoinSource {
+    pub fn new(peer: Peer, genesis_challenge: Bytes32) -> Self {
+        Self {
+            peer,
+            genesis_challenge,
+        }
+    }
+}
+
+impl CoinSource for PeerCoinSource {
+    type Error = ClientError;
+
+    async fn unspent_coins(
+        &self,
+        puzzle_hash: Bytes32,
+        include_hints: bool,
+    ) -> Result<Vec<Coin>, Self::Error> {
+        let mut coins = Vec::new();
+        let mut previous_height = None;
+        let mut header_hash = self.genesis_challenge;
+
+        loop {
+            let response = self
+                .peer
+                .request_puzzle_state(
+                    vec![puzzle_hash],
+                    previous_height,
+                    header_hash,
+                    CoinStateFilters::new(false, true, include_hints, 0),
+                    false,
+                )
+                .await?
+                .map_err(|_| ClientError::Rejected)?;
+
+            coins.extend(
+                response
+                    .coin_states
+                    .into_iter()
+                    .filter(|cs| cs.spent_height.is_none())
+                    .map(|cs| cs.coin),
+            );
+
+            if response.is_finished {
+                break;
+            }
+
+            previous_height = Some(response.height);
+            header_hash = response.header_hash;
+        }
+
+        Ok(coins)
+    }
+
+    async fn coin_spend(&self, coin_id: Bytes32) -> Result<Option<CoinSpend>, Self::Error> {
+        let response = self
+            .peer
+            .request_coin_state(vec![coin_id], None, self.genesis_challenge, false)
+            .await?
+            .map_err(|_| ClientError::Rejected)?;
+
+        let Some(coin_state) = response
+            .coin_states
+            .into_iter()
+            .find(|cs| cs.coin.coin_id() == coin_id)
+        else {
+            return Ok(None);
+        };
+
+        let Some(spent_height) = coin_state.spent_height else {
+            return Ok(None);
+        };
+
+        let response = self
+            .peer
+            .request_puzzle_and_solution(coin_id, spent_height)
+            .await?
+            .map_err(|_| ClientError::Rejected)?;
+
+        Ok(Some(CoinSpend::new(
+            coin_state.coin,
+            response.puzzle,
+            response.solution,
+        )))
+    }
+
+    async fn coin_states(&self, coin_ids: Vec<Bytes32>) -> Result<Vec<CoinState>, Self::Error> {
+        let response = self
+            .peer
+            .request_coin_state(coin_ids, None, self.genesis_challenge, false)
+            .await?
+            .map_err(|_| ClientError::Rejected)?;
+
+        Ok(response.coin_states)
+    }
+}
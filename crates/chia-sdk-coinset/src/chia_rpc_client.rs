@@ -5,8 +5,9 @@ use std::future::Future;
 use super::{
     AdditionsAndRemovalsResponse, BlockchainStateResponse, GetBlockRecordByHeightResponse,
     GetBlockRecordResponse, GetBlockRecordsResponse, GetBlockResponse, GetBlockSpendsResponse,
-    GetBlocksResponse, GetCoinRecordResponse, GetCoinRecordsResponse, GetMempoolItemResponse,
-    GetMempoolItemsResponse, GetNetworkInfoResponse, GetPuzzleAndSolutionResponse, PushTxResponse,
+    GetBlocksResponse, GetCoinRecordResponse, GetCoinRecordsResponse, GetFeeEstimateResponse,
+    GetMempoolItemResponse, GetMempoolItemsResponse, GetNetworkInfoResponse,
+    GetPuzzleAndSolutionResponse, PushTxResponse,
 };
 
 pub trait ChiaRpcClient {
@@ -321,6 +322,22 @@ pub trait ChiaRpcClient {
             }),
         )
     }
+
+    /// Estimates the fee required for a transaction with the given cost to be included
+    /// within each of the target times, in seconds.
+    fn get_fee_estimate(
+        &self,
+        target_times: Vec<u64>,
+        cost: u64,
+    ) -> impl Future<Output = Result<GetFeeEstimateResponse, Self::Error>> {
+        self.make_post_request(
+            "get_fee_estimate",
+            serde_json::json!({
+                "target_times": target_times,
+                "cost": cost,
+            }),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -786,4 +803,52 @@ mod tests {
         );
         assert_eq!(coin_solution.solution.to_bytes().unwrap(), hex!("ff0180"));
     }
+
+    #[tokio::test]
+    async fn test_get_fee_estimate() {
+        let mut client = MockRpcClient::new();
+
+        client.mock_response(
+            "http://api.example.com/get_fee_estimate",
+            r#"{
+                "current_fee_rate": 5.0,
+                "estimates": [0, 10000000, 50000000],
+                "fee_rate_last_block": 5.0,
+                "fees_last_block": 100000000,
+                "full_node_synced": true,
+                "last_block_cost": 20000000,
+                "last_peak_timestamp": 1737325862,
+                "last_tx_block_height": 6515819,
+                "mempool_fees": 10,
+                "mempool_max_size": 110000000000,
+                "mempool_size": 88022711,
+                "node_time_utc": 1737325870,
+                "num_spends": 2,
+                "peak_height": 6515821,
+                "target_times": [60, 120, 300],
+                "success": true
+            }"#,
+        );
+
+        let response = client
+            .get_fee_estimate(vec![60, 120, 300], 10_000_000)
+            .await
+            .unwrap();
+        assert!(response.success);
+        assert!(response.error.is_none());
+        assert_eq!(response.target_times, Some(vec![60, 120, 300]));
+        assert_eq!(response.full_node_synced, Some(true));
+
+        let fee_rates = response.fee_rates(10_000_000);
+        assert_eq!(fee_rates.len(), 3);
+        assert_eq!(fee_rates[0].fee(1_000_000), 0);
+        assert_eq!(fee_rates[1].fee(1_000_000), 1_000_000);
+        assert_eq!(fee_rates[2].fee(1_000_000), 5_000_000);
+
+        let requests = client.get_requests();
+        assert_eq!(
+            requests[0].1,
+            serde_json::json!({ "target_times": [60, 120, 300], "cost": 10_000_000 })
+        );
+    }
 }
@@ -2,12 +2,14 @@ mod chia_rpc_client;
 mod coinset_client;
 mod mock_client;
 mod models;
+mod rpc_coin_source;
 mod types;
 
 pub use chia_rpc_client::*;
 pub use coinset_client::*;
 pub use mock_client::*;
 pub use models::*;
+pub use rpc_coin_source::*;
 pub use types::*;
 
 #[cfg(all(
@@ -1,16 +1,17 @@
 use chia_protocol::{BlockRecord, Bytes32, CoinSpend, FullBlock};
-use serde::Deserialize;
+use chia_sdk_utils::FeeRate;
+use serde::{Deserialize, Serialize};
 
 use crate::{CoinRecord, MempoolItem};
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockchainStateResponse {
     pub blockchain_state: Option<BlockchainState>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockchainState {
     pub average_block_time: u64,
     pub block_max_cost: u64,
@@ -28,12 +29,19 @@ pub struct BlockchainState {
     pub sync: SyncState,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct MempoolMinFees {
     pub cost_5000000: u64,
 }
 
-#[derive(Deserialize, Debug, Clone, Copy)]
+impl MempoolMinFees {
+    /// The minimum fee rate required to enter the mempool when it's full.
+    pub fn fee_rate(&self) -> FeeRate {
+        FeeRate::new(self.cost_5000000, 5_000_000)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub struct SyncState {
     pub sync_mode: bool,
     pub sync_progress_height: u32,
@@ -41,7 +49,7 @@ pub struct SyncState {
     pub synced: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AdditionsAndRemovalsResponse {
     pub additions: Option<Vec<CoinRecord>>,
     pub removals: Option<Vec<CoinRecord>>,
@@ -49,14 +57,14 @@ pub struct AdditionsAndRemovalsResponse {
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBlockResponse {
     pub block: Option<FullBlock>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBlockRecordResponse {
     pub block_record: Option<BlockRecord>,
     pub error: Option<String>,
@@ -65,35 +73,35 @@ pub struct GetBlockRecordResponse {
 
 pub type GetBlockRecordByHeightResponse = GetBlockRecordResponse;
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBlockRecordsResponse {
     pub block_records: Option<Vec<BlockRecord>>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBlocksResponse {
     pub blocks: Option<Vec<FullBlock>>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBlockSpendsResponse {
     pub block_spends: Option<Vec<CoinSpend>>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetCoinRecordResponse {
     pub coin_record: Option<CoinRecord>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetCoinRecordsResponse {
     pub coin_records: Option<Vec<CoinRecord>>,
     pub error: Option<String>,
@@ -108,14 +116,14 @@ pub struct GetCoinRecordsResponse {
     pub next_cursor: Option<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetPuzzleAndSolutionResponse {
     pub coin_solution: Option<CoinSpend>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PushTxResponse {
     #[serde(default)]
     pub status: Option<String>,
@@ -123,7 +131,7 @@ pub struct PushTxResponse {
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetNetworkInfoResponse {
     pub network_name: Option<String>,
     pub network_prefix: Option<String>,
@@ -132,16 +140,44 @@ pub struct GetNetworkInfoResponse {
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetMempoolItemResponse {
     pub mempool_item: Option<MempoolItem>,
     pub error: Option<String>,
     pub success: bool,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetMempoolItemsResponse {
     pub mempool_items: Option<Vec<MempoolItem>>,
     pub error: Option<String>,
     pub success: bool,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetFeeEstimateResponse {
+    /// The estimated fee for each of the target times, for the cost in the request.
+    pub estimates: Option<Vec<u64>>,
+    pub target_times: Option<Vec<u64>>,
+    pub current_fee_rate: Option<f64>,
+    pub mempool_size: Option<u64>,
+    pub mempool_fees: Option<u64>,
+    pub mempool_max_size: Option<u64>,
+    pub full_node_synced: Option<bool>,
+    pub peak_height: Option<u32>,
+    pub last_block_cost: Option<u64>,
+    pub fees_last_block: Option<u64>,
+    pub error: Option<String>,
+    pub success: bool,
+}
+
+impl GetFeeEstimateResponse {
+    /// Converts the estimates into fee rates, given the cost that was used in the request.
+    pub fn fee_rates(&self, cost: u64) -> Vec<FeeRate> {
+        self.estimates
+            .iter()
+            .flatten()
+            .map(|&estimate| FeeRate::new(estimate, cost))
+            .collect()
+    }
+}
@@ -0,0 +1,277 @@
+use std::{collections::HashSet, future::Future};
+
+use chia_protocol::{Bytes32, Coin, CoinSpend, CoinState};
+use chia_sdk_utils::CoinSource;
+use thiserror::Error;
+
+use crate::{ChiaRpcClient, CoinRecord, GetCoinRecordsResponse};
+
+#[derive(Debug, Error)]
+pub enum RpcCoinSourceError<E> {
+    #[error("{0}")]
+    Client(E),
+
+    #[error("RPC error: {0}")]
+    Rpc(String),
+}
+
+/// A [`CoinSource`] backed by a [`ChiaRpcClient`], such as coinset.org or a full node.
+#[derive(Debug, Clone)]
+pub struct RpcCoinSource<C> {
+    pub client: C,
+}
+
+impl<C> RpcCoinSource<C>
+where
+    C: ChiaRpcClient,
+{
+    pub fn new(client: C) -> Self {
+        Self { client }
+    }
+
+    /// Fetches all pages of unspent coin records by puzzle hash, or by hint.
+    async fn unspent_coin_records(
+        &self,
+        puzzle_hash: Bytes32,
+        hint: bool,
+    ) -> Result<Vec<CoinRecord>, RpcCoinSourceError<C::Error>> {
+        self.paginate(|cursor| async move {
+            if hint {
+                self.client
+                    .get_coin_records_by_hint(puzzle_hash, None, None, Some(false), cursor)
+                    .await
+            } else {
+                self.client
+                    .get_coin_records_by_puzzle_hash(puzzle_hash, None, None, Some(false), cursor)
+                    .await
+            }
+        })
+        .await
+    }
+
+    /// Fetches all pages of coin records from a request, following the cursor of each response.
+    async fn paginate<F, Fut>(
+        &self,
+        mut request: F,
+    ) -> Result<Vec<CoinRecord>, RpcCoinSourceError<C::Error>>
+    where
+        F: FnMut(Option<String>) -> Fut,
+        Fut: Future<Output = Result<GetCoinRecordsResponse, C::Error>>,
+    {
+        let mut coin_records = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let response = request(cursor).await.map_err(RpcCoinSourceError::Client)?;
+
+            let GetCoinRecordsResponse {
+                coin_records: records,
+                error,
+                success,
+                next_cursor,
+                ..
+            } = response;
+
+            if !success {
+                return Err(RpcCoinSourceError::Rpc(error.unwrap_or_default()));
+            }
+
+            coin_records.extend(records.unwrap_or_default());
+
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+
+        Ok(coin_records)
+    }
+}
+
+impl<C> CoinSource for RpcCoinSource<C>
+where
+    C: ChiaRpcClient,
+{
+    type Error = RpcCoinSourceError<C::Error>;
+
+    async fn unspent_coins(
+        &self,
+        puzzle_hash: Bytes32,
+        include_hints: bool,
+    ) -> Result<Vec<Coin>, Self::Error> {
+        let mut coin_records = self.unspent_coin_records(puzzle_hash, false).await?;
+
+        if include_hints {
+            coin_records.extend(self.unspent_coin_records(puzzle_hash, true).await?);
+        }
+
+        let mut coin_ids = HashSet::new();
+
+        Ok(coin_records
+            .into_iter()
+            .filter(|record| !record.spent && coin_ids.insert(record.coin.coin_id()))
+            .map(|record| record.coin)
+            .collect())
+    }
+
+    async fn coin_spend(&self, coin_id: Bytes32) -> Result<Option<CoinSpend>, Self::Error> {
+        let response = self
+            .client
+            .get_coin_record_by_name(coin_id)
+            .await
+            .map_err(RpcCoinSourceError::Client)?;
+
+        if !response.success {
+            return Err(RpcCoinSourceError::Rpc(response.error.unwrap_or_default()));
+        }
+
+        let Some(coin_record) = response.coin_record else {
+            return Ok(None);
+        };
+
+        if !coin_record.spent {
+            return Ok(None);
+        }
+
+        let response = self
+            .client
+            .get_puzzle_and_solution(coin_id, Some(coin_record.spent_block_index))
+            .await
+            .map_err(RpcCoinSourceError::Client)?;
+
+        if !response.success {
+            return Err(RpcCoinSourceError::Rpc(response.error.unwrap_or_default()));
+        }
+
+        Ok(response.coin_solution)
+    }
+
+    async fn coin_states(&self, coin_ids: Vec<Bytes32>) -> Result<Vec<CoinState>, Self::Error> {
+        let coin_records = self
+            .paginate(|cursor| {
+                self.client.get_coin_records_by_names(
+                    coin_ids.clone(),
+                    None,
+                    None,
+                    Some(true),
+                    cursor,
+                )
+            })
+            .await?;
+
+        Ok(coin_records
+            .into_iter()
+            .map(|record| {
+                CoinState::new(
+                    record.coin,
+                    record.spent.then_some(record.spent_block_index),
+                    Some(record.confirmed_block_index),
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MockRpcClient;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unspent_coins() {
+        let mut client = MockRpcClient::new();
+
+        client.mock_response(
+            "http://api.example.com/get_coin_records_by_puzzle_hash",
+            r#"{
+                "coin_records": [
+                    {"coin": {"parent_coin_info": "0x0101010101010101010101010101010101010101010101010101010101010101", "puzzle_hash": "0x0202020202020202020202020202020202020202020202020202020202020202", "amount": 100}, "coinbase": false, "confirmed_block_index": 10, "spent": false, "spent_block_index": 0, "timestamp": 0},
+                    {"coin": {"parent_coin_info": "0x0303030303030303030303030303030303030303030303030303030303030303", "puzzle_hash": "0x0202020202020202020202020202020202020202020202020202020202020202", "amount": 200}, "coinbase": false, "confirmed_block_index": 10, "spent": true, "spent_block_index": 11, "timestamp": 0}
+                ],
+                "success": true
+            }"#,
+        );
+
+        client.mock_response(
+            "http://api.example.com/get_coin_records_by_hint",
+            r#"{
+                "coin_records": [
+                    {"coin": {"parent_coin_info": "0x0404040404040404040404040404040404040404040404040404040404040404", "puzzle_hash": "0x0505050505050505050505050505050505050505050505050505050505050505", "amount": 1}, "coinbase": false, "confirmed_block_index": 12, "spent": false, "spent_block_index": 0, "timestamp": 0}
+                ],
+                "success": true
+            }"#,
+        );
+
+        let source = RpcCoinSource::new(client);
+        let puzzle_hash = Bytes32::new([2; 32]);
+
+        let coins = source.unspent_coins(puzzle_hash, false).await.unwrap();
+        assert_eq!(
+            coins,
+            vec![Coin::new(Bytes32::new([1; 32]), puzzle_hash, 100)]
+        );
+
+        let coins = source.unspent_coins(puzzle_hash, true).await.unwrap();
+        assert_eq!(coins.len(), 2);
+        assert_eq!(coins[1].amount, 1);
+    }
+
+    #[tokio::test]
+    async fn test_coin_spend_unspent() {
+        let mut client = MockRpcClient::new();
+
+        client.mock_response(
+            "http://api.example.com/get_coin_record_by_name",
+            r#"{
+                "coin_record": {"coin": {"parent_coin_info": "0x0101010101010101010101010101010101010101010101010101010101010101", "puzzle_hash": "0x0202020202020202020202020202020202020202020202020202020202020202", "amount": 100}, "coinbase": false, "confirmed_block_index": 10, "spent": false, "spent_block_index": 0, "timestamp": 0},
+                "success": true
+            }"#,
+        );
+
+        let source = RpcCoinSource::new(client);
+        let coin_spend = source.coin_spend(Bytes32::default()).await.unwrap();
+        assert!(coin_spend.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_coin_states() {
+        let mut client = MockRpcClient::new();
+
+        client.mock_response(
+            "http://api.example.com/get_coin_records_by_names",
+            r#"{
+                "coin_records": [
+                    {"coin": {"parent_coin_info": "0x0101010101010101010101010101010101010101010101010101010101010101", "puzzle_hash": "0x0202020202020202020202020202020202020202020202020202020202020202", "amount": 100}, "coinbase": false, "confirmed_block_index": 10, "spent": false, "spent_block_index": 0, "timestamp": 0},
+                    {"coin": {"parent_coin_info": "0x0303030303030303030303030303030303030303030303030303030303030303", "puzzle_hash": "0x0202020202020202020202020202020202020202020202020202020202020202", "amount": 200}, "coinbase": false, "confirmed_block_index": 10, "spent": true, "spent_block_index": 11, "timestamp": 0}
+                ],
+                "success": true
+            }"#,
+        );
+
+        let source = RpcCoinSource::new(client);
+        let coin_states = source.coin_states(vec![Bytes32::default()]).await.unwrap();
+
+        assert_eq!(coin_states.len(), 2);
+        assert_eq!(coin_states[0].spent_height, None);
+        assert_eq!(coin_states[0].created_height, Some(10));
+        assert_eq!(coin_states[1].spent_height, Some(11));
+    }
+
+    #[tokio::test]
+    async fn test_coin_spend_error() {
+        let mut client = MockRpcClient::new();
+
+        client.mock_response(
+            "http://api.example.com/get_coin_record_by_name",
+            r#"{"error": "coin not found", "success": false}"#,
+        );
+
+        let source = RpcCoinSource::new(client);
+
+        assert!(matches!(
+            source.coin_spend(Bytes32::default()).await,
+            Err(RpcCoinSourceError::Rpc(error)) if error == "coin not found"
+        ));
+    }
+}
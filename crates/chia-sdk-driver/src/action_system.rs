@@ -1,23 +1,39 @@
 mod action;
 mod asset;
+mod batch;
+mod coin_source_selection;
+mod custody;
 mod deltas;
+mod fee_estimation;
 mod fungible_spends;
 mod id;
 mod output;
+mod preview;
 mod relation;
 mod singleton_spends;
 mod spend_kind;
 mod spendable_asset;
 mod spends;
+mod vault_spend;
 
 pub use action::*;
 pub use asset::*;
+pub use batch::*;
+pub use custody::*;
 pub use deltas::*;
 pub use fungible_spends::*;
 pub use id::*;
 pub use output::*;
+pub use preview::*;
 pub use relation::*;
 pub use singleton_spends::*;
 pub use spend_kind::*;
 pub use spendable_asset::*;
 pub use spends::*;
+pub use vault_spend::*;
+
+#[cfg(feature = "chip-0035")]
+mod datastore_spend;
+
+#[cfg(feature = "chip-0035")]
+pub use datastore_spend::*;
@@ -6,12 +6,18 @@ use chia_puzzle_types::{
 use hex_literal::hex;
 
 use crate::{
-    CreateDidAction, Delta, Deltas, DriverError, FeeAction, HashedPtr, Id, IssueCatAction,
-    MeltSingletonAction, MintNftAction, MintOptionAction, OptionType, RunTailAction, SendAction,
-    SettleAction, Spend, SpendContext, Spends, TailIssuance, TransferNftById, UpdateDidAction,
-    UpdateNftAction,
+    CancelOfferAction, ClawbackPath, ClawbackV2, ConsolidateAction, CreateDidAction,
+    CreateStreamAction, Delta, Deltas, DriverError, ExerciseOptionAction, FeeAction, HashedPtr, Id,
+    IssueCatAction, MeltSingletonAction, MintNftAction, MintNftsAction, MintOptionAction, NftMint,
+    Offer, OptionType, OptionUnderlying, ReclaimOptionUnderlyingAction, RunTailAction, SendAction,
+    SendWithClawbackAction, SettleAction, Spend, SpendClawbackAction, SpendContext,
+    SpendStreamAction, SpendableAsset, Spends, SplitAction, StreamedAsset, TailIssuance,
+    TransferNftById, UpdateDidAction, UpdateNftAction,
 };
 
+#[cfg(feature = "chip-0035")]
+use crate::{DataStoreMetadata, DelegatedPuzzle, MintDataStoreAction, UpdateDataStoreAction};
+
 pub const BURN_PUZZLE_HASH: Bytes32 = Bytes32::new(hex!(
     "000000000000000000000000000000000000000000000000000000000000dead"
 ));
@@ -19,15 +25,29 @@ pub const BURN_PUZZLE_HASH: Bytes32 = Bytes32::new(hex!(
 #[derive(Debug, Clone)]
 pub enum Action {
     Send(SendAction),
+    SendWithClawback(SendWithClawbackAction),
+    SpendClawback(Box<SpendClawbackAction>),
     Settle(SettleAction),
     CreateDid(CreateDidAction),
     UpdateDid(UpdateDidAction),
     MintNft(MintNftAction),
+    MintNfts(MintNftsAction),
     UpdateNft(UpdateNftAction),
     IssueCat(IssueCatAction),
     RunTail(RunTailAction),
     MintOption(MintOptionAction),
+    ExerciseOption(Box<ExerciseOptionAction>),
+    ReclaimOptionUnderlying(Box<ReclaimOptionUnderlyingAction>),
+    CreateStream(CreateStreamAction),
+    #[cfg(feature = "chip-0035")]
+    MintDataStore(MintDataStoreAction),
+    #[cfg(feature = "chip-0035")]
+    UpdateDataStore(UpdateDataStoreAction),
+    SpendStream(Box<SpendStreamAction>),
     MeltSingleton(MeltSingletonAction),
+    Consolidate(ConsolidateAction),
+    Split(SplitAction),
+    CancelOffer(Box<CancelOfferAction>),
     Fee(FeeAction),
 }
 
@@ -36,6 +56,36 @@ impl Action {
         Self::Send(SendAction::new(id, puzzle_hash, amount, memos))
     }
 
+    pub fn send_with_clawback(
+        id: Id,
+        receiver_puzzle_hash: Bytes32,
+        seconds: u64,
+        amount: u64,
+    ) -> Self {
+        Self::SendWithClawback(SendWithClawbackAction::new(
+            id,
+            receiver_puzzle_hash,
+            seconds,
+            amount,
+        ))
+    }
+
+    pub fn claw_back(clawback: ClawbackV2, asset: SpendableAsset) -> Self {
+        Self::SpendClawback(Box::new(SpendClawbackAction::new(
+            clawback,
+            ClawbackPath::Sender,
+            asset,
+        )))
+    }
+
+    pub fn claim_clawback(clawback: ClawbackV2, asset: SpendableAsset) -> Self {
+        Self::SpendClawback(Box::new(SpendClawbackAction::new(
+            clawback,
+            ClawbackPath::Receiver,
+            asset,
+        )))
+    }
+
     pub fn settle(id: Id, notarized_payment: NotarizedPayment) -> Self {
         Self::Settle(SettleAction::new(id, notarized_payment))
     }
@@ -129,6 +179,11 @@ impl Action {
         ))
     }
 
+    pub fn mint_nfts(parent_id: Id, mints: Vec<NftMint>) -> Self {
+        let mint_total = mints.len();
+        Self::MintNfts(MintNftsAction::new(parent_id, mints, 0, mint_total))
+    }
+
     pub fn mint_empty_nft() -> Self {
         Self::mint_nft(HashedPtr::NIL, Bytes32::default(), Bytes32::default(), 0, 1)
     }
@@ -215,10 +270,110 @@ impl Action {
         ))
     }
 
+    pub fn exercise_option(
+        id: Id,
+        underlying: OptionUnderlying,
+        underlying_asset: SpendableAsset,
+        amount: u64,
+    ) -> Self {
+        Self::ExerciseOption(Box::new(ExerciseOptionAction::new(
+            id,
+            underlying,
+            underlying_asset,
+            amount,
+        )))
+    }
+
+    /// Transfers an option contract to a new owner. This is the same as [`Action::send`], since
+    /// the option is a singleton and keeps its launcher id as the [`Id`].
+    pub fn transfer_option(id: Id, puzzle_hash: Bytes32, amount: u64, memos: Memos) -> Self {
+        Self::send(id, puzzle_hash, amount, memos)
+    }
+
+    pub fn reclaim_option_underlying(underlying: OptionUnderlying, asset: SpendableAsset) -> Self {
+        Self::ReclaimOptionUnderlying(Box::new(ReclaimOptionUnderlyingAction::new(
+            underlying, asset,
+        )))
+    }
+
+    pub fn create_stream(
+        id: Id,
+        recipient: Bytes32,
+        clawback_puzzle_hash: Option<Bytes32>,
+        start_time: u64,
+        end_time: u64,
+        amount: u64,
+    ) -> Self {
+        Self::CreateStream(CreateStreamAction::new(
+            id,
+            recipient,
+            clawback_puzzle_hash,
+            start_time,
+            end_time,
+            amount,
+        ))
+    }
+
+    pub fn claim_stream(stream: StreamedAsset, payment_time: u64) -> Self {
+        Self::SpendStream(Box::new(SpendStreamAction::new(
+            stream,
+            payment_time,
+            false,
+        )))
+    }
+
+    pub fn claw_back_stream(stream: StreamedAsset, payment_time: u64) -> Self {
+        Self::SpendStream(Box::new(SpendStreamAction::new(stream, payment_time, true)))
+    }
+
+    #[cfg(feature = "chip-0035")]
+    pub fn mint_datastore(
+        metadata: DataStoreMetadata,
+        delegated_puzzles: Vec<DelegatedPuzzle>,
+    ) -> Self {
+        Self::MintDataStore(MintDataStoreAction::new(metadata, delegated_puzzles))
+    }
+
+    #[cfg(feature = "chip-0035")]
+    pub fn update_datastore(
+        id: Id,
+        new_metadata: Option<DataStoreMetadata>,
+        new_delegated_puzzles: Option<Vec<DelegatedPuzzle>>,
+    ) -> Self {
+        Self::UpdateDataStore(UpdateDataStoreAction::new(
+            id,
+            new_metadata,
+            None,
+            new_delegated_puzzles,
+        ))
+    }
+
+    #[cfg(feature = "chip-0035")]
+    pub fn transfer_datastore(id: Id, owner_puzzle_hash: Bytes32) -> Self {
+        Self::UpdateDataStore(UpdateDataStoreAction::new(
+            id,
+            None,
+            Some(owner_puzzle_hash),
+            None,
+        ))
+    }
+
     pub fn melt_singleton(id: Id, amount: u64) -> Self {
         Self::MeltSingleton(MeltSingletonAction::new(id, amount))
     }
 
+    pub fn consolidate(id: Id, max_coins: usize) -> Self {
+        Self::Consolidate(ConsolidateAction::new(id, max_coins))
+    }
+
+    pub fn split(id: Id, puzzle_hash: Bytes32, amount: u64, count: usize) -> Self {
+        Self::Split(SplitAction::new(id, puzzle_hash, amount, count))
+    }
+
+    pub fn cancel_offer(offer: Offer) -> Self {
+        Self::CancelOffer(Box::new(CancelOfferAction::new(offer)))
+    }
+
     pub fn fee(amount: u64) -> Self {
         Self::Fee(FeeAction::new(amount))
     }
@@ -239,15 +394,29 @@ impl SpendAction for Action {
     fn calculate_delta(&self, deltas: &mut Deltas, index: usize) {
         match self {
             Action::Send(action) => action.calculate_delta(deltas, index),
+            Action::SendWithClawback(action) => action.calculate_delta(deltas, index),
+            Action::SpendClawback(action) => action.calculate_delta(deltas, index),
             Action::Settle(action) => action.calculate_delta(deltas, index),
             Action::CreateDid(action) => action.calculate_delta(deltas, index),
             Action::UpdateDid(action) => action.calculate_delta(deltas, index),
             Action::MintNft(action) => action.calculate_delta(deltas, index),
+            Action::MintNfts(action) => action.calculate_delta(deltas, index),
             Action::UpdateNft(action) => action.calculate_delta(deltas, index),
             Action::IssueCat(action) => action.calculate_delta(deltas, index),
             Action::RunTail(action) => action.calculate_delta(deltas, index),
             Action::MintOption(action) => action.calculate_delta(deltas, index),
+            Action::ExerciseOption(action) => action.calculate_delta(deltas, index),
+            Action::ReclaimOptionUnderlying(action) => action.calculate_delta(deltas, index),
+            Action::CreateStream(action) => action.calculate_delta(deltas, index),
+            #[cfg(feature = "chip-0035")]
+            Action::MintDataStore(action) => action.calculate_delta(deltas, index),
+            #[cfg(feature = "chip-0035")]
+            Action::UpdateDataStore(action) => action.calculate_delta(deltas, index),
+            Action::SpendStream(action) => action.calculate_delta(deltas, index),
             Action::MeltSingleton(action) => action.calculate_delta(deltas, index),
+            Action::Consolidate(action) => action.calculate_delta(deltas, index),
+            Action::Split(action) => action.calculate_delta(deltas, index),
+            Action::CancelOffer(action) => action.calculate_delta(deltas, index),
             Action::Fee(action) => action.calculate_delta(deltas, index),
         }
     }
@@ -260,15 +429,29 @@ impl SpendAction for Action {
     ) -> Result<(), DriverError> {
         match self {
             Action::Send(action) => action.spend(ctx, spends, index),
+            Action::SendWithClawback(action) => action.spend(ctx, spends, index),
+            Action::SpendClawback(action) => action.spend(ctx, spends, index),
             Action::Settle(action) => action.spend(ctx, spends, index),
             Action::CreateDid(action) => action.spend(ctx, spends, index),
             Action::UpdateDid(action) => action.spend(ctx, spends, index),
             Action::MintNft(action) => action.spend(ctx, spends, index),
+            Action::MintNfts(action) => action.spend(ctx, spends, index),
             Action::UpdateNft(action) => action.spend(ctx, spends, index),
             Action::IssueCat(action) => action.spend(ctx, spends, index),
             Action::RunTail(action) => action.spend(ctx, spends, index),
             Action::MintOption(action) => action.spend(ctx, spends, index),
+            Action::ExerciseOption(action) => action.spend(ctx, spends, index),
+            Action::ReclaimOptionUnderlying(action) => action.spend(ctx, spends, index),
+            Action::CreateStream(action) => action.spend(ctx, spends, index),
+            #[cfg(feature = "chip-0035")]
+            Action::MintDataStore(action) => action.spend(ctx, spends, index),
+            #[cfg(feature = "chip-0035")]
+            Action::UpdateDataStore(action) => action.spend(ctx, spends, index),
+            Action::SpendStream(action) => action.spend(ctx, spends, index),
             Action::MeltSingleton(action) => action.spend(ctx, spends, index),
+            Action::Consolidate(action) => action.spend(ctx, spends, index),
+            Action::Split(action) => action.spend(ctx, spends, index),
+            Action::CancelOffer(action) => action.spend(ctx, spends, index),
             Action::Fee(action) => action.spend(ctx, spends, index),
         }
     }
@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+
+use chia_protocol::CoinSpend;
+
+use crate::{DriverError, SpendContext, spend_bundle_cost};
+
+/// A spend bundle that was built for a subset of items by [`build_batches`].
+#[derive(Debug, Clone)]
+pub struct Batch<T> {
+    pub coin_spends: Vec<CoinSpend>,
+    pub value: T,
+}
+
+/// Builds a separate spend bundle for each batch of up to `max_items` items, so that operations
+/// which are too large for a single transaction can be spread out over several of them.
+///
+/// The `build` function should apply the actions for the items and finish the spends, leaving the
+/// coin spends in the [`SpendContext`]. If the cost of the resulting spend bundle exceeds `max_cost`,
+/// the coin spends are discarded and the batch is split in half, so the function may be called again
+/// with a subset of items that it has already seen.
+///
+/// Any coin spends that were already in the [`SpendContext`] are left there, and aren't included in the batches.
+pub fn build_batches<I, T, F>(
+    ctx: &mut SpendContext,
+    items: Vec<I>,
+    max_items: usize,
+    max_cost: u64,
+    mut build: F,
+) -> Result<Vec<Batch<T>>, DriverError>
+where
+    F: FnMut(&mut SpendContext, &[I]) -> Result<T, DriverError>,
+{
+    let pending = ctx.take();
+
+    let mut queue: VecDeque<Vec<I>> = VecDeque::new();
+    let mut items = items.into_iter().peekable();
+
+    while items.peek().is_some() {
+        queue.push_back(items.by_ref().take(max_items.max(1)).collect());
+    }
+
+    let mut batches = Vec::new();
+
+    let result = loop {
+        let Some(mut batch) = queue.pop_front() else {
+            break Ok(batches);
+        };
+
+        let value = match build(ctx, &batch) {
+            Ok(value) => value,
+            Err(error) => break Err(error),
+        };
+
+        let coin_spends = ctx.take();
+
+        let cost = match spend_bundle_cost(&coin_spends) {
+            Ok(cost) => cost,
+            Err(error) => break Err(error),
+        };
+
+        if cost <= max_cost {
+            batches.push(Batch { coin_spends, value });
+            continue;
+        }
+
+        if batch.len() == 1 {
+            break Err(DriverError::ExceededMaxCost);
+        }
+
+        let rest = batch.split_off(batch.len() / 2);
+        queue.push_front(rest);
+        queue.push_front(batch);
+    };
+
+    ctx.take();
+
+    for coin_spend in pending {
+        ctx.insert(coin_spend);
+    }
+
+    result
+}
@@ -0,0 +1,563 @@
+use std::{collections::HashSet, fmt::Display};
+
+use chia_protocol::{Bytes32, Coin};
+use chia_puzzle_types::cat::CatArgs;
+use chia_sdk_utils::{CoinSource, select_coins};
+use clvm_traits::ToClvm;
+use clvmr::NodePtr;
+use indexmap::IndexMap;
+
+use crate::{
+    Action, Cat, Deltas, Did, DriverError, FungibleSpends, Id, Nft, OptionContract, Puzzle,
+    SpendContext, SpendableAsset, Spends, Unfinished,
+};
+
+impl Spends<Unfinished> {
+    /// Selects the inputs required by the actions from a [`CoinSource`], and then applies the actions.
+    ///
+    /// See [`Spends::select_inputs`] for how the inputs are selected.
+    pub async fn apply_with_source<S>(
+        &mut self,
+        ctx: &mut SpendContext,
+        actions: &[Action],
+        source: &S,
+        p2_puzzle_hash: Bytes32,
+    ) -> Result<Deltas, DriverError>
+    where
+        S: CoinSource,
+        S::Error: Display,
+    {
+        let deltas = Deltas::from_actions(actions);
+        self.select_inputs(ctx, &deltas, source, p2_puzzle_hash)
+            .await?;
+        self.apply(ctx, actions)
+    }
+
+    /// Adds the coins owned by the p2 puzzle hash that are required to cover the [`Deltas`],
+    /// taking into account the assets that have already been added.
+    ///
+    /// XCH and CATs are selected by amount. If there are no CAT coins for an asset id, NFTs, DIDs
+    /// and option contracts are found by their launcher id among the coins hinted with the p2 puzzle
+    /// hash instead. Revocable CATs aren't selected, since their puzzle hash can't be known ahead
+    /// of time.
+    pub async fn select_inputs<S>(
+        &mut self,
+        ctx: &mut SpendContext,
+        deltas: &Deltas,
+        source: &S,
+        p2_puzzle_hash: Bytes32,
+    ) -> Result<(), DriverError>
+    where
+        S: CoinSource,
+        S::Error: Display,
+    {
+        let mut singletons = None;
+
+        for &id in deltas.ids() {
+            let delta = deltas.get(&id).copied().unwrap_or_default();
+
+            match id {
+                Id::Xch => {
+                    let selected = self.xch.selected_amount();
+
+                    let required = delta.output.saturating_sub(delta.input);
+
+                    if required <= selected
+                        && (!deltas.is_needed(&id) || !self.xch.items.is_empty())
+                    {
+                        continue;
+                    }
+
+                    let added = self
+                        .xch
+                        .items
+                        .iter()
+                        .map(|item| item.asset.coin_id())
+                        .collect();
+
+                    let coins = unspent_coins(source, p2_puzzle_hash, &added).await?;
+
+                    for coin in select_coins(coins, required.saturating_sub(selected))? {
+                        self.add(coin);
+                    }
+                }
+                Id::Existing(asset_id) => {
+                    if self.nfts.contains_key(&id)
+                        || self.dids.contains_key(&id)
+                        || self.options.contains_key(&id)
+                    {
+                        continue;
+                    }
+
+                    let cats = self.cats.get(&id).map_or(&[][..], |cat| &cat.items[..]);
+                    let has_cats = !cats.is_empty();
+
+                    let selected = self
+                        .cats
+                        .get(&id)
+                        .map_or(0, FungibleSpends::selected_amount);
+
+                    let required = delta.output.saturating_sub(delta.input);
+
+                    if required <= selected && (!deltas.is_needed(&id) || has_cats) {
+                        continue;
+                    }
+
+                    let added = cats.iter().map(|item| item.asset.coin.coin_id()).collect();
+
+                    let cat_puzzle_hash =
+                        CatArgs::curry_tree_hash(asset_id, p2_puzzle_hash.into()).into();
+
+                    let coins = unspent_coins(source, cat_puzzle_hash, &added).await?;
+
+                    // Finding a singleton requires fetching the parent spend of every hinted coin, so
+                    // it's only done if the asset id isn't a CAT, and at most once.
+                    if coins.is_empty() && !has_cats {
+                        if singletons.is_none() {
+                            singletons =
+                                Some(fetch_hinted_singletons(ctx, source, p2_puzzle_hash).await?);
+                        }
+
+                        if let Some(singleton) = singletons
+                            .as_mut()
+                            .and_then(|singletons| singletons.shift_remove(&asset_id))
+                        {
+                            match singleton {
+                                SpendableAsset::Nft(nft) => self.add(nft),
+                                SpendableAsset::Did(did) => self.add(did),
+                                SpendableAsset::Option(option) => self.add(option),
+                                SpendableAsset::Xch(_) | SpendableAsset::Cat(_) => {}
+                            }
+                            continue;
+                        }
+                    }
+
+                    for coin in select_coins(coins, required.saturating_sub(selected))? {
+                        let cat = fetch_cat(ctx, source, coin).await?;
+                        self.add(cat);
+                    }
+                }
+                Id::New(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn unspent_coins<S>(
+    source: &S,
+    puzzle_hash: Bytes32,
+    added: &HashSet<Bytes32>,
+) -> Result<Vec<Coin>, DriverError>
+where
+    S: CoinSource,
+    S::Error: Display,
+{
+    let coins = source
+        .unspent_coins(puzzle_hash, false)
+        .await
+        .map_err(|error| DriverError::CoinSource(error.to_string()))?;
+
+    Ok(coins
+        .into_iter()
+        .filter(|coin| !added.contains(&coin.coin_id()))
+        .collect())
+}
+
+async fn fetch_parent_spend<S>(
+    ctx: &mut SpendContext,
+    source: &S,
+    coin: Coin,
+) -> Result<Option<(Coin, Puzzle, NodePtr)>, DriverError>
+where
+    S: CoinSource,
+    S::Error: Display,
+{
+    let Some(parent_spend) = source
+        .coin_spend(coin.parent_coin_info)
+        .await
+        .map_err(|error| DriverError::CoinSource(error.to_string()))?
+    else {
+        return Ok(None);
+    };
+
+    let parent_puzzle = parent_spend.puzzle_reveal.to_clvm(&mut **ctx)?;
+    let parent_puzzle = Puzzle::parse(ctx, parent_puzzle);
+    let parent_solution = parent_spend.solution.to_clvm(&mut **ctx)?;
+
+    Ok(Some((parent_spend.coin, parent_puzzle, parent_solution)))
+}
+
+async fn fetch_cat<S>(ctx: &mut SpendContext, source: &S, coin: Coin) -> Result<Cat, DriverError>
+where
+    S: CoinSource,
+    S::Error: Display,
+{
+    let (parent_coin, parent_puzzle, parent_solution) = fetch_parent_spend(ctx, source, coin)
+        .await?
+        .ok_or(DriverError::MissingSpend)?;
+
+    Cat::parse_children(ctx, parent_coin, parent_puzzle, parent_solution)?
+        .into_iter()
+        .flatten()
+        .find(|cat| cat.coin == coin)
+        .ok_or(DriverError::MissingChild)
+}
+
+/// Parses the NFTs, DIDs and option contracts owned by the p2 puzzle hash, keyed by their launcher id.
+///
+/// Anyone can hint a coin to the p2 puzzle hash, so coins that can't be parsed are skipped.
+async fn fetch_hinted_singletons<S>(
+    ctx: &mut SpendContext,
+    source: &S,
+    p2_puzzle_hash: Bytes32,
+) -> Result<IndexMap<Bytes32, SpendableAsset>, DriverError>
+where
+    S: CoinSource,
+    S::Error: Display,
+{
+    let coins = source
+        .unspent_coins(p2_puzzle_hash, true)
+        .await
+        .map_err(|error| DriverError::CoinSource(error.to_string()))?;
+
+    let mut singletons = IndexMap::new();
+
+    for coin in coins {
+        if coin.puzzle_hash == p2_puzzle_hash || coin.amount % 2 == 0 {
+            continue;
+        }
+
+        let Some((parent_coin, parent_puzzle, parent_solution)) =
+            fetch_parent_spend(ctx, source, coin).await?
+        else {
+            continue;
+        };
+
+        if let Ok(Some(nft)) = Nft::parse_child(ctx, parent_coin, parent_puzzle, parent_solution)
+            && nft.coin == coin
+            && nft.info.p2_puzzle_hash == p2_puzzle_hash
+        {
+            singletons.insert(nft.info.launcher_id, SpendableAsset::Nft(nft));
+            continue;
+        }
+
+        if let Ok(Some(did)) =
+            Did::parse_child(ctx, parent_coin, parent_puzzle, parent_solution, coin)
+            && did.info.p2_puzzle_hash == p2_puzzle_hash
+        {
+            singletons.insert(did.info.launcher_id, SpendableAsset::Did(did));
+            continue;
+        }
+
+        if let Ok(Some(option)) =
+            OptionContract::parse_child(ctx, parent_coin, parent_puzzle, parent_solution)
+            && option.coin == coin
+            && option.info.p2_puzzle_hash == p2_puzzle_hash
+        {
+            singletons.insert(option.info.launcher_id, SpendableAsset::Option(option));
+        }
+    }
+
+    Ok(singletons)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, convert::Infallible, slice};
+
+    use anyhow::Result;
+    use chia_protocol::{CoinSpend, CoinState};
+    use chia_puzzle_types::Memos;
+    use chia_sdk_test::Simulator;
+    use chia_sdk_utils::CoinSelectionError;
+    use indexmap::indexmap;
+
+    use crate::{OptionType, Relation};
+
+    use super::*;
+
+    /// Counts the parent spends fetched from the simulator.
+    struct CountingSource<'a> {
+        sim: &'a Simulator,
+        coin_spends: Cell<usize>,
+    }
+
+    impl CoinSource for CountingSource<'_> {
+        type Error = Infallible;
+
+        async fn unspent_coins(
+            &self,
+            puzzle_hash: Bytes32,
+            include_hints: bool,
+        ) -> Result<Vec<Coin>, Self::Error> {
+            CoinSource::unspent_coins(self.sim, puzzle_hash, include_hints).await
+        }
+
+        async fn coin_spend(&self, coin_id: Bytes32) -> Result<Option<CoinSpend>, Self::Error> {
+            self.coin_spends.set(self.coin_spends.get() + 1);
+            CoinSource::coin_spend(self.sim, coin_id).await
+        }
+
+        async fn coin_states(&self, coin_ids: Vec<Bytes32>) -> Result<Vec<CoinState>, Self::Error> {
+            CoinSource::coin_states(self.sim, coin_ids).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_with_source() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(10);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::single_issue_cat(None, 5), Action::mint_empty_nft()],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let asset_id = outputs.cats[&Id::New(0)][0].info.asset_id;
+        let launcher_id = outputs.nfts[&Id::New(1)].info.launcher_id;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        let deltas = spends
+            .apply_with_source(
+                &mut ctx,
+                &[
+                    Action::send(Id::Existing(asset_id), bob.puzzle_hash, 3, Memos::None),
+                    Action::send(Id::Existing(launcher_id), bob.puzzle_hash, 1, Memos::None),
+                    Action::fee(1),
+                ],
+                &sim,
+                alice.puzzle_hash,
+            )
+            .await?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let nft = outputs.nfts[&Id::Existing(launcher_id)];
+        assert_eq!(nft.info.p2_puzzle_hash, bob.puzzle_hash);
+        assert_ne!(sim.coin_state(nft.coin.coin_id()), None);
+
+        let cats = &outputs.cats[&Id::Existing(asset_id)];
+        assert!(
+            cats.iter()
+                .any(|cat| cat.info.p2_puzzle_hash == bob.puzzle_hash && cat.coin.amount == 3)
+        );
+        assert!(
+            cats.iter()
+                .any(|cat| cat.info.p2_puzzle_hash == alice.puzzle_hash && cat.coin.amount == 2)
+        );
+
+        assert_eq!(outputs.fee, 1);
+        assert!(
+            outputs
+                .xch
+                .iter()
+                .any(|coin| coin.puzzle_hash == alice.puzzle_hash && coin.amount == 3)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_cats_without_scanning_singletons() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(10);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(None, 5),
+                Action::mint_empty_nft(),
+                Action::mint_empty_nft(),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let asset_id = outputs.cats[&Id::New(0)][0].info.asset_id;
+
+        let source = CountingSource {
+            sim: &sim,
+            coin_spends: Cell::new(0),
+        };
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        let deltas = Deltas::from_actions(&[Action::send(
+            Id::Existing(asset_id),
+            alice.puzzle_hash,
+            5,
+            Memos::None,
+        )]);
+
+        spends
+            .select_inputs(&mut ctx, &deltas, &source, alice.puzzle_hash)
+            .await?;
+
+        assert_eq!(spends.cats[&Id::Existing(asset_id)].items.len(), 1);
+
+        // Only the parent of the CAT coin is fetched, not the parents of the hinted NFTs.
+        assert_eq!(source.coin_spends.get(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_with_source_option() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+        let bob = sim.bls(0);
+        let alice_hint = ctx.hint(alice.puzzle_hash)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(None, 1),
+                Action::mint_option(
+                    alice.puzzle_hash,
+                    10,
+                    Id::New(0),
+                    1,
+                    OptionType::Xch { amount: 1 },
+                    1,
+                ),
+                Action::send(Id::New(1), alice.puzzle_hash, 1, alice_hint),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let launcher_id = outputs.options[&Id::New(1)].info.launcher_id;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        let deltas = spends
+            .apply_with_source(
+                &mut ctx,
+                &[Action::send(
+                    Id::Existing(launcher_id),
+                    bob.puzzle_hash,
+                    1,
+                    Memos::None,
+                )],
+                &sim,
+                alice.puzzle_hash,
+            )
+            .await?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let option = outputs.options[&Id::Existing(launcher_id)];
+        assert_eq!(option.info.p2_puzzle_hash, bob.puzzle_hash);
+        assert_ne!(sim.coin_state(option.coin.coin_id()), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_inputs_already_added() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(5);
+        let coin = sim.new_coin(alice.puzzle_hash, 10);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(coin);
+
+        let deltas = Deltas::from_actions(&[Action::fee(8)]);
+        spends
+            .select_inputs(&mut ctx, &deltas, &sim, alice.puzzle_hash)
+            .await?;
+
+        assert_eq!(spends.xch.items.len(), 1);
+
+        let deltas = Deltas::from_actions(&[Action::fee(12)]);
+        spends
+            .select_inputs(&mut ctx, &deltas, &sim, alice.puzzle_hash)
+            .await?;
+
+        assert_eq!(spends.xch.items.len(), 2);
+        assert_eq!(spends.xch.items[1].asset, alice.coin);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_inputs_insufficient_balance() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(5);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        let deltas = Deltas::from_actions(&[Action::fee(6)]);
+
+        assert!(matches!(
+            spends
+                .select_inputs(&mut ctx, &deltas, &sim, alice.puzzle_hash)
+                .await,
+            Err(DriverError::CoinSelection(
+                CoinSelectionError::InsufficientBalance(5)
+            ))
+        ));
+
+        Ok(())
+    }
+}
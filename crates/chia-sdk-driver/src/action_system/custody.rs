@@ -0,0 +1,34 @@
+use chia_bls::PublicKey;
+use clvm_utils::{ToTreeHash, TreeHash};
+
+use crate::{StandardLayer, VaultP2Puzzle};
+
+/// The puzzle that has custody over assets, and determines how their spends are authorized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Custody {
+    /// The standard puzzle, which is authorized by a signature from the synthetic key.
+    Standard(PublicKey),
+    /// A vault p2 puzzle, which is authorized by a message from the vault singleton.
+    Vault(VaultP2Puzzle),
+}
+
+impl ToTreeHash for Custody {
+    fn tree_hash(&self) -> TreeHash {
+        match self {
+            Self::Standard(synthetic_key) => StandardLayer::new(*synthetic_key).tree_hash(),
+            Self::Vault(p2_puzzle) => p2_puzzle.tree_hash(),
+        }
+    }
+}
+
+impl From<PublicKey> for Custody {
+    fn from(value: PublicKey) -> Self {
+        Self::Standard(value)
+    }
+}
+
+impl From<VaultP2Puzzle> for Custody {
+    fn from(value: VaultP2Puzzle) -> Self {
+        Self::Vault(value)
+    }
+}
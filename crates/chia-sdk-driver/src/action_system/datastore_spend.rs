@@ -0,0 +1,90 @@
+use chia_protocol::Bytes32;
+use chia_sdk_types::Conditions;
+
+use crate::{
+    ConditionsSpend, DataStore, DataStoreInfo, DataStoreMetadata, DelegatedPuzzle, DriverError,
+    SpendContext,
+};
+
+/// A [`DataStore`] that is being spent by its owner, along with the changes that will be made to it.
+#[derive(Debug, Clone)]
+pub struct DataStoreSpend {
+    pub asset: DataStore,
+    pub spend: ConditionsSpend,
+    pub child_info: ChildDataStoreInfo,
+    pub ephemeral: bool,
+}
+
+impl DataStoreSpend {
+    pub fn new(asset: DataStore, ephemeral: bool) -> Self {
+        let child_info = ChildDataStoreInfo {
+            metadata: asset.info.metadata.clone(),
+            owner_puzzle_hash: None,
+            delegated_puzzles: asset.info.delegated_puzzles.clone(),
+            melt: false,
+        };
+
+        Self {
+            asset,
+            spend: ConditionsSpend::new(),
+            child_info,
+            ephemeral,
+        }
+    }
+
+    /// Emits the conditions required to update or melt the [`DataStore`], and returns the child if there is one.
+    /// If the owner isn't changed, the child is sent to the change puzzle hash.
+    pub fn finalize(
+        &mut self,
+        ctx: &mut SpendContext,
+        change_puzzle_hash: Bytes32,
+    ) -> Result<Option<DataStore>, DriverError> {
+        let child_info = self.child_info.clone();
+
+        let mut conditions = Conditions::new();
+
+        if child_info.metadata != self.asset.info.metadata {
+            conditions.push(DataStore::new_metadata_condition(
+                ctx,
+                child_info.metadata.clone(),
+            )?);
+        }
+
+        if child_info.melt {
+            self.spend.add_conditions(conditions.melt_singleton());
+            return Ok(None);
+        }
+
+        let owner_puzzle_hash = child_info.owner_puzzle_hash.unwrap_or(change_puzzle_hash);
+
+        // Delegated puzzles can only be rebuilt from the memos, so they're hinted whenever there are any.
+        conditions.push(DataStore::<DataStoreMetadata>::owner_create_coin_condition(
+            ctx,
+            self.asset.info.launcher_id,
+            owner_puzzle_hash,
+            child_info.delegated_puzzles.clone(),
+            !child_info.delegated_puzzles.is_empty(),
+        )?);
+
+        self.spend.add_conditions(conditions);
+
+        let info = DataStoreInfo::new(
+            self.asset.info.launcher_id,
+            child_info.metadata,
+            owner_puzzle_hash,
+            child_info.delegated_puzzles,
+        );
+
+        let child = self.asset.child(ctx, info)?;
+
+        Ok(Some(child))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChildDataStoreInfo {
+    pub metadata: DataStoreMetadata,
+    pub owner_puzzle_hash: Option<Bytes32>,
+    pub delegated_puzzles: Vec<DelegatedPuzzle>,
+    pub melt: bool,
+}
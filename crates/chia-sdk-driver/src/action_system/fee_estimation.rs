@@ -0,0 +1,380 @@
+use std::fmt::Display;
+
+use chia_protocol::{Bytes32, CoinSpend};
+use chia_sdk_utils::{CoinSource, FeeRate};
+
+use crate::{Action, Deltas, DriverError, SpendContext, Spends, Unfinished, spend_bundle_cost};
+
+impl Spends<Unfinished> {
+    /// Applies the actions along with a fee that is calculated from the cost of the transaction,
+    /// and then finishes the transaction with the provided function.
+    ///
+    /// The transaction is first built without a fee, and its cost is calculated with [`spend_bundle_cost`],
+    /// including any coin spends that were already in the [`SpendContext`]. Since the fee can change
+    /// the cost (for example by removing the change coin), the transaction is rebuilt from a copy of
+    /// these spends until the fee covers the cost at the given fee rate.
+    ///
+    /// Any [`Action::Fee`] in the actions counts towards the fee, so the total fee is the larger of
+    /// the fee in the actions and the fee required by the fee rate.
+    ///
+    /// Only the coin spends of the final transaction are left in the [`SpendContext`].
+    pub fn apply_with_fee_rate<T, F>(
+        &self,
+        ctx: &mut SpendContext,
+        actions: &[Action],
+        fee_rate: FeeRate,
+        mut finish: F,
+    ) -> Result<T, DriverError>
+    where
+        F: FnMut(&mut SpendContext, Spends, &Deltas) -> Result<T, DriverError>,
+    {
+        let pending = ctx.take();
+        let mut fee = action_fee(actions);
+
+        let result = loop {
+            let mut spends = self.clone();
+
+            let value = match spends
+                .apply(ctx, &with_fee(actions, fee))
+                .and_then(|deltas| finish(ctx, spends, &deltas))
+            {
+                Ok(value) => value,
+                Err(error) => break Err(error),
+            };
+
+            match insufficient_fee(ctx, &pending, fee_rate, fee) {
+                Ok(Some(required_fee)) => fee = required_fee,
+                Ok(None) => break Ok(value),
+                Err(error) => break Err(error),
+            }
+        };
+
+        restore_pending(ctx, pending, result.is_ok());
+
+        result
+    }
+
+    /// Applies the actions along with a fee that is calculated from the cost of the transaction,
+    /// selecting the inputs from a [`CoinSource`].
+    ///
+    /// This works like [`Spends::apply_with_fee_rate`], except that the inputs are selected again
+    /// each time the transaction is rebuilt, so that additional coins can be spent to cover the fee.
+    /// Any [`Action::Fee`] in the actions counts towards the fee in the same way.
+    pub async fn apply_with_source_and_fee_rate<S, T, F>(
+        &self,
+        ctx: &mut SpendContext,
+        actions: &[Action],
+        source: &S,
+        p2_puzzle_hash: Bytes32,
+        fee_rate: FeeRate,
+        mut finish: F,
+    ) -> Result<T, DriverError>
+    where
+        S: CoinSource,
+        S::Error: Display,
+        F: FnMut(&mut SpendContext, Spends, &Deltas) -> Result<T, DriverError>,
+    {
+        let pending = ctx.take();
+        let mut fee = action_fee(actions);
+
+        let result = loop {
+            let mut spends = self.clone();
+
+            let value = match spends
+                .apply_with_source(ctx, &with_fee(actions, fee), source, p2_puzzle_hash)
+                .await
+                .and_then(|deltas| finish(ctx, spends, &deltas))
+            {
+                Ok(value) => value,
+                Err(error) => break Err(error),
+            };
+
+            match insufficient_fee(ctx, &pending, fee_rate, fee) {
+                Ok(Some(required_fee)) => fee = required_fee,
+                Ok(None) => break Ok(value),
+                Err(error) => break Err(error),
+            }
+        };
+
+        restore_pending(ctx, pending, result.is_ok());
+
+        result
+    }
+}
+
+/// The total fee that is already paid by the actions.
+fn action_fee(actions: &[Action]) -> u64 {
+    actions
+        .iter()
+        .map(|action| match action {
+            Action::Fee(fee) => fee.amount,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Adds the part of the fee that isn't already paid by the actions.
+fn with_fee(actions: &[Action], fee: u64) -> Vec<Action> {
+    let mut actions = actions.to_vec();
+    let remaining = fee.saturating_sub(action_fee(&actions));
+
+    if remaining > 0 {
+        actions.push(Action::fee(remaining));
+    }
+
+    actions
+}
+
+/// Calculates the fee required for the coin spends in the context, along with the pending ones.
+/// If the current fee isn't enough, the coin spends are discarded so that the transaction can be rebuilt.
+fn insufficient_fee(
+    ctx: &mut SpendContext,
+    pending: &[CoinSpend],
+    fee_rate: FeeRate,
+    fee: u64,
+) -> Result<Option<u64>, DriverError> {
+    let coin_spends = ctx.take();
+    let required_fee = fee_rate.fee(spend_bundle_cost(&[pending, &coin_spends].concat())?);
+
+    if required_fee > fee {
+        return Ok(Some(required_fee));
+    }
+
+    for coin_spend in coin_spends {
+        ctx.insert(coin_spend);
+    }
+
+    Ok(None)
+}
+
+fn restore_pending(ctx: &mut SpendContext, pending: Vec<CoinSpend>, keep_new: bool) {
+    let coin_spends = ctx.take();
+
+    for coin_spend in pending {
+        ctx.insert(coin_spend);
+    }
+
+    if keep_new {
+        for coin_spend in coin_spends {
+            ctx.insert(coin_spend);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_puzzle_types::Memos;
+    use chia_sdk_test::Simulator;
+    use chia_sdk_types::Conditions;
+    use indexmap::{IndexMap, indexmap};
+
+    use crate::{Id, Relation, StandardLayer};
+
+    use super::*;
+
+    #[test]
+    fn test_apply_with_fee_rate() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1_000_000_000_000);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let fee_rate = FeeRate::mojos_per_cost(5);
+
+        let outputs = spends.apply_with_fee_rate(
+            &mut ctx,
+            &[Action::send(Id::Xch, bob.puzzle_hash, 1000, Memos::None)],
+            fee_rate,
+            |ctx, spends, deltas| {
+                spends.finish_with_keys(
+                    ctx,
+                    deltas,
+                    Relation::None,
+                    &indexmap! { alice.puzzle_hash => alice.pk },
+                )
+            },
+        )?;
+
+        let coin_spends = ctx.take();
+        let cost = spend_bundle_cost(&coin_spends)?;
+
+        assert_eq!(coin_spends.len(), 1);
+        assert!(outputs.fee >= fee_rate.fee(cost));
+        assert!(outputs.fee < fee_rate.fee(cost + 100_000));
+
+        sim.spend_coins(coin_spends, &[alice.sk])?;
+
+        assert!(
+            outputs
+                .xch
+                .iter()
+                .any(|coin| coin.puzzle_hash == alice.puzzle_hash
+                    && coin.amount == alice.coin.amount - 1000 - outputs.fee)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_with_fee_rate_includes_action_fee() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1_000_000_000_000);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let fee_rate = FeeRate::mojos_per_cost(5);
+
+        let finish = |ctx: &mut SpendContext, spends: Spends, deltas: &Deltas| {
+            spends.finish_with_keys(
+                ctx,
+                deltas,
+                Relation::None,
+                &indexmap! { alice.puzzle_hash => alice.pk },
+            )
+        };
+
+        // A fee that's too small is topped up to the required fee, rather than added to it.
+        let outputs = spends.apply_with_fee_rate(&mut ctx, &[Action::fee(1)], fee_rate, finish)?;
+
+        let cost = spend_bundle_cost(&ctx.take())?;
+        assert!(outputs.fee >= fee_rate.fee(cost));
+        assert!(outputs.fee < fee_rate.fee(cost + 100_000));
+
+        // A fee that's larger than the required fee is paid as is.
+        let outputs = spends.apply_with_fee_rate(
+            &mut ctx,
+            &[Action::fee(100_000_000_000)],
+            fee_rate,
+            finish,
+        )?;
+
+        assert_eq!(outputs.fee, 100_000_000_000);
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_with_fee_rate_keeps_pending_spends() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1_000_000_000_000);
+        let bob = sim.bls(1);
+
+        StandardLayer::new(bob.pk).spend(&mut ctx, bob.coin, Conditions::new())?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let fee_rate = FeeRate::mojos_per_cost(1);
+
+        let outputs =
+            spends.apply_with_fee_rate(&mut ctx, &[], fee_rate, |ctx, spends, deltas| {
+                spends.finish_with_keys(
+                    ctx,
+                    deltas,
+                    Relation::None,
+                    &indexmap! { alice.puzzle_hash => alice.pk },
+                )
+            })?;
+
+        let coin_spends = ctx.take();
+        assert_eq!(coin_spends.len(), 2);
+        assert_eq!(coin_spends[0].coin, bob.coin);
+        assert!(outputs.fee >= fee_rate.fee(spend_bundle_cost(&coin_spends)?));
+
+        sim.spend_coins(coin_spends, &[alice.sk, bob.sk])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_with_fee_rate_error() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1000);
+        let bob = sim.bls(1);
+
+        StandardLayer::new(bob.pk).spend(&mut ctx, bob.coin, Conditions::new())?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let result = spends.apply_with_fee_rate(
+            &mut ctx,
+            &[Action::send(Id::Xch, bob.puzzle_hash, 500, Memos::None)],
+            FeeRate::mojos_per_cost(1),
+            |ctx, spends, deltas| {
+                spends.finish_with_keys(ctx, deltas, Relation::None, &IndexMap::new())
+            },
+        );
+
+        assert!(matches!(result, Err(DriverError::MissingKey)));
+
+        let coin_spends = ctx.take();
+        assert_eq!(coin_spends.len(), 1);
+        assert_eq!(coin_spends[0].coin, bob.coin);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_with_source_and_fee_rate() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1000);
+        let coin = sim.new_coin(alice.puzzle_hash, 1_000_000_000_000);
+        let bob = sim.bls(0);
+
+        let fee_rate = FeeRate::mojos_per_cost(5);
+
+        let outputs = Spends::new(alice.puzzle_hash)
+            .apply_with_source_and_fee_rate(
+                &mut ctx,
+                &[Action::send(Id::Xch, bob.puzzle_hash, 1000, Memos::None)],
+                &sim,
+                alice.puzzle_hash,
+                fee_rate,
+                |ctx, spends, deltas| {
+                    spends.finish_with_keys(
+                        ctx,
+                        deltas,
+                        Relation::None,
+                        &indexmap! { alice.puzzle_hash => alice.pk },
+                    )
+                },
+            )
+            .await?;
+
+        let coin_spends = ctx.take();
+        let cost = spend_bundle_cost(&coin_spends)?;
+
+        // The exact coin would be selected without a fee, so the larger coin must be spent to pay it.
+        assert!(coin_spends.iter().any(|coin_spend| coin_spend.coin == coin));
+        assert!(outputs.fee >= fee_rate.fee(cost));
+
+        sim.spend_coins(coin_spends, slice::from_ref(&alice.sk))?;
+
+        assert!(
+            sim.unspent_coins(bob.puzzle_hash, false)
+                .iter()
+                .any(|coin| coin.amount == 1000)
+        );
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use chia_protocol::{Bytes32, Coin};
 use chia_puzzle_types::{
     Memos,
@@ -6,6 +8,7 @@ use chia_puzzle_types::{
 };
 use chia_puzzles::{SETTLEMENT_PAYMENT_HASH, SINGLETON_LAUNCHER_HASH};
 use chia_sdk_types::conditions::{AssertPuzzleAnnouncement, CreateCoin};
+use chia_sdk_utils::CoinSelectionError;
 
 use crate::{
     Asset, Cat, Delta, DriverError, Launcher, OptionLauncher, OptionLauncherInfo, OptionType,
@@ -34,6 +37,35 @@ where
             .sum()
     }
 
+    /// Keeps at most `max_coins` of the selected coins, preferring the smallest ones. The rest are removed,
+    /// so that they can be spent in a later transaction. Coins that are already used by an action are always
+    /// kept, and ephemeral coins aren't counted towards the limit.
+    pub fn retain_smallest(&mut self, max_coins: usize) -> Result<(), DriverError> {
+        let mut unused: Vec<usize> = (0..self.items.len())
+            .filter(|&index| !self.items[index].ephemeral && self.items[index].kind.is_empty())
+            .collect();
+
+        let used = self.items.iter().filter(|item| !item.ephemeral).count() - unused.len();
+
+        if used > max_coins {
+            return Err(CoinSelectionError::ExceededMaxCoins.into());
+        }
+
+        unused.sort_by_key(|&index| self.items[index].asset.amount());
+
+        let removed: HashSet<usize> = unused.into_iter().skip(max_coins - used).collect();
+
+        let mut index = 0;
+
+        self.items.retain(|_| {
+            let keep = !removed.contains(&index);
+            index += 1;
+            keep
+        });
+
+        Ok(())
+    }
+
     pub fn output_source(
         &mut self,
         ctx: &mut SpendContext,
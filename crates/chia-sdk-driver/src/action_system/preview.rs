@@ -0,0 +1,725 @@
+use std::collections::HashSet;
+
+use chia_protocol::{Bytes32, Coin};
+use chia_puzzle_types::offer::NotarizedPayment;
+use chia_sdk_types::conditions::AssertPuzzleAnnouncement;
+use clvm_utils::ToTreeHash;
+use indexmap::IndexMap;
+
+#[cfg(feature = "chip-0035")]
+use crate::DataStore;
+
+use crate::{
+    ClawbackV2, Deltas, DriverError, Finished, Id, Relation, SingletonAsset, SingletonSpends,
+    SpendContext, SpendKind, SpendableAsset, Spends, StreamedAsset, Unfinished,
+};
+
+/// A summary of what a transaction will do, which can be shown to the user before it's signed.
+#[derive(Debug, Clone)]
+pub struct TransactionPreview {
+    /// The assets that will be spent. Ephemeral assets that are created by the transaction itself
+    /// aren't included, but clawbacks and option underlyings that are unlocked by it are.
+    pub inputs: Vec<PreviewAsset>,
+    /// The assets that will be created, grouped by their p2 puzzle hash.
+    pub outputs: IndexMap<Bytes32, Vec<PreviewAsset>>,
+    /// The net change in the balance of each asset, across the provided p2 puzzle hashes.
+    pub balance_changes: IndexMap<Id, BalanceChange>,
+    /// The singletons that are launched by the transaction.
+    pub created_singletons: Vec<Id>,
+    /// The singletons that are spent without being recreated.
+    pub melted_singletons: Vec<Id>,
+    pub fee: u64,
+    pub reserved_fee: u64,
+    /// The payments that are made from settlement coins, such as when taking an offer.
+    pub settlement_payments: Vec<(Id, NotarizedPayment)>,
+    /// The settlement payments that must be made by other spends for the transaction to be valid,
+    /// such as the requested payments of an offer.
+    pub payment_assertions: Vec<AssertPuzzleAnnouncement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreviewAsset {
+    pub id: Id,
+    pub kind: PreviewAssetKind,
+}
+
+impl PreviewAsset {
+    pub fn new(id: Id, kind: PreviewAssetKind) -> Self {
+        Self { id, kind }
+    }
+
+    pub fn coin(&self) -> Coin {
+        match &self.kind {
+            PreviewAssetKind::Asset(asset)
+            | PreviewAssetKind::Clawback(asset, _)
+            | PreviewAssetKind::OptionUnderlying(asset) => asset.coin(),
+            PreviewAssetKind::Stream(stream) => stream.coin,
+            #[cfg(feature = "chip-0035")]
+            PreviewAssetKind::DataStore(datastore) => datastore.coin,
+        }
+    }
+
+    /// The puzzle hash that the asset is locked by, inside of any asset layers such as the CAT layer.
+    pub fn p2_puzzle_hash(&self) -> Bytes32 {
+        match &self.kind {
+            PreviewAssetKind::Asset(asset)
+            | PreviewAssetKind::Clawback(asset, _)
+            | PreviewAssetKind::OptionUnderlying(asset) => asset.p2_puzzle_hash(),
+            PreviewAssetKind::Stream(stream) => stream.info.inner_puzzle_hash().into(),
+            #[cfg(feature = "chip-0035")]
+            PreviewAssetKind::DataStore(datastore) => datastore.info.owner_puzzle_hash,
+        }
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.coin().amount
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PreviewAssetKind {
+    Asset(SpendableAsset),
+    /// An asset that is locked in a clawback, which can be clawed back by the sender until it expires.
+    Clawback(SpendableAsset, ClawbackV2),
+    /// The asset that is locked by an option, which can be reclaimed by its creator once it expires.
+    OptionUnderlying(SpendableAsset),
+    Stream(StreamedAsset),
+    #[cfg(feature = "chip-0035")]
+    DataStore(DataStore),
+}
+
+/// The amount of an asset that is sent from and received by the wallet.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub sent: u64,
+    pub received: u64,
+}
+
+impl BalanceChange {
+    pub fn new(sent: u64, received: u64) -> Self {
+        Self { sent, received }
+    }
+
+    /// The change in balance, which is negative if more is sent than received.
+    pub fn net(&self) -> i128 {
+        i128::from(self.received) - i128::from(self.sent)
+    }
+}
+
+impl Spends<Unfinished> {
+    /// Previews the transaction that would be created by finishing these spends with the given deltas.
+    ///
+    /// The spends themselves aren't modified, and any coin spends created while preparing the preview
+    /// are discarded, so the transaction can still be finished afterwards. The balance changes are
+    /// calculated for the given p2 puzzle hashes, which should be the wallet's own.
+    pub fn preview(
+        &self,
+        ctx: &mut SpendContext,
+        deltas: &Deltas,
+        p2_puzzle_hashes: &[Bytes32],
+    ) -> Result<TransactionPreview, DriverError> {
+        let pending = ctx.take();
+
+        let result = self
+            .clone()
+            .prepare(ctx, deltas, Relation::None)
+            .map(|spends| spends.preview(p2_puzzle_hashes));
+
+        ctx.take();
+
+        for coin_spend in pending {
+            ctx.insert(coin_spend);
+        }
+
+        result
+    }
+}
+
+impl Spends<Finished> {
+    /// Previews the transaction, with the balance changes calculated for the given p2 puzzle hashes.
+    pub fn preview(&self, p2_puzzle_hashes: &[Bytes32]) -> TransactionPreview {
+        let mut inputs = Vec::new();
+        let mut settlement_payments = Vec::new();
+        let mut payment_assertions = self.xch.payment_assertions.clone();
+
+        for item in &self.xch.items {
+            if let Some(kind) = self.input_kind(&SpendableAsset::Xch(item.asset), item.ephemeral) {
+                inputs.push(PreviewAsset::new(Id::Xch, kind));
+            }
+
+            add_settlement_payments(&mut settlement_payments, Id::Xch, &item.kind);
+        }
+
+        for (&id, cat) in &self.cats {
+            for item in &cat.items {
+                if let Some(kind) =
+                    self.input_kind(&SpendableAsset::Cat(item.asset), item.ephemeral)
+                {
+                    inputs.push(PreviewAsset::new(id, kind));
+                }
+
+                add_settlement_payments(&mut settlement_payments, id, &item.kind);
+            }
+
+            payment_assertions.extend_from_slice(&cat.payment_assertions);
+        }
+
+        self.add_singleton_inputs(
+            &mut inputs,
+            &mut settlement_payments,
+            &mut payment_assertions,
+            &self.dids,
+            SpendableAsset::Did,
+        );
+        self.add_singleton_inputs(
+            &mut inputs,
+            &mut settlement_payments,
+            &mut payment_assertions,
+            &self.nfts,
+            SpendableAsset::Nft,
+        );
+        self.add_singleton_inputs(
+            &mut inputs,
+            &mut settlement_payments,
+            &mut payment_assertions,
+            &self.options,
+            SpendableAsset::Option,
+        );
+
+        #[cfg(feature = "chip-0035")]
+        for (&id, datastore) in &self.datastores {
+            if !datastore.ephemeral {
+                inputs.push(PreviewAsset::new(
+                    id,
+                    PreviewAssetKind::DataStore(datastore.asset.clone()),
+                ));
+            }
+        }
+
+        for stream in &self.streams {
+            inputs.push(PreviewAsset::new(
+                stream_id(&stream.asset),
+                PreviewAssetKind::Stream(stream.asset.clone()),
+            ));
+        }
+
+        let clawbacks: IndexMap<Bytes32, ClawbackV2> = self
+            .outputs
+            .clawbacks
+            .iter()
+            .map(|&clawback| (clawback.tree_hash().into(), clawback))
+            .collect();
+
+        let underlying_coin_ids: HashSet<Bytes32> = self
+            .outputs
+            .options
+            .values()
+            .map(|option| option.info.underlying_coin_id)
+            .collect();
+
+        // Created streams are also sent as regular coins, so they're only listed once as streams.
+        let stream_coin_ids: HashSet<Bytes32> = self
+            .outputs
+            .streams
+            .iter()
+            .map(|stream| stream.coin.coin_id())
+            .collect();
+
+        let mut outputs = Vec::new();
+
+        let mut add_output = |id: Id, asset: SpendableAsset| {
+            let coin = asset.coin();
+
+            let kind = if let Some(&clawback) = clawbacks.get(&asset.p2_puzzle_hash()) {
+                PreviewAssetKind::Clawback(asset, clawback)
+            } else if underlying_coin_ids.contains(&coin.coin_id()) {
+                PreviewAssetKind::OptionUnderlying(asset)
+            } else if stream_coin_ids.contains(&coin.coin_id()) {
+                return;
+            } else {
+                PreviewAssetKind::Asset(asset)
+            };
+
+            outputs.push(PreviewAsset::new(id, kind));
+        };
+
+        for &coin in &self.outputs.xch {
+            add_output(Id::Xch, SpendableAsset::Xch(coin));
+        }
+
+        for (&id, cats) in &self.outputs.cats {
+            for &cat in cats {
+                add_output(id, SpendableAsset::Cat(cat));
+            }
+        }
+
+        for (&id, &did) in &self.outputs.dids {
+            add_output(id, SpendableAsset::Did(did));
+        }
+
+        for (&id, &nft) in &self.outputs.nfts {
+            add_output(id, SpendableAsset::Nft(nft));
+        }
+
+        for (&id, &option) in &self.outputs.options {
+            add_output(id, SpendableAsset::Option(option));
+        }
+
+        #[cfg(feature = "chip-0035")]
+        for (&id, datastore) in &self.outputs.datastores {
+            outputs.push(PreviewAsset::new(
+                id,
+                PreviewAssetKind::DataStore(datastore.clone()),
+            ));
+        }
+
+        for stream in &self.outputs.streams {
+            outputs.push(PreviewAsset::new(
+                stream_id(stream),
+                PreviewAssetKind::Stream(stream.clone()),
+            ));
+        }
+
+        let own_puzzle_hashes: HashSet<Bytes32> = p2_puzzle_hashes.iter().copied().collect();
+        let mut balance_changes = IndexMap::<Id, BalanceChange>::new();
+
+        for input in &inputs {
+            if own_puzzle_hashes.contains(&input.p2_puzzle_hash()) {
+                balance_changes.entry(input.id).or_default().sent += input.amount();
+            }
+        }
+
+        for output in &outputs {
+            if own_puzzle_hashes.contains(&output.p2_puzzle_hash()) {
+                balance_changes.entry(output.id).or_default().received += output.amount();
+            }
+        }
+
+        let input_ids: HashSet<Id> = inputs.iter().map(|input| input.id).collect();
+
+        let singleton_outputs = self
+            .outputs
+            .dids
+            .keys()
+            .chain(self.outputs.nfts.keys())
+            .chain(self.outputs.options.keys());
+
+        #[cfg(feature = "chip-0035")]
+        let singleton_outputs = singleton_outputs.chain(self.outputs.datastores.keys());
+
+        let singleton_outputs: Vec<Id> = singleton_outputs.copied().collect();
+
+        let singleton_inputs = self
+            .dids
+            .keys()
+            .chain(self.nfts.keys())
+            .chain(self.options.keys());
+
+        #[cfg(feature = "chip-0035")]
+        let singleton_inputs = singleton_inputs.chain(self.datastores.keys());
+
+        let created_singletons = singleton_outputs
+            .iter()
+            .filter(|id| !input_ids.contains(id))
+            .copied()
+            .collect();
+
+        let melted_singletons = singleton_inputs
+            .filter(|id| !singleton_outputs.contains(id))
+            .copied()
+            .collect();
+
+        let mut grouped_outputs = IndexMap::<Bytes32, Vec<PreviewAsset>>::new();
+
+        for output in outputs {
+            grouped_outputs
+                .entry(output.p2_puzzle_hash())
+                .or_default()
+                .push(output);
+        }
+
+        TransactionPreview {
+            inputs,
+            outputs: grouped_outputs,
+            balance_changes,
+            created_singletons,
+            melted_singletons,
+            fee: self.outputs.fee,
+            reserved_fee: self.outputs.reserved_fee,
+            settlement_payments,
+            payment_assertions,
+        }
+    }
+
+    /// Clawbacks and option underlyings are added as ephemeral assets once they're unlocked, but the
+    /// coins themselves are still spent by the transaction, so they're included as inputs.
+    fn input_kind(&self, asset: &SpendableAsset, ephemeral: bool) -> Option<PreviewAssetKind> {
+        let p2_puzzle_hash = asset.p2_puzzle_hash();
+
+        if let Some(info) = self.clawbacks.get(&p2_puzzle_hash) {
+            Some(PreviewAssetKind::Clawback(*asset, info.clawback))
+        } else if self.option_underlyings.contains_key(&p2_puzzle_hash) {
+            Some(PreviewAssetKind::OptionUnderlying(*asset))
+        } else if ephemeral {
+            None
+        } else {
+            Some(PreviewAssetKind::Asset(*asset))
+        }
+    }
+
+    fn add_singleton_inputs<A>(
+        &self,
+        inputs: &mut Vec<PreviewAsset>,
+        settlement_payments: &mut Vec<(Id, NotarizedPayment)>,
+        payment_assertions: &mut Vec<AssertPuzzleAnnouncement>,
+        singletons: &IndexMap<Id, SingletonSpends<A>>,
+        to_asset: fn(A) -> SpendableAsset,
+    ) where
+        A: SingletonAsset + Copy,
+    {
+        for (&id, singleton) in singletons {
+            // Only the first spend in the lineage is an input, since the rest are created by the transaction.
+            if let Some(item) = singleton.lineage.first()
+                && let Some(kind) = self.input_kind(&to_asset(item.asset), singleton.ephemeral)
+            {
+                inputs.push(PreviewAsset::new(id, kind));
+            }
+
+            for item in &singleton.lineage {
+                add_settlement_payments(settlement_payments, id, &item.kind);
+                payment_assertions.extend_from_slice(&item.payment_assertions);
+            }
+        }
+    }
+}
+
+fn stream_id(stream: &StreamedAsset) -> Id {
+    stream.asset_id.map_or(Id::Xch, Id::Existing)
+}
+
+fn add_settlement_payments(
+    settlement_payments: &mut Vec<(Id, NotarizedPayment)>,
+    id: Id,
+    kind: &SpendKind,
+) {
+    if let SpendKind::Settlement(spend) = kind {
+        for notarized_payment in spend.clone().finish() {
+            settlement_payments.push((id, notarized_payment));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_puzzle_types::Memos;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+
+    use crate::Action;
+
+    use super::*;
+
+    #[test]
+    fn test_preview_send_xch() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(5000);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::send(Id::Xch, bob.puzzle_hash, 1000, Memos::None),
+                Action::fee(100),
+            ],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        assert_eq!(preview.inputs.len(), 1);
+        assert_eq!(preview.inputs[0].amount(), 5000);
+        assert!(matches!(preview.inputs[0].kind, PreviewAssetKind::Asset(_)));
+        assert_eq!(preview.fee, 100);
+        assert_eq!(
+            preview.balance_changes[&Id::Xch],
+            BalanceChange::new(5000, 3900)
+        );
+        assert_eq!(preview.balance_changes[&Id::Xch].net(), -1100);
+        assert_eq!(preview.outputs[&bob.puzzle_hash].len(), 1);
+        assert_eq!(preview.outputs[&bob.puzzle_hash][0].amount(), 1000);
+        assert_eq!(preview.outputs[&alice.puzzle_hash][0].amount(), 3900);
+        assert!(preview.created_singletons.is_empty());
+        assert!(preview.melted_singletons.is_empty());
+
+        // The preview shouldn't affect the transaction that's created afterward.
+        spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preview_singletons() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::mint_empty_nft(), Action::create_empty_did()],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        assert_eq!(preview.created_singletons, [Id::New(1), Id::New(0)]);
+        assert!(preview.melted_singletons.is_empty());
+        assert_eq!(preview.balance_changes[&Id::Xch].net(), -2);
+        assert_eq!(preview.balance_changes[&Id::New(0)].net(), 1);
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let did = outputs.dids[&Id::New(1)];
+        let did_id = Id::Existing(did.info.launcher_id);
+
+        let coin = sim.new_coin(alice.puzzle_hash, 1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(coin);
+        spends.add(did);
+
+        let deltas = spends.apply(&mut ctx, &[Action::melt_singleton(did_id, 1)])?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        assert!(preview.created_singletons.is_empty());
+        assert_eq!(preview.melted_singletons, [did_id]);
+        assert_eq!(preview.balance_changes[&did_id].net(), -1);
+        assert_eq!(preview.balance_changes[&Id::Xch].net(), 1);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "chip-0035")]
+    #[test]
+    fn test_preview_melt_datastore() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::mint_datastore(
+                crate::DataStoreMetadata::default(),
+                vec![],
+            )],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        assert_eq!(preview.created_singletons, [Id::New(0)]);
+        assert!(matches!(
+            preview.outputs[&alice.puzzle_hash][0].kind,
+            PreviewAssetKind::DataStore(_)
+        ));
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let datastore = outputs.datastores[&Id::New(0)].clone();
+        let datastore_id = Id::Existing(datastore.info.launcher_id);
+
+        let coin = sim.new_coin(alice.puzzle_hash, 1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(coin);
+        spends.add(datastore);
+
+        let deltas = spends.apply(&mut ctx, &[Action::melt_singleton(datastore_id, 1)])?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        assert!(preview.created_singletons.is_empty());
+        assert_eq!(preview.melted_singletons, [datastore_id]);
+        assert_eq!(preview.balance_changes[&datastore_id].net(), -1);
+        assert_eq!(preview.balance_changes[&Id::Xch].net(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preview_clawback() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send_with_clawback(Id::Xch, bob.puzzle_hash, 100, 1)],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        let (&p2_puzzle_hash, outputs) = preview.outputs.first().expect("missing output");
+        assert_eq!(outputs.len(), 1);
+        assert!(matches!(
+            outputs[0].kind,
+            PreviewAssetKind::Clawback(_, clawback) if clawback.tree_hash() == p2_puzzle_hash.into()
+        ));
+        assert_eq!(preview.balance_changes[&Id::Xch].net(), -1);
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let clawback = outputs.clawbacks[0];
+        let coin = outputs.xch[0];
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::claw_back(clawback, SpendableAsset::Xch(coin))],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        assert_eq!(preview.inputs.len(), 1);
+        assert!(matches!(
+            preview.inputs[0].kind,
+            PreviewAssetKind::Clawback(SpendableAsset::Xch(input), _) if input == coin
+        ));
+        assert_eq!(preview.outputs[&alice.puzzle_hash][0].amount(), 1);
+        assert_eq!(preview.balance_changes[&Id::Xch], BalanceChange::new(0, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preview_stream() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1000);
+        let bob = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::create_stream(
+                Id::Xch,
+                bob.puzzle_hash,
+                None,
+                0,
+                1000,
+                1000,
+            )],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[alice.puzzle_hash])?;
+
+        // The stream is only listed once, even though it's also created as a regular coin.
+        let outputs: Vec<&PreviewAsset> = preview.outputs.values().flatten().collect();
+        assert_eq!(outputs.len(), 1);
+        assert!(matches!(outputs[0].kind, PreviewAssetKind::Stream(_)));
+        assert_eq!(preview.balance_changes[&Id::Xch].net(), -1000);
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let stream = outputs.streams[0].clone();
+
+        sim.set_next_timestamp(250)?;
+
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(bob.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::claim_stream(stream.clone(), 250), Action::fee(1)],
+        )?;
+
+        let preview = spends.preview(&mut ctx, &deltas, &[bob.puzzle_hash])?;
+
+        assert_eq!(preview.inputs.len(), 2);
+        assert!(matches!(
+            &preview.inputs[1].kind,
+            PreviewAssetKind::Stream(input) if *input == stream
+        ));
+
+        let child = preview
+            .outputs
+            .values()
+            .flatten()
+            .find(|output| matches!(output.kind, PreviewAssetKind::Stream(_)))
+            .expect("missing stream child");
+        assert_eq!(child.amount(), 750);
+        assert_eq!(
+            preview.balance_changes[&Id::Xch],
+            BalanceChange::new(1, 250)
+        );
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&bob.sk))?;
+
+        assert_eq!(outputs.streams[0].coin, child.coin());
+
+        Ok(())
+    }
+}
@@ -40,6 +40,14 @@ impl SpendKind {
         matches!(self, Self::Settlement(_))
     }
 
+    /// Whether nothing has been added to the spend yet, so the coin isn't used by any action.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Conditions(spend) => spend.is_empty(),
+            Self::Settlement(spend) => spend.is_empty(),
+        }
+    }
+
     pub fn create_coin_with_assertion(
         &mut self,
         allocator: &Allocator,
@@ -25,6 +25,10 @@ impl ConditionsSpend {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
     pub fn finish(self) -> Conditions {
         self.conditions
     }
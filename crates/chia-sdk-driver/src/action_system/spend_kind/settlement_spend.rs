@@ -24,6 +24,10 @@ impl SettlementSpend {
         self.notarized_payments.push(notarized_payment);
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.notarized_payments.is_empty()
+    }
+
     pub fn finish(self) -> Vec<NotarizedPayment> {
         self.notarized_payments
     }
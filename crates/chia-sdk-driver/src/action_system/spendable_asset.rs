@@ -1,6 +1,6 @@
 use chia_protocol::{Bytes32, Coin};
 
-use crate::{Cat, Did, Nft, OptionContract};
+use crate::{Cat, Did, Id, Nft, OptionContract};
 
 #[derive(Debug, Clone, Copy)]
 pub enum SpendableAsset {
@@ -12,6 +12,16 @@ pub enum SpendableAsset {
 }
 
 impl SpendableAsset {
+    pub fn id(&self) -> Id {
+        match self {
+            Self::Xch(_) => Id::Xch,
+            Self::Cat(cat) => Id::Existing(cat.info.asset_id),
+            Self::Did(did) => Id::Existing(did.info.launcher_id),
+            Self::Nft(nft) => Id::Existing(nft.info.launcher_id),
+            Self::Option(option) => Id::Existing(option.info.launcher_id),
+        }
+    }
+
     pub fn p2_puzzle_hash(&self) -> Bytes32 {
         match self {
             Self::Xch(coin) => coin.puzzle_hash,
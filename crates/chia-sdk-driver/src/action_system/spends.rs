@@ -2,15 +2,22 @@ use std::{collections::HashMap, mem};
 
 use chia_bls::PublicKey;
 use chia_protocol::{Bytes32, Coin};
-use chia_puzzle_types::offer::SettlementPaymentsSolution;
-use chia_sdk_types::{Conditions, conditions::AssertPuzzleAnnouncement};
+use chia_puzzle_types::{Memos, offer::SettlementPaymentsSolution};
+use chia_sdk_types::{
+    Conditions, MessageFlags, MessageSide,
+    conditions::{AssertPuzzleAnnouncement, SendMessage},
+};
 use indexmap::IndexMap;
 
+#[cfg(feature = "chip-0035")]
+use crate::{DataStore, DataStoreSpend};
+
 use crate::{
-    Action, Asset, Cat, CatSpend, ConditionsSpend, Delta, Deltas, Did, DriverError, FungibleSpend,
-    FungibleSpends, Id, Layer, Nft, OptionContract, Relation, SettlementLayer, SingletonSpends,
-    Spend, SpendAction, SpendContext, SpendKind, SpendWithConditions, SpendableAsset,
-    StandardLayer,
+    Action, Asset, Cat, CatSpend, ClawbackInfo, ClawbackPath, ClawbackV2, ConditionsSpend, Custody,
+    Delta, Deltas, Did, DriverError, FungibleSpend, FungibleSpends, Id, Layer, Nft, OptionContract,
+    OptionUnderlying, Relation, SettlementLayer, SingletonSpends, Spend, SpendAction, SpendContext,
+    SpendKind, SpendWithConditions, SpendableAsset, StandardLayer, StreamedAsset, Vault,
+    VaultP2Puzzle, VaultSpend,
 };
 
 #[derive(Debug, Clone)]
@@ -21,8 +28,13 @@ pub struct Spends<S = Unfinished> {
     pub dids: IndexMap<Id, SingletonSpends<Did>>,
     pub nfts: IndexMap<Id, SingletonSpends<Nft>>,
     pub options: IndexMap<Id, SingletonSpends<OptionContract>>,
+    #[cfg(feature = "chip-0035")]
+    pub datastores: IndexMap<Id, DataStoreSpend>,
     pub intermediate_puzzle_hash: Bytes32,
     pub change_puzzle_hash: Bytes32,
+    pub clawbacks: IndexMap<Bytes32, ClawbackInfo>,
+    pub option_underlyings: IndexMap<Bytes32, OptionUnderlying>,
+    pub streams: Vec<StreamSpend>,
     pub outputs: Outputs,
     pub conditions: ConditionConfig,
     _state: S,
@@ -35,6 +47,23 @@ pub struct ConditionConfig {
     pub disable_settlement_assertions: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct StreamSpend {
+    pub asset: StreamedAsset,
+    pub payment_time: u64,
+    pub clawback: bool,
+}
+
+impl StreamSpend {
+    pub fn new(asset: StreamedAsset, payment_time: u64, clawback: bool) -> Self {
+        Self {
+            asset,
+            payment_time,
+            clawback,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Outputs {
     pub xch: Vec<Coin>,
@@ -42,6 +71,10 @@ pub struct Outputs {
     pub dids: IndexMap<Id, Did>,
     pub nfts: IndexMap<Id, Nft>,
     pub options: IndexMap<Id, OptionContract>,
+    #[cfg(feature = "chip-0035")]
+    pub datastores: IndexMap<Id, DataStore>,
+    pub clawbacks: Vec<ClawbackV2>,
+    pub streams: Vec<StreamedAsset>,
     pub fee: u64,
     pub reserved_fee: u64,
 }
@@ -67,8 +100,13 @@ impl Spends<Unfinished> {
             dids: IndexMap::new(),
             nfts: IndexMap::new(),
             options: IndexMap::new(),
+            #[cfg(feature = "chip-0035")]
+            datastores: IndexMap::new(),
             intermediate_puzzle_hash,
             change_puzzle_hash,
+            clawbacks: IndexMap::new(),
+            option_underlyings: IndexMap::new(),
+            streams: Vec::new(),
             outputs: Outputs::default(),
             conditions: ConditionConfig::default(),
             _state: Unfinished,
@@ -79,6 +117,34 @@ impl Spends<Unfinished> {
         asset.add(self);
     }
 
+    /// Adds an asset that is created or unlocked by an action, rather than selected as an input.
+    /// Its value must be accounted for as an input in the deltas.
+    pub fn add_ephemeral(&mut self, asset: SpendableAsset) {
+        let id = asset.id();
+
+        match asset {
+            SpendableAsset::Xch(coin) => {
+                self.xch.items.push(FungibleSpend::new(coin, true));
+            }
+            SpendableAsset::Cat(cat) => {
+                self.cats
+                    .entry(id)
+                    .or_default()
+                    .items
+                    .push(FungibleSpend::new(cat, true));
+            }
+            SpendableAsset::Did(did) => {
+                self.dids.insert(id, SingletonSpends::new(did, true));
+            }
+            SpendableAsset::Nft(nft) => {
+                self.nfts.insert(id, SingletonSpends::new(nft, true));
+            }
+            SpendableAsset::Option(option) => {
+                self.options.insert(id, SingletonSpends::new(option, true));
+            }
+        }
+    }
+
     pub fn apply(
         &mut self,
         ctx: &mut SpendContext,
@@ -138,6 +204,13 @@ impl Spends<Unfinished> {
             }
         }
 
+        #[cfg(feature = "chip-0035")]
+        for (&id, datastore) in &mut self.datastores {
+            if let Some(change) = datastore.finalize(ctx, self.change_puzzle_hash)? {
+                self.outputs.datastores.insert(id, change);
+            }
+        }
+
         Ok(())
     }
 
@@ -170,7 +243,8 @@ impl Spends<Unfinished> {
     }
 
     fn iter_conditions_spends(&mut self) -> impl Iterator<Item = (Coin, &mut ConditionsSpend)> {
-        self.xch
+        let iter = self
+            .xch
             .items
             .iter_mut()
             .filter_map(|item| {
@@ -225,7 +299,16 @@ impl Spends<Unfinished> {
                         }
                     })
                     .last()
-            }))
+            }));
+
+        #[cfg(feature = "chip-0035")]
+        let iter = iter.chain(
+            self.datastores
+                .values_mut()
+                .map(|datastore| (datastore.asset.coin, &mut datastore.spend)),
+        );
+
+        iter
     }
 
     fn emit_conditions(&mut self, ctx: &mut SpendContext) -> Result<(), DriverError> {
@@ -386,6 +469,11 @@ impl Spends<Unfinished> {
             }
         }
 
+        #[cfg(feature = "chip-0035")]
+        for datastore in self.datastores.values() {
+            p2_puzzle_hashes.push(datastore.asset.info.owner_puzzle_hash);
+        }
+
         p2_puzzle_hashes
     }
 
@@ -430,6 +518,11 @@ impl Spends<Unfinished> {
             }
         }
 
+        #[cfg(feature = "chip-0035")]
+        for datastore in self.datastores.values() {
+            coin_ids.push(datastore.asset.coin.coin_id());
+        }
+
         coin_ids
     }
 
@@ -449,8 +542,13 @@ impl Spends<Unfinished> {
             dids: self.dids,
             nfts: self.nfts,
             options: self.options,
+            #[cfg(feature = "chip-0035")]
+            datastores: self.datastores,
             intermediate_puzzle_hash: self.intermediate_puzzle_hash,
             change_puzzle_hash: self.change_puzzle_hash,
+            clawbacks: self.clawbacks,
+            option_underlyings: self.option_underlyings,
+            streams: self.streams,
             outputs: self.outputs,
             conditions: self.conditions,
             _state: Finished,
@@ -468,34 +566,214 @@ impl Spends<Unfinished> {
         let mut coin_spends = HashMap::new();
 
         for (asset, kind) in spends.unspent() {
-            match kind {
+            let spend = match kind {
                 SpendKind::Conditions(spend) => {
-                    let Some(&synthetic_key) = synthetic_keys.get(&asset.p2_puzzle_hash()) else {
+                    let custody_puzzle_hash = spends.custody_puzzle_hash(asset)?;
+
+                    let Some(&synthetic_key) = synthetic_keys.get(&custody_puzzle_hash) else {
                         return Err(DriverError::MissingKey);
                     };
-                    coin_spends.insert(
-                        asset.coin().coin_id(),
-                        StandardLayer::new(synthetic_key)
-                            .spend_with_conditions(ctx, spend.finish())?,
-                    );
+
+                    let inner_spend = StandardLayer::new(synthetic_key)
+                        .spend_with_conditions(ctx, spend.finish())?;
+
+                    spends.wrap_custody_spend(ctx, asset, inner_spend)?
                 }
-                SpendKind::Settlement(spend) => {
-                    coin_spends.insert(
+                SpendKind::Settlement(spend) => SettlementLayer
+                    .construct_spend(ctx, SettlementPaymentsSolution::new(spend.finish()))?,
+            };
+
+            coin_spends.insert(asset.coin().coin_id(), spend);
+        }
+
+        // Data stores are always spent by their owner.
+        #[cfg(feature = "chip-0035")]
+        for datastore in spends.datastores.values() {
+            let Some(&synthetic_key) = synthetic_keys.get(&datastore.asset.info.owner_puzzle_hash)
+            else {
+                return Err(DriverError::MissingKey);
+            };
+
+            coin_spends.insert(
+                datastore.asset.coin.coin_id(),
+                StandardLayer::new(synthetic_key)
+                    .spend_with_conditions(ctx, datastore.spend.clone().finish())?,
+            );
+        }
+
+        spends.spend(ctx, coin_spends)
+    }
+
+    /// Finishes the transaction for assets that are owned by a vault, rather than by standard BLS keys.
+    ///
+    /// The custody of each asset is looked up by its custody puzzle hash. Assets with vault custody
+    /// must belong to the given vault, and the vault singleton sends a message to authorize the
+    /// delegated spend of each of their coins. Assets with standard custody are spent as usual, and
+    /// still need to be signed by their synthetic keys. The returned [`VaultSpend`] still needs to be
+    /// signed by the vault's members before it can be finished.
+    pub fn finish_with_vault(
+        self,
+        ctx: &mut SpendContext,
+        deltas: &Deltas,
+        relation: Relation,
+        vault: Vault,
+        custody: &IndexMap<Bytes32, Custody>,
+    ) -> Result<VaultSpend, DriverError> {
+        let spends = self.prepare(ctx, deltas, relation)?;
+        let mut coin_spends = HashMap::new();
+        let mut vault_conditions = Conditions::new();
+
+        for (asset, kind) in spends.unspent() {
+            let spend = match kind {
+                SpendKind::Conditions(spend) => {
+                    let custody_puzzle_hash = spends.custody_puzzle_hash(asset)?;
+
+                    let inner_spend = custody_spend(
+                        ctx,
+                        &vault,
+                        custody,
+                        &mut vault_conditions,
                         asset.coin().coin_id(),
-                        SettlementLayer.construct_spend(
-                            ctx,
-                            SettlementPaymentsSolution::new(spend.finish()),
-                        )?,
-                    );
+                        custody_puzzle_hash,
+                        spend.finish(),
+                    )?;
+
+                    spends.wrap_custody_spend(ctx, asset, inner_spend)?
                 }
-            }
+                SpendKind::Settlement(spend) => SettlementLayer
+                    .construct_spend(ctx, SettlementPaymentsSolution::new(spend.finish()))?,
+            };
+
+            coin_spends.insert(asset.coin().coin_id(), spend);
         }
 
-        spends.spend(ctx, coin_spends)
+        #[cfg(feature = "chip-0035")]
+        for datastore in spends.datastores.values() {
+            let spend = custody_spend(
+                ctx,
+                &vault,
+                custody,
+                &mut vault_conditions,
+                datastore.asset.coin.coin_id(),
+                datastore.asset.info.owner_puzzle_hash,
+                datastore.spend.clone().finish(),
+            )?;
+
+            coin_spends.insert(datastore.asset.coin.coin_id(), spend);
+        }
+
+        let outputs = spends.spend(ctx, coin_spends)?;
+
+        // The vault is recreated with the same custody configuration and amount.
+        let delegated_spend = ctx.delegated_spend(vault_conditions.create_coin(
+            vault.info.custody_hash.into(),
+            vault.coin.amount,
+            Memos::None,
+        ))?;
+        let delegated_puzzle_hash = ctx.tree_hash(delegated_spend.puzzle).into();
+
+        Ok(VaultSpend::new(
+            vault,
+            delegated_spend,
+            delegated_puzzle_hash,
+            outputs,
+        ))
     }
 }
 
+fn custody_spend(
+    ctx: &mut SpendContext,
+    vault: &Vault,
+    custody: &IndexMap<Bytes32, Custody>,
+    vault_conditions: &mut Conditions,
+    coin_id: Bytes32,
+    custody_puzzle_hash: Bytes32,
+    mut conditions: Conditions,
+) -> Result<Spend, DriverError> {
+    let p2_puzzle = match custody.get(&custody_puzzle_hash) {
+        Some(Custody::Standard(synthetic_key)) => {
+            return StandardLayer::new(*synthetic_key).spend_with_conditions(ctx, conditions);
+        }
+        Some(Custody::Vault(p2_puzzle)) => *p2_puzzle,
+        None => return Err(DriverError::UnsupportedCustody(custody_puzzle_hash)),
+    };
+
+    if p2_puzzle.launcher_id() != vault.info.launcher_id {
+        return Err(DriverError::ConflictingVaultLauncherIds);
+    }
+
+    // Otherwise, the spend could be swapped for the fixed conditions path.
+    if let VaultP2Puzzle::P2ConditionsOrSingleton(_) = p2_puzzle {
+        conditions = conditions.assert_my_coin_id(coin_id);
+    }
+
+    let delegated_spend = ctx.delegated_spend(conditions)?;
+    let delegated_puzzle_hash = ctx.tree_hash(delegated_spend.puzzle);
+
+    let mode = MessageFlags::PUZZLE.encode(MessageSide::Sender)
+        | MessageFlags::COIN.encode(MessageSide::Receiver);
+    let receiver = ctx.alloc(&coin_id)?;
+
+    vault_conditions.push(SendMessage::new(
+        mode,
+        delegated_puzzle_hash.to_vec().into(),
+        vec![receiver],
+    ));
+
+    p2_puzzle.spend(
+        ctx,
+        vault.info.custody_hash.into(),
+        vault.coin.amount,
+        delegated_spend,
+    )
+}
+
 impl Spends<Finished> {
+    /// Returns the puzzle hash that has custody over the asset, taking clawbacks and option underlyings into account.
+    pub fn custody_puzzle_hash(&self, asset: SpendableAsset) -> Result<Bytes32, DriverError> {
+        let p2_puzzle_hash = asset.p2_puzzle_hash();
+
+        // Clawback coins are spent by either the sender or receiver, depending on the path.
+        if let Some(info) = self.clawbacks.get(&p2_puzzle_hash) {
+            return match info.path {
+                ClawbackPath::Sender => Ok(info.clawback.sender_puzzle_hash),
+                ClawbackPath::Receiver => Ok(info.clawback.receiver_puzzle_hash),
+                ClawbackPath::PushThrough => Err(DriverError::CannotEmitConditions),
+            };
+        }
+
+        // Expired option underlying coins are spent by the creator of the option.
+        if let Some(underlying) = self.option_underlyings.get(&p2_puzzle_hash) {
+            return Ok(underlying.creator_puzzle_hash);
+        }
+
+        Ok(p2_puzzle_hash)
+    }
+
+    /// Wraps the spend of the custody puzzle in the outer p2 puzzle of the asset, if there is one.
+    pub fn wrap_custody_spend(
+        &self,
+        ctx: &mut SpendContext,
+        asset: SpendableAsset,
+        mut inner_spend: Spend,
+    ) -> Result<Spend, DriverError> {
+        let p2_puzzle_hash = asset.p2_puzzle_hash();
+
+        if let Some(info) = self.clawbacks.get(&p2_puzzle_hash) {
+            inner_spend = if info.path == ClawbackPath::Sender {
+                info.clawback.sender_spend(ctx, inner_spend)?
+            } else {
+                info.clawback.receiver_spend(ctx, inner_spend)?
+            };
+        }
+
+        if let Some(underlying) = self.option_underlyings.get(&p2_puzzle_hash) {
+            inner_spend = underlying.clawback_spend(ctx, inner_spend)?;
+        }
+
+        Ok(inner_spend)
+    }
+
     pub fn unspent(&self) -> Vec<(SpendableAsset, SpendKind)> {
         let mut result = Vec::new();
 
@@ -580,6 +858,21 @@ impl Spends<Finished> {
             }
         }
 
+        #[cfg(feature = "chip-0035")]
+        for datastore in self.datastores.into_values() {
+            let spend = coin_spends
+                .remove(&datastore.asset.coin.coin_id())
+                .ok_or(DriverError::MissingSpend)?;
+            let coin_spend = datastore.asset.spend(ctx, spend)?;
+            ctx.insert(coin_spend);
+        }
+
+        for stream in self.streams {
+            stream
+                .asset
+                .spend(ctx, stream.payment_time, stream.clawback)?;
+        }
+
         Ok(self.outputs)
     }
 }
@@ -631,3 +924,13 @@ impl AddAsset for OptionContract {
         );
     }
 }
+
+#[cfg(feature = "chip-0035")]
+impl AddAsset for DataStore {
+    fn add(self, spends: &mut Spends) {
+        spends.datastores.insert(
+            Id::Existing(self.info.launcher_id),
+            DataStoreSpend::new(self, false),
+        );
+    }
+}
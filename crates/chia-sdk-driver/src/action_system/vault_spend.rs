@@ -0,0 +1,371 @@
+use chia_protocol::Bytes32;
+use clvm_utils::TreeHash;
+
+use crate::{
+    DriverError, InnerPuzzleSpend, MipsSpend, MipsSpendKind, MofN, Outputs, Restriction, Spend,
+    SpendContext, Vault, mips_puzzle_hash,
+};
+
+/// A transaction that has been prepared with [`Spends::finish_with_vault`](crate::Spends::finish_with_vault),
+/// but which still needs to be authorized by the members of the vault.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct VaultSpend {
+    pub vault: Vault,
+    pub mips_spend: MipsSpend,
+    /// The hash of the delegated puzzle, which is what the members of the vault sign.
+    pub delegated_puzzle_hash: Bytes32,
+    pub outputs: Outputs,
+}
+
+impl VaultSpend {
+    pub fn new(
+        vault: Vault,
+        delegated_spend: Spend,
+        delegated_puzzle_hash: Bytes32,
+        outputs: Outputs,
+    ) -> Self {
+        Self {
+            vault,
+            mips_spend: MipsSpend::new(delegated_spend),
+            delegated_puzzle_hash,
+            outputs,
+        }
+    }
+
+    /// Adds the spend of a member puzzle, and returns its puzzle hash within the vault's custody.
+    /// Only the root of the custody tree is top level, and its puzzle hash is the custody hash.
+    pub fn add_member(
+        &mut self,
+        ctx: &SpendContext,
+        nonce: usize,
+        restrictions: Vec<Restriction>,
+        spend: Spend,
+        top_level: bool,
+    ) -> TreeHash {
+        let puzzle_hash = mips_puzzle_hash(
+            nonce,
+            restrictions.clone(),
+            ctx.tree_hash(spend.puzzle),
+            top_level,
+        );
+
+        self.mips_spend.members.insert(
+            puzzle_hash,
+            InnerPuzzleSpend::new(nonce, restrictions, spend),
+        );
+
+        puzzle_hash
+    }
+
+    /// Adds an m of n threshold over the given member puzzle hashes, and returns its puzzle hash within the vault's custody.
+    pub fn add_m_of_n(
+        &mut self,
+        nonce: usize,
+        restrictions: Vec<Restriction>,
+        m_of_n: MofN,
+        top_level: bool,
+    ) -> TreeHash {
+        let puzzle_hash = mips_puzzle_hash(
+            nonce,
+            restrictions.clone(),
+            m_of_n.inner_puzzle_hash(),
+            top_level,
+        );
+
+        self.mips_spend.members.insert(
+            puzzle_hash,
+            InnerPuzzleSpend {
+                nonce,
+                restrictions,
+                kind: MipsSpendKind::MofN(m_of_n),
+            },
+        );
+
+        puzzle_hash
+    }
+
+    pub fn add_restriction(&mut self, puzzle_hash: TreeHash, spend: Spend) {
+        self.mips_spend.restrictions.insert(puzzle_hash, spend);
+    }
+
+    /// Spends the vault singleton, which authorizes the rest of the transaction.
+    pub fn finish(self, ctx: &mut SpendContext) -> Result<Outputs, DriverError> {
+        self.vault.spend(ctx, &self.mips_spend)?;
+        Ok(self.outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_bls::PublicKey;
+    use chia_puzzle_types::Memos;
+    use chia_sdk_test::{BlsPairWithCoin, Simulator};
+    use chia_sdk_types::{Mod, puzzles::BlsMember};
+    use clvm_utils::ToTreeHash;
+    use clvmr::NodePtr;
+    use indexmap::{IndexMap, indexmap};
+    use rstest::rstest;
+
+    use crate::{
+        Action, Custody, Id, Launcher, P2ConditionsOrSingleton, P2Singleton, Relation, Reveals,
+        Spends, StandardLayer, VaultP2Puzzle, parse_vault_transaction,
+    };
+
+    use super::*;
+
+    fn mint_vault(
+        sim: &mut Simulator,
+        ctx: &mut SpendContext,
+        funder: &BlsPairWithCoin,
+        custody_hash: TreeHash,
+        p2_puzzle_hash: Bytes32,
+    ) -> Result<Vault> {
+        let (mut conditions, vault) =
+            Launcher::new(funder.coin.coin_id(), 1).mint_vault(ctx, custody_hash, ())?;
+
+        conditions = conditions.create_coin(p2_puzzle_hash, funder.coin.amount - 1, Memos::None);
+
+        StandardLayer::new(funder.pk).spend(ctx, funder.coin, conditions)?;
+        sim.spend_coins(ctx.take(), slice::from_ref(&funder.sk))?;
+
+        Ok(vault)
+    }
+
+    fn bls_member_spend(ctx: &mut SpendContext, public_key: PublicKey) -> Result<Spend> {
+        let puzzle = ctx.curry(BlsMember::new(public_key))?;
+        Ok(Spend::new(puzzle, NodePtr::NIL))
+    }
+
+    #[rstest]
+    fn test_vault_spend(#[values(false, true)] conditions_or_singleton: bool) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1001);
+        let bob = sim.bls(0);
+
+        let custody_hash =
+            mips_puzzle_hash(0, vec![], BlsMember::new(alice.pk).curry_tree_hash(), true);
+
+        // The launcher id is needed to calculate the p2 puzzle hash, so the launcher is created up front.
+        let launcher_id = Launcher::new(alice.coin.coin_id(), 1).coin().coin_id();
+
+        let p2_puzzle = if conditions_or_singleton {
+            VaultP2Puzzle::P2ConditionsOrSingleton(
+                P2ConditionsOrSingleton::from_quoted_conditions_hash(
+                    launcher_id,
+                    0,
+                    Bytes32::default(),
+                ),
+            )
+        } else {
+            VaultP2Puzzle::P2Singleton(P2Singleton::new(launcher_id, 0))
+        };
+        let p2_puzzle_hash = p2_puzzle.tree_hash().into();
+
+        let vault = mint_vault(&mut sim, &mut ctx, &alice, custody_hash, p2_puzzle_hash)?;
+        let coin = sim.unspent_coins(p2_puzzle_hash, false)[0];
+
+        let mut spends = Spends::new(p2_puzzle_hash);
+        spends.add(coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::send(Id::Xch, bob.puzzle_hash, 600, Memos::None),
+                Action::fee(100),
+            ],
+        )?;
+
+        let mut vault_spend = spends.finish_with_vault(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            vault,
+            &indexmap! { p2_puzzle_hash => Custody::Vault(p2_puzzle) },
+        )?;
+
+        let delegated_spend = vault_spend.mips_spend.delegated;
+        let delegated_puzzle_hash = vault_spend.delegated_puzzle_hash;
+        assert_eq!(
+            delegated_puzzle_hash,
+            ctx.tree_hash(delegated_spend.puzzle).into()
+        );
+
+        let member_spend = bls_member_spend(&mut ctx, alice.pk)?;
+        let member_hash = vault_spend.add_member(&ctx, 0, vec![], member_spend, true);
+        assert_eq!(member_hash, custody_hash);
+
+        let outputs = vault_spend.finish(&mut ctx)?;
+        let coin_spends = ctx.take();
+
+        // The transaction should be verifiable by the members of the vault before they sign it.
+        let mut reveals = Reveals::from_coin_spends(&mut ctx, &coin_spends)?;
+        if let VaultP2Puzzle::P2ConditionsOrSingleton(p2) = p2_puzzle {
+            reveals.reveal_p2_conditions_or_singleton(p2);
+        }
+        let tx = parse_vault_transaction(reveals, &mut ctx, launcher_id, delegated_spend)?;
+        assert_eq!(tx.delegated_puzzle_hash, delegated_puzzle_hash);
+        assert_eq!(tx.spends.len(), 1);
+        assert_eq!(tx.reserved_fee, 100);
+
+        sim.spend_coins(coin_spends, &[alice.sk])?;
+
+        assert!(
+            sim.unspent_coins(bob.puzzle_hash, false)
+                .iter()
+                .any(|coin| coin.amount == 600)
+        );
+        assert!(
+            outputs
+                .xch
+                .iter()
+                .any(|coin| coin.puzzle_hash == p2_puzzle_hash && coin.amount == 300)
+        );
+        assert_ne!(
+            sim.coin_state(vault.child(custody_hash, 1).coin.coin_id()),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vault_spend_m_of_n() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1001);
+        let keys = [sim.bls(0), sim.bls(0), sim.bls(0)];
+
+        let member_hashes = keys
+            .iter()
+            .map(|key| mips_puzzle_hash(0, vec![], BlsMember::new(key.pk).curry_tree_hash(), false))
+            .collect::<Vec<_>>();
+        let m_of_n = MofN::new(2, member_hashes.clone());
+        let custody_hash = mips_puzzle_hash(0, vec![], m_of_n.inner_puzzle_hash(), true);
+
+        let launcher_id = Launcher::new(alice.coin.coin_id(), 1).coin().coin_id();
+        let p2_puzzle = P2Singleton::new(launcher_id, 0);
+        let p2_puzzle_hash = p2_puzzle.tree_hash().into();
+
+        let vault = mint_vault(&mut sim, &mut ctx, &alice, custody_hash, p2_puzzle_hash)?;
+        let coin = sim.unspent_coins(p2_puzzle_hash, false)[0];
+
+        let mut spends = Spends::new(p2_puzzle_hash);
+        spends.add(coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::fee(1000)])?;
+
+        let mut vault_spend = spends.finish_with_vault(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            vault,
+            &indexmap! { p2_puzzle_hash => Custody::Vault(p2_puzzle.into()) },
+        )?;
+
+        assert_eq!(
+            vault_spend.add_m_of_n(0, vec![], m_of_n, true),
+            custody_hash
+        );
+
+        for key in &keys[..2] {
+            let member_spend = bls_member_spend(&mut ctx, key.pk)?;
+            let member_hash = vault_spend.add_member(&ctx, 0, vec![], member_spend, false);
+            assert!(member_hashes.contains(&member_hash));
+        }
+
+        vault_spend.finish(&mut ctx)?;
+
+        sim.spend_coins(ctx.take(), &[keys[0].sk.clone(), keys[1].sk.clone()])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vault_spend_mixed_custody() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1001);
+        let bob = sim.bls(500);
+
+        let custody_hash =
+            mips_puzzle_hash(0, vec![], BlsMember::new(alice.pk).curry_tree_hash(), true);
+
+        let launcher_id = Launcher::new(alice.coin.coin_id(), 1).coin().coin_id();
+        let p2_puzzle = P2Singleton::new(launcher_id, 0);
+        let p2_puzzle_hash = p2_puzzle.tree_hash().into();
+
+        let vault = mint_vault(&mut sim, &mut ctx, &alice, custody_hash, p2_puzzle_hash)?;
+        let coin = sim.unspent_coins(p2_puzzle_hash, false)[0];
+
+        let mut spends = Spends::new(p2_puzzle_hash);
+        spends.add(coin);
+        spends.add(bob.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::fee(1200)])?;
+
+        let mut vault_spend = spends.finish_with_vault(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            vault,
+            &indexmap! {
+                p2_puzzle_hash => Custody::Vault(p2_puzzle.into()),
+                bob.puzzle_hash => Custody::Standard(bob.pk),
+            },
+        )?;
+
+        let member_spend = bls_member_spend(&mut ctx, alice.pk)?;
+        vault_spend.add_member(&ctx, 0, vec![], member_spend, true);
+
+        let outputs = vault_spend.finish(&mut ctx)?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk, bob.sk])?;
+
+        assert!(
+            outputs
+                .xch
+                .iter()
+                .any(|coin| coin.puzzle_hash == p2_puzzle_hash && coin.amount == 300)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vault_spend_unknown_p2_puzzle() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1001);
+
+        let custody_hash =
+            mips_puzzle_hash(0, vec![], BlsMember::new(alice.pk).curry_tree_hash(), true);
+
+        let launcher_id = Launcher::new(alice.coin.coin_id(), 1).coin().coin_id();
+        let p2_puzzle = P2Singleton::new(launcher_id, 0);
+        let p2_puzzle_hash = p2_puzzle.tree_hash().into();
+
+        let vault = mint_vault(&mut sim, &mut ctx, &alice, custody_hash, p2_puzzle_hash)?;
+        let coin = sim.unspent_coins(p2_puzzle_hash, false)[0];
+
+        let mut spends = Spends::new(p2_puzzle_hash);
+        spends.add(coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::fee(1000)])?;
+
+        assert!(matches!(
+            spends.finish_with_vault(&mut ctx, &deltas, Relation::None, vault, &IndexMap::new()),
+            Err(DriverError::UnsupportedCustody(puzzle_hash)) if puzzle_hash == p2_puzzle_hash
+        ));
+
+        Ok(())
+    }
+}
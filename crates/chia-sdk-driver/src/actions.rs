@@ -1,23 +1,53 @@
+mod cancel_offer;
+mod consolidate;
 mod create_did;
+mod create_stream;
+mod exercise_option;
 mod fee;
 mod issue_cat;
 mod melt_singleton;
 mod mint_nft;
+mod mint_nfts;
 mod mint_option;
+mod reclaim_option_underlying;
 mod run_tail;
 mod send;
+mod send_with_clawback;
 mod settle;
+mod spend_clawback;
+mod spend_stream;
+mod split;
 mod update_did;
 mod update_nft;
 
+pub use cancel_offer::*;
+pub use consolidate::*;
 pub use create_did::*;
+pub use create_stream::*;
+pub use exercise_option::*;
 pub use fee::*;
 pub use issue_cat::*;
 pub use melt_singleton::*;
 pub use mint_nft::*;
+pub use mint_nfts::*;
 pub use mint_option::*;
+pub use reclaim_option_underlying::*;
 pub use run_tail::*;
 pub use send::*;
+pub use send_with_clawback::*;
 pub use settle::*;
+pub use spend_clawback::*;
+pub use spend_stream::*;
+pub use split::*;
 pub use update_did::*;
 pub use update_nft::*;
+
+#[cfg(feature = "chip-0035")]
+mod mint_datastore;
+#[cfg(feature = "chip-0035")]
+mod update_datastore;
+
+#[cfg(feature = "chip-0035")]
+pub use mint_datastore::*;
+#[cfg(feature = "chip-0035")]
+pub use update_datastore::*;
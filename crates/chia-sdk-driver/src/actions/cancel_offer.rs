@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+
+use crate::{
+    Cat, Deltas, DriverError, Nft, Offer, OptionContract, Puzzle, SpendAction, SpendContext, Spends,
+};
+
+/// Cancels an offer by spending the coins that it offered, so that it can no longer be taken.
+///
+/// The XCH, CAT, NFT and option contract coins are added to the spends, and are sent back to the
+/// change puzzle hash unless they're used by other actions. This means that multiple offers can be
+/// cancelled in the same transaction, with a single fee.
+#[derive(Debug, Clone)]
+pub struct CancelOfferAction {
+    pub offer: Offer,
+}
+
+impl CancelOfferAction {
+    pub fn new(offer: Offer) -> Self {
+        Self { offer }
+    }
+}
+
+impl SpendAction for CancelOfferAction {
+    fn calculate_delta(&self, _deltas: &mut Deltas, _index: usize) {}
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let mut spent_coin_ids: HashSet<_> = spends.non_settlement_coin_ids().into_iter().collect();
+
+        for coin_spend in self.offer.cancellable_coin_spends()? {
+            let coin = coin_spend.coin;
+
+            // The same coins can be offered more than once, but they can only be spent once.
+            if !spent_coin_ids.insert(coin.coin_id()) {
+                continue;
+            }
+
+            let puzzle = ctx.alloc(&coin_spend.puzzle_reveal)?;
+            let puzzle = Puzzle::parse(ctx, puzzle);
+            let solution = ctx.alloc(&coin_spend.solution)?;
+
+            if let Some(parsed) = Cat::parse(ctx, coin, puzzle, solution)? {
+                spends.add(parsed.cat);
+            } else if let Some((nft, _, _)) = Nft::parse(ctx, coin, puzzle, solution)? {
+                spends.add(nft);
+            } else if let Some((option, _, _)) = OptionContract::parse(ctx, coin, puzzle, solution)?
+            {
+                spends.add(option);
+            } else {
+                spends.add(coin);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_puzzle_types::Memos;
+    use chia_puzzles::SETTLEMENT_PAYMENT_HASH;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+
+    use crate::{
+        Action, Id, OfferExpiration, Relation, RequestedAsset, finish_with_key, make_offer,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_action_cancel_offers() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1101);
+
+        // Mint an NFT and issue a CAT
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::mint_empty_nft(),
+                Action::single_issue_cat(None, 100),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let nft = outputs.nfts[&Id::New(0)];
+        let cat = outputs.cats[&Id::New(1)][0];
+        let coin = outputs.xch[0];
+
+        // Make an offer for XCH, and another for the NFT and CAT
+        let finish = |ctx: &mut SpendContext, spends: Spends, deltas: &Deltas| {
+            finish_with_key(ctx, spends, deltas, &alice)
+        };
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(coin);
+
+        let xch_offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![Action::send(
+                Id::Xch,
+                SETTLEMENT_PAYMENT_HASH.into(),
+                500,
+                Memos::None,
+            )],
+            &[RequestedAsset::cat(cat.info.asset_id, None, 50)],
+            OfferExpiration::default(),
+            finish,
+        )?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(nft);
+        spends.add(cat);
+
+        let id = Id::Existing(cat.info.asset_id);
+
+        let nft_offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![
+                Action::send(
+                    Id::Existing(nft.info.launcher_id),
+                    SETTLEMENT_PAYMENT_HASH.into(),
+                    1,
+                    Memos::None,
+                ),
+                Action::send(id, SETTLEMENT_PAYMENT_HASH.into(), 100, Memos::None),
+            ],
+            &[RequestedAsset::xch(1000)],
+            OfferExpiration::default(),
+            finish,
+        )?;
+
+        // Cancel both offers
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::cancel_offer(xch_offer.clone()),
+                Action::cancel_offer(nft_offer),
+                Action::cancel_offer(xch_offer),
+                Action::fee(10),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert!(
+            sim.coin_state(coin.coin_id())
+                .unwrap()
+                .spent_height
+                .is_some()
+        );
+        assert_eq!(outputs.xch.len(), 1);
+        assert_eq!(outputs.xch[0].amount, coin.amount - 10);
+        assert_eq!(outputs.cats[&id][0].coin.amount, 100);
+        assert_eq!(outputs.cats[&id][0].info.p2_puzzle_hash, alice.puzzle_hash);
+        assert_eq!(
+            outputs.nfts[&Id::Existing(nft.info.launcher_id)]
+                .info
+                .p2_puzzle_hash,
+            alice.puzzle_hash
+        );
+
+        Ok(())
+    }
+}
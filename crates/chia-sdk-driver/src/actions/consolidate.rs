@@ -0,0 +1,354 @@
+use chia_sdk_utils::MAX_COINS;
+
+use crate::{
+    Asset, Batch, Deltas, DriverError, FungibleAsset, Id, SpendAction, SpendContext, Spends,
+    build_batches,
+};
+
+/// Combines the smallest XCH or CAT coins that have been added to the spends into a single coin.
+///
+/// At most `max_coins` coins are spent, and never more than [`MAX_COINS`], so that the transaction
+/// stays within the limits of coin selection. The largest of the remaining coins are removed from the
+/// spends, so that they can be consolidated later, unless they're already used by an earlier action.
+///
+/// Any amount that isn't used by other actions is already sent to the change puzzle hash as a single
+/// coin, so this action also ensures that the coins are spent even if nothing else uses them.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsolidateAction {
+    pub id: Id,
+    pub max_coins: usize,
+}
+
+impl ConsolidateAction {
+    pub fn new(id: Id, max_coins: usize) -> Self {
+        Self { id, max_coins }
+    }
+
+    /// Consolidates the coins in batches of up to `max_coins`, each of which is built into its own
+    /// spend bundle with the `build` function. See [`build_batches`] for how the cost is limited.
+    ///
+    /// The smallest coins are consolidated first, since they're the most expensive to spend relative
+    /// to their value. The batch size can't exceed [`MAX_COINS`], so that the resulting coins can be
+    /// used by coin selection later.
+    pub fn build_batches<A, T, F>(
+        ctx: &mut SpendContext,
+        mut coins: Vec<A>,
+        max_coins: usize,
+        max_cost: u64,
+        build: F,
+    ) -> Result<Vec<Batch<T>>, DriverError>
+    where
+        A: FungibleAsset,
+        F: FnMut(&mut SpendContext, &[A]) -> Result<T, DriverError>,
+    {
+        coins.sort_by_key(Asset::amount);
+
+        build_batches(ctx, coins, max_coins.min(MAX_COINS), max_cost, build)
+    }
+}
+
+impl SpendAction for ConsolidateAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.set_needed(self.id);
+    }
+
+    fn spend(
+        &self,
+        _ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let max_coins = self.max_coins.min(MAX_COINS);
+
+        if matches!(self.id, Id::Xch) {
+            spends.xch.retain_smallest(max_coins)
+        } else if let Some(cat) = spends.cats.get_mut(&self.id) {
+            cat.retain_smallest(max_coins)
+        } else {
+            Err(DriverError::InvalidAssetId)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_protocol::{Bytes32, Coin};
+    use chia_puzzle_types::Memos;
+    use chia_sdk_test::Simulator;
+    use chia_sdk_utils::CoinSelectionError;
+    use indexmap::indexmap;
+
+    use crate::{Action, Relation, spend_bundle_cost};
+
+    use super::*;
+
+    #[test]
+    fn test_action_consolidate_xch() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        for amount in 2..=5 {
+            spends.add(sim.new_coin(alice.puzzle_hash, amount));
+        }
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::consolidate(Id::Xch, 10), Action::fee(3)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert_eq!(outputs.xch.len(), 1);
+        assert_eq!(outputs.xch[0].amount, 12);
+        assert_eq!(sim.unspent_coins(alice.puzzle_hash, false), outputs.xch);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_consolidate_smallest() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        // The coins are added out of order, to ensure that they're sorted by amount.
+        for amount in [5, 2, 4, 3] {
+            spends.add(sim.new_coin(alice.puzzle_hash, amount));
+        }
+
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::consolidate(Id::Xch, 3)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert_eq!(outputs.xch.len(), 1);
+        assert_eq!(outputs.xch[0].amount, 6);
+
+        let mut amounts: Vec<u64> = sim
+            .unspent_coins(alice.puzzle_hash, false)
+            .iter()
+            .map(|coin| coin.amount)
+            .collect();
+        amounts.sort_unstable();
+        assert_eq!(amounts, [4, 5, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_consolidate_more_than_max_coins() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let count = MAX_COINS as u64 + 10;
+
+        for amount in 2..=count {
+            spends.add(sim.new_coin(alice.puzzle_hash, amount));
+        }
+
+        let deltas = spends.apply(&mut ctx, &[Action::consolidate(Id::Xch, usize::MAX)])?;
+
+        assert_eq!(spends.xch.items.len(), MAX_COINS);
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let max = MAX_COINS as u64;
+        assert_eq!(outputs.xch.len(), 1);
+        assert_eq!(outputs.xch[0].amount, max * (max + 1) / 2);
+        // The ten largest coins are left for a later consolidation.
+        assert_eq!(sim.unspent_coins(alice.puzzle_hash, false).len(), 11);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_consolidate_used_coins() {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+        spends.add(sim.new_coin(alice.puzzle_hash, 1));
+
+        // The coin used by the send can't be removed, so the limit can't be met.
+        assert!(matches!(
+            spends.apply(
+                &mut ctx,
+                &[
+                    Action::send(Id::Xch, alice.puzzle_hash, 2, Memos::None),
+                    Action::consolidate(Id::Xch, 0),
+                ]
+            ),
+            Err(DriverError::CoinSelection(
+                CoinSelectionError::ExceededMaxCoins
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_action_consolidate_cat() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(6);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(None, 6),
+                Action::split(Id::New(0), alice.puzzle_hash, 2, 3),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let cats = &outputs.cats[&Id::New(0)];
+        assert_eq!(cats.len(), 3);
+
+        let id = Id::Existing(cats[0].info.asset_id);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        for &cat in cats {
+            spends.add(cat);
+        }
+
+        let deltas = spends.apply(&mut ctx, &[Action::consolidate(id, 10)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert_eq!(outputs.cats[&id].len(), 1);
+        assert_eq!(outputs.cats[&id][0].coin.amount, 6);
+        assert_eq!(outputs.cats[&id][0].info.p2_puzzle_hash, alice.puzzle_hash);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_consolidate_invalid_asset() {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        assert!(matches!(
+            spends.apply(
+                &mut ctx,
+                &[Action::consolidate(Id::Existing(Bytes32::default()), 10)]
+            ),
+            Err(DriverError::InvalidAssetId)
+        ));
+    }
+
+    #[test]
+    fn test_consolidate_batches() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut coins = vec![alice.coin];
+
+        for amount in 2..=10 {
+            coins.push(sim.new_coin(alice.puzzle_hash, amount));
+        }
+
+        let max_cost = 15_000_000;
+
+        let batches = ConsolidateAction::build_batches(
+            &mut ctx,
+            coins,
+            4,
+            max_cost,
+            |ctx, coins: &[Coin]| {
+                let mut spends = Spends::new(alice.puzzle_hash);
+
+                for &coin in coins {
+                    spends.add(coin);
+                }
+
+                let deltas = spends.apply(ctx, &[Action::consolidate(Id::Xch, coins.len())])?;
+
+                spends.finish_with_keys(
+                    ctx,
+                    &deltas,
+                    Relation::None,
+                    &indexmap! { alice.puzzle_hash => alice.pk },
+                )
+            },
+        )?;
+
+        assert!(batches.len() > 3);
+
+        for batch in batches {
+            assert!(spend_bundle_cost(&batch.coin_spends)? <= max_cost);
+            assert_eq!(batch.value.xch.len(), 1);
+            sim.spend_coins(batch.coin_spends, slice::from_ref(&alice.sk))?;
+        }
+
+        let coins = sim.unspent_coins(alice.puzzle_hash, false);
+        assert_eq!(coins.iter().map(|coin| coin.amount).sum::<u64>(), 55);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+use chia_protocol::Bytes32;
+use chia_puzzle_types::Memos;
+
+use crate::{
+    Deltas, DriverError, Id, SendAction, SpendAction, SpendContext, Spends, StreamedAsset,
+    StreamingPuzzleInfo,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct CreateStreamAction {
+    pub id: Id,
+    pub recipient: Bytes32,
+    pub clawback_puzzle_hash: Option<Bytes32>,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub amount: u64,
+}
+
+impl CreateStreamAction {
+    pub fn new(
+        id: Id,
+        recipient: Bytes32,
+        clawback_puzzle_hash: Option<Bytes32>,
+        start_time: u64,
+        end_time: u64,
+        amount: u64,
+    ) -> Self {
+        Self {
+            id,
+            recipient,
+            clawback_puzzle_hash,
+            start_time,
+            end_time,
+            amount,
+        }
+    }
+}
+
+impl SpendAction for CreateStreamAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(self.id).output += self.amount;
+        deltas.set_needed(self.id);
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        index: usize,
+    ) -> Result<(), DriverError> {
+        let info = StreamingPuzzleInfo::new(
+            self.recipient,
+            self.clawback_puzzle_hash,
+            self.end_time,
+            self.start_time,
+        );
+
+        // Streams can only be created from XCH or CATs, so singletons are rejected before sending.
+        if !matches!(self.id, Id::Xch) && !spends.cats.contains_key(&self.id) {
+            return Err(DriverError::InvalidAssetId);
+        }
+
+        let memos = Memos::Some(ctx.alloc(&info.get_launch_hints())?);
+
+        SendAction::new(self.id, info.inner_puzzle_hash().into(), self.amount, memos)
+            .spend(ctx, spends, index)?;
+
+        let stream = if matches!(self.id, Id::Xch) {
+            let coin = spends.outputs.xch.last().ok_or(DriverError::MissingChild)?;
+            StreamedAsset::xch(*coin, info)
+        } else {
+            let cat = spends
+                .outputs
+                .cats
+                .get(&self.id)
+                .and_then(|cats| cats.last())
+                .ok_or(DriverError::MissingChild)?;
+
+            // The stream puzzle is spent without a revocation layer, so it can't be wrapped in one.
+            if cat.info.hidden_puzzle_hash.is_some() {
+                return Err(DriverError::IncompatibleAssetInfo);
+            }
+
+            StreamedAsset::cat(
+                cat.coin,
+                cat.info.asset_id,
+                cat.lineage_proof.ok_or(DriverError::MissingChild)?,
+                info,
+            )
+        };
+
+        spends.outputs.streams.push(stream);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+
+    use crate::{Action, Relation};
+
+    use super::*;
+
+    #[test]
+    fn test_action_create_xch_stream() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(5);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::create_stream(
+                Id::Xch,
+                bob.puzzle_hash,
+                Some(alice.puzzle_hash),
+                0,
+                1000,
+                4,
+            )],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        let coin_spends = ctx.take();
+        sim.spend_coins(coin_spends.clone(), &[alice.sk])?;
+
+        let stream = outputs.streams[0].clone();
+        assert_eq!(stream.coin.amount, 4);
+        assert_ne!(sim.coin_state(stream.coin.coin_id()), None);
+
+        let parsed = StreamedAsset::from_parent_spend(&mut ctx, &coin_spends[0])?.0;
+        assert_eq!(parsed, Some(stream));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_create_cat_stream() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(5);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(None, 5),
+                Action::create_stream(Id::New(0), bob.puzzle_hash, None, 0, 1000, 4),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let stream = outputs.streams[0].clone();
+        assert_eq!(stream.coin.amount, 4);
+        assert_eq!(
+            stream.asset_id,
+            Some(outputs.cats[&Id::New(0)][0].info.asset_id)
+        );
+        assert_ne!(sim.coin_state(stream.coin.coin_id()), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_create_revocable_cat_stream() {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(5);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        assert!(matches!(
+            spends.apply(
+                &mut ctx,
+                &[
+                    Action::single_issue_cat(Some(Bytes32::default()), 5),
+                    Action::create_stream(Id::New(0), bob.puzzle_hash, None, 0, 1000, 4),
+                ],
+            ),
+            Err(DriverError::IncompatibleAssetInfo)
+        ));
+    }
+}
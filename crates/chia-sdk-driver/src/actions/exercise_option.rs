@@ -0,0 +1,310 @@
+use chia_protocol::Coin;
+use chia_puzzles::SETTLEMENT_PAYMENT_HASH;
+use clvm_utils::ToTreeHash;
+
+use crate::{
+    Cat, CatSpend, Deltas, DriverError, Id, OptionType, OptionUnderlying, SettleAction,
+    SingletonDestination, SingletonInfo, SpendAction, SpendContext, SpendableAsset, Spends,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExerciseOptionAction {
+    pub id: Id,
+    pub underlying: OptionUnderlying,
+    pub underlying_asset: SpendableAsset,
+    pub amount: u64,
+}
+
+impl ExerciseOptionAction {
+    pub fn new(
+        id: Id,
+        underlying: OptionUnderlying,
+        underlying_asset: SpendableAsset,
+        amount: u64,
+    ) -> Self {
+        Self {
+            id,
+            underlying,
+            underlying_asset,
+            amount,
+        }
+    }
+
+    fn strike_id(&self) -> Id {
+        match self.underlying.strike_type {
+            OptionType::Xch { .. } => Id::Xch,
+            OptionType::Cat { asset_id, .. } | OptionType::RevocableCat { asset_id, .. } => {
+                Id::Existing(asset_id)
+            }
+            OptionType::Nft { launcher_id, .. } => Id::Existing(launcher_id),
+        }
+    }
+}
+
+impl SpendAction for ExerciseOptionAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        // The option is melted, and its amount is returned as XCH.
+        deltas.set_needed(self.id);
+        deltas.update(self.id).output += self.amount;
+        deltas.update(Id::Xch).input += self.amount;
+
+        // The underlying asset is unlocked, and the strike price is paid in exchange.
+        deltas.update(self.underlying_asset.id()).input += self.underlying_asset.coin().amount;
+        deltas.update(self.strike_id()).output += self.underlying.strike_type.amount();
+        deltas.set_needed(self.strike_id());
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        index: usize,
+    ) -> Result<(), DriverError> {
+        if self.underlying_asset.p2_puzzle_hash() != self.underlying.tree_hash().into() {
+            return Err(DriverError::WrongPuzzleHash);
+        }
+
+        let Some(option) = spends.options.get_mut(&self.id) else {
+            return Err(DriverError::InvalidAssetId);
+        };
+
+        let source = option.last_mut()?;
+
+        if source.asset.info.launcher_id != self.underlying.launcher_id
+            || source.asset.info.underlying_coin_id != self.underlying_asset.coin().coin_id()
+        {
+            return Err(DriverError::InvalidAssetId);
+        }
+
+        // The strike price isn't part of the underlying puzzle hash, but the option commits to it.
+        if source.asset.info.underlying_delegated_puzzle_hash
+            != self.underlying.delegated_puzzle().tree_hash().into()
+        {
+            return Err(DriverError::WrongPuzzleHash);
+        }
+
+        // Melting the option sends the message that authorizes the underlying to be unlocked.
+        source.child_info.destination = Some(SingletonDestination::Melt);
+
+        let exercise_spend = self.underlying.exercise_spend(
+            ctx,
+            source.asset.info.inner_puzzle_hash().into(),
+            source.asset.coin.amount,
+        )?;
+
+        // The underlying is unlocked to the settlement puzzle, so it can be spent like any other offered asset.
+        let unlocked = match self.underlying_asset {
+            SpendableAsset::Xch(coin) => {
+                ctx.spend(coin, exercise_spend)?;
+                SpendableAsset::Xch(Coin::new(
+                    coin.coin_id(),
+                    SETTLEMENT_PAYMENT_HASH.into(),
+                    coin.amount,
+                ))
+            }
+            SpendableAsset::Cat(cat) => {
+                let children = Cat::spend_all(ctx, &[CatSpend::new(cat, exercise_spend)])?;
+                SpendableAsset::Cat(children[0])
+            }
+            SpendableAsset::Nft(nft) => SpendableAsset::Nft(nft.spend(ctx, exercise_spend)?),
+            SpendableAsset::Did(_) | SpendableAsset::Option(_) => {
+                return Err(DriverError::InvalidAssetId);
+            }
+        };
+
+        spends.add_ephemeral(unlocked);
+
+        // The underlying asserts that the strike price has been paid to the creator.
+        let notarized_payment = self.underlying.requested_payment(&mut **ctx)?;
+
+        SettleAction::new(self.strike_id(), notarized_payment).spend(ctx, spends, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chia_protocol::Bytes32;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+    use rstest::rstest;
+
+    use crate::{Action, Relation};
+
+    use super::*;
+
+    #[rstest]
+    fn test_action_exercise_option(
+        #[values(None, Some(Bytes32::default()))] hidden_puzzle_hash: Option<Bytes32>,
+        #[values(false, true)] expired: bool,
+    ) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+        let bob = sim.bls(5);
+        let bob_hint = ctx.hint(bob.puzzle_hash)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(hidden_puzzle_hash, 1),
+                Action::mint_option(
+                    alice.puzzle_hash,
+                    10,
+                    Id::New(0),
+                    1,
+                    OptionType::Xch { amount: 4 },
+                    1,
+                ),
+                Action::send(Id::New(1), bob.puzzle_hash, 1, bob_hint),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let underlying_cat = outputs.cats[&Id::New(0)][0];
+        let option = outputs.options[&Id::New(1)];
+        let asset_id = underlying_cat.info.asset_id;
+
+        let underlying = OptionUnderlying::new(
+            option.info.launcher_id,
+            alice.puzzle_hash,
+            10,
+            1,
+            OptionType::Xch { amount: 4 },
+        );
+
+        if expired {
+            sim.set_next_timestamp(11)?;
+        }
+
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(bob.coin);
+        spends.add(option);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::exercise_option(
+                Id::Existing(option.info.launcher_id),
+                underlying,
+                SpendableAsset::Cat(underlying_cat),
+                1,
+            )],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        let result = sim.spend_coins(ctx.take(), &[bob.sk]);
+
+        if expired {
+            assert!(result.is_err());
+            return Ok(());
+        }
+
+        result?;
+
+        let cat = outputs.cats[&Id::Existing(asset_id)][0];
+        assert_eq!(cat.info.p2_puzzle_hash, bob.puzzle_hash);
+        assert_eq!(cat.coin.amount, 1);
+        assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+
+        // The strike price is paid to the creator, and the melted option is returned as change.
+        assert!(outputs.xch.iter().any(|coin| {
+            coin.puzzle_hash == alice.puzzle_hash
+                && coin.amount == 4
+                && sim.coin_state(coin.coin_id()).is_some()
+        }));
+        assert!(
+            outputs
+                .xch
+                .iter()
+                .any(|coin| coin.puzzle_hash == bob.puzzle_hash && coin.amount == 2)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_exercise_option_wrong_underlying() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::mint_option(
+                alice.puzzle_hash,
+                10,
+                Id::Xch,
+                1,
+                OptionType::Xch { amount: 1 },
+                1,
+            )],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let option = outputs.options[&Id::New(0)];
+
+        // The underlying is locked with a different strike price than the one requested here.
+        let underlying = OptionUnderlying::new(
+            option.info.launcher_id,
+            alice.puzzle_hash,
+            10,
+            1,
+            OptionType::Xch { amount: 2 },
+        );
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(option);
+
+        let underlying_coin = outputs
+            .xch
+            .iter()
+            .find(|coin| coin.coin_id() == option.info.underlying_coin_id)
+            .copied()
+            .expect("missing underlying coin");
+
+        assert!(matches!(
+            spends.apply(
+                &mut ctx,
+                &[Action::exercise_option(
+                    Id::Existing(option.info.launcher_id),
+                    underlying,
+                    SpendableAsset::Xch(underlying_coin),
+                    1,
+                )],
+            ),
+            Err(DriverError::WrongPuzzleHash)
+        ));
+
+        Ok(())
+    }
+}
@@ -25,6 +25,12 @@ impl SpendAction for MeltSingletonAction {
         spends: &mut Spends,
         _index: usize,
     ) -> Result<(), DriverError> {
+        #[cfg(feature = "chip-0035")]
+        if let Some(datastore) = spends.datastores.get_mut(&self.id) {
+            datastore.child_info.melt = true;
+            return Ok(());
+        }
+
         if let Some(did) = spends.dids.get_mut(&self.id) {
             let source = did.last_mut()?;
             source.child_info.destination = Some(SingletonDestination::Melt);
@@ -85,6 +91,40 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "chip-0035")]
+    #[test]
+    fn test_action_melt_datastore() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::mint_datastore(crate::DataStoreMetadata::default(), vec![]),
+                Action::melt_singleton(Id::New(0), 1),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert!(outputs.datastores.is_empty());
+        assert_eq!(outputs.xch[0].amount, 1);
+
+        Ok(())
+    }
+
     #[rstest]
     #[case::normal(None)]
     #[case::revocable(Some(Bytes32::default()))]
@@ -0,0 +1,134 @@
+use crate::{
+    Asset, DataStoreMetadata, DataStoreSpend, DelegatedPuzzle, Deltas, DriverError, Id,
+    SpendAction, SpendContext, SpendKind, Spends,
+};
+
+#[derive(Debug, Clone)]
+pub struct MintDataStoreAction {
+    pub metadata: DataStoreMetadata,
+    pub delegated_puzzles: Vec<DelegatedPuzzle>,
+}
+
+impl MintDataStoreAction {
+    pub fn new(metadata: DataStoreMetadata, delegated_puzzles: Vec<DelegatedPuzzle>) -> Self {
+        Self {
+            metadata,
+            delegated_puzzles,
+        }
+    }
+}
+
+impl SpendAction for MintDataStoreAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, index: usize) {
+        deltas.update(Id::Xch).output += 1;
+        deltas.update(Id::New(index)).input += 1;
+        deltas.set_needed(Id::Xch);
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        index: usize,
+    ) -> Result<(), DriverError> {
+        let (source, launcher) = spends.xch.create_launcher(1)?;
+        let source = &mut spends.xch.items[source];
+
+        let (parent_conditions, eve_datastore) = launcher.mint_datastore(
+            ctx,
+            self.metadata.clone(),
+            source.asset.p2_puzzle_hash().into(),
+            self.delegated_puzzles.clone(),
+        )?;
+
+        match &mut source.kind {
+            SpendKind::Conditions(spend) => {
+                spend.add_conditions(parent_conditions);
+            }
+            SpendKind::Settlement(_) => {
+                return Err(DriverError::CannotEmitConditions);
+            }
+        }
+
+        spends
+            .datastores
+            .insert(Id::New(index), DataStoreSpend::new(eve_datastore, true));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chia_protocol::Bytes32;
+    use chia_sdk_test::Simulator;
+    use clvm_utils::TreeHash;
+    use indexmap::indexmap;
+    use rstest::rstest;
+
+    use crate::{Action, DataStore, Relation};
+
+    use super::*;
+
+    #[rstest]
+    fn test_action_mint_datastore(#[values(false, true)] delegated: bool) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let delegated_puzzles = if delegated {
+            vec![
+                DelegatedPuzzle::Admin(TreeHash::new([1; 32])),
+                DelegatedPuzzle::Writer(TreeHash::new([2; 32])),
+            ]
+        } else {
+            vec![]
+        };
+
+        let metadata = DataStoreMetadata {
+            root_hash: Bytes32::new([3; 32]),
+            label: Some("Label".to_string()),
+            ..Default::default()
+        };
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::mint_datastore(
+                metadata.clone(),
+                delegated_puzzles.clone(),
+            )],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        let coin_spends = ctx.take();
+        sim.spend_coins(coin_spends.clone(), &[alice.sk])?;
+
+        let datastore = outputs.datastores[&Id::New(0)].clone();
+        assert_eq!(datastore.info.metadata, metadata);
+        assert_eq!(datastore.info.owner_puzzle_hash, alice.puzzle_hash);
+        assert_eq!(datastore.info.delegated_puzzles, delegated_puzzles);
+        assert_ne!(sim.coin_state(datastore.coin.coin_id()), None);
+
+        // The eve spend must be parseable, so that wallets can sync the store.
+        let eve_spend = coin_spends
+            .iter()
+            .find(|cs| cs.coin.coin_id() == datastore.coin.parent_coin_info)
+            .expect("missing eve spend");
+        let parsed =
+            DataStore::<DataStoreMetadata>::from_spend(&mut ctx, eve_spend, &delegated_puzzles)?;
+        assert_eq!(parsed, Some(datastore));
+
+        Ok(())
+    }
+}
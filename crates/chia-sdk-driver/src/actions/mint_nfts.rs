@@ -0,0 +1,380 @@
+use chia_protocol::{Bytes32, Program};
+use chia_sdk_types::Conditions;
+
+use crate::{
+    Asset, COST_PER_BYTE, CREATE_COIN_COST, Deltas, DriverError, Id, IntermediateLauncher, NftMint,
+    SpendAction, SpendContext, SpendKind, Spends, spend_bundle_cost,
+};
+
+/// Mints many NFTs at once from XCH or a DID, using an [`IntermediateLauncher`] for each of them.
+///
+/// Unlike [`MintNftAction`](crate::MintNftAction), each NFT is sent directly to the p2 puzzle hash
+/// of its [`NftMint`], and is added to the outputs by its launcher id.
+#[derive(Debug, Clone)]
+pub struct MintNftsAction {
+    pub parent_id: Id,
+    pub mints: Vec<NftMint>,
+    /// The mint number of the first NFT. Along with the total, this makes each intermediate launcher unique.
+    pub mint_number: usize,
+    pub mint_total: usize,
+}
+
+impl MintNftsAction {
+    pub fn new(parent_id: Id, mints: Vec<NftMint>, mint_number: usize, mint_total: usize) -> Self {
+        Self {
+            parent_id,
+            mints,
+            mint_number,
+            mint_total,
+        }
+    }
+
+    /// Splits the mints into several actions, each of which can be minted in its own spend bundle
+    /// without exceeding the given cost.
+    ///
+    /// The cost of spending the parent coin isn't included, so some room should be left for it,
+    /// as well as for the fee and change.
+    pub fn split(self, ctx: &SpendContext, max_cost: u64) -> Result<Vec<Self>, DriverError> {
+        let costs = self.estimate_costs(ctx)?;
+
+        let mut actions = Vec::new();
+        let mut mints = Vec::new();
+        let mut mint_number = self.mint_number;
+        let mut cost = 0;
+
+        for (mint, mint_cost) in self.mints.into_iter().zip(costs) {
+            if !mints.is_empty() && cost + mint_cost > max_cost {
+                let batch = Self::new(self.parent_id, mints, mint_number, self.mint_total);
+                mint_number += batch.mints.len();
+                actions.push(batch);
+                mints = Vec::new();
+                cost = 0;
+            }
+
+            mints.push(mint);
+            cost += mint_cost;
+        }
+
+        if !mints.is_empty() {
+            actions.push(Self::new(
+                self.parent_id,
+                mints,
+                mint_number,
+                self.mint_total,
+            ));
+        }
+
+        Ok(actions)
+    }
+
+    /// Estimates the cost of each mint in a separate context, so that the caller's coin spends
+    /// aren't affected.
+    fn estimate_costs(&self, ctx: &SpendContext) -> Result<Vec<u64>, DriverError> {
+        let mut scratch = SpendContext::new();
+        let mut costs = Vec::with_capacity(self.mints.len());
+
+        for (index, mint) in self.mints.iter().enumerate() {
+            // The metadata is allocated in the caller's context, so it has to be copied over.
+            let metadata: Program = ctx.extract(mint.metadata.ptr())?;
+
+            let mint = NftMint {
+                metadata: scratch.alloc_hashed(&metadata)?,
+                ..mint.clone()
+            };
+
+            let (parent_conditions, _nft) = IntermediateLauncher::new(
+                Bytes32::default(),
+                self.mint_number + index,
+                self.mint_total,
+            )
+            .create(&mut scratch)?
+            .mint_nft(&mut scratch, &mint)?;
+
+            let mut cost = spend_bundle_cost(&scratch.take())?;
+
+            // The conditions are output by the parent spend, so they're not included above.
+            cost += scratch.serialize(&parent_conditions)?.len() as u64 * COST_PER_BYTE;
+            cost += parent_conditions
+                .iter()
+                .filter(|condition| condition.is_create_coin())
+                .count() as u64
+                * CREATE_COIN_COST;
+
+            costs.push(cost);
+        }
+
+        Ok(costs)
+    }
+}
+
+impl SpendAction for MintNftsAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(Id::Xch).output += self.mints.len() as u64;
+
+        if matches!(self.parent_id, Id::Xch) {
+            deltas.set_needed(Id::Xch);
+        } else {
+            let did = deltas.update(self.parent_id);
+            did.input += 1;
+            did.output += 1;
+        }
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let (source, parent_coin_id) = if matches!(self.parent_id, Id::Xch) {
+            let (source, _) = spends.xch.launcher_source()?;
+            (source, spends.xch.items[source].asset.coin_id())
+        } else {
+            let did = spends
+                .dids
+                .get_mut(&self.parent_id)
+                .ok_or(DriverError::InvalidAssetId)?;
+            let (source, _) = did.launcher_source()?;
+            (source, did.lineage[source].asset.coin_id())
+        };
+
+        let mut parent_conditions = Conditions::new();
+        let mut nfts = Vec::with_capacity(self.mints.len());
+
+        for (index, mint) in self.mints.iter().enumerate() {
+            let (conditions, nft) = IntermediateLauncher::new(
+                parent_coin_id,
+                self.mint_number + index,
+                self.mint_total,
+            )
+            .create(ctx)?
+            .mint_nft(ctx, mint)?;
+
+            parent_conditions = parent_conditions.extend(conditions);
+            nfts.push(nft);
+        }
+
+        let source_kind = if matches!(self.parent_id, Id::Xch) {
+            &mut spends.xch.items[source].kind
+        } else {
+            &mut spends
+                .dids
+                .get_mut(&self.parent_id)
+                .ok_or(DriverError::InvalidAssetId)?
+                .lineage[source]
+                .kind
+        };
+
+        match source_kind {
+            SpendKind::Conditions(spend) => {
+                spend.add_conditions(parent_conditions);
+            }
+            SpendKind::Settlement(_) => {
+                return Err(DriverError::CannotEmitConditions);
+            }
+        }
+
+        for nft in nfts {
+            spends
+                .outputs
+                .nfts
+                .insert(Id::Existing(nft.info.launcher_id), nft);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_puzzle_types::nft::NftMetadata;
+    use chia_sdk_test::Simulator;
+    use chia_sdk_types::conditions::TransferNft;
+    use indexmap::indexmap;
+
+    use crate::{Action, Relation, SingletonInfo};
+
+    use super::*;
+
+    fn nft_mints(
+        ctx: &mut SpendContext,
+        count: usize,
+        p2_puzzle_hash: Bytes32,
+        transfer_condition: Option<&TransferNft>,
+    ) -> Result<Vec<NftMint>> {
+        (0..count)
+            .map(|index| {
+                let metadata = ctx.alloc_hashed(&NftMetadata {
+                    edition_number: index as u64 + 1,
+                    edition_total: count as u64,
+                    ..Default::default()
+                })?;
+
+                Ok(NftMint::new(
+                    metadata,
+                    p2_puzzle_hash,
+                    300,
+                    transfer_condition.cloned(),
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_action_mint_nfts() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(10);
+        let bob = sim.bls(0);
+
+        let mints = nft_mints(&mut ctx, 3, bob.puzzle_hash, None)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::mint_nfts(Id::Xch, mints)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert_eq!(outputs.nfts.len(), 3);
+
+        for (id, nft) in &outputs.nfts {
+            assert_eq!(*id, Id::Existing(nft.info.launcher_id));
+            assert_eq!(nft.info.p2_puzzle_hash, bob.puzzle_hash);
+            assert_eq!(nft.info.royalty_basis_points, 300);
+            assert_ne!(sim.coin_state(nft.coin.coin_id()), None);
+        }
+
+        assert!(
+            outputs
+                .xch
+                .iter()
+                .any(|coin| coin.puzzle_hash == alice.puzzle_hash && coin.amount == 7)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_mint_nfts_from_did() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(10);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::create_empty_did()])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let did = outputs.dids[&Id::New(0)];
+        let id = Id::Existing(did.info.launcher_id);
+
+        let transfer_condition = TransferNft::new(
+            Some(did.info.launcher_id),
+            Vec::new(),
+            Some(did.info.inner_puzzle_hash().into()),
+        );
+        let mints = nft_mints(&mut ctx, 2, alice.puzzle_hash, Some(&transfer_condition))?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(did);
+
+        for &coin in &outputs.xch {
+            spends.add(coin);
+        }
+
+        let deltas = spends.apply(&mut ctx, &[Action::mint_nfts(id, mints)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert_ne!(sim.coin_state(outputs.dids[&id].coin.coin_id()), None);
+        assert_eq!(outputs.nfts.len(), 2);
+
+        for nft in outputs.nfts.values() {
+            assert_eq!(nft.info.current_owner, Some(did.info.launcher_id));
+            assert_eq!(nft.info.p2_puzzle_hash, alice.puzzle_hash);
+            assert_ne!(sim.coin_state(nft.coin.coin_id()), None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_mint_nfts_split() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(100);
+
+        let mints = nft_mints(&mut ctx, 10, alice.puzzle_hash, None)?;
+        let max_cost = 200_000_000;
+
+        let actions = MintNftsAction::new(Id::Xch, mints, 0, 10).split(&ctx, max_cost)?;
+        assert_eq!(actions.len(), 5);
+
+        let mut coin = alice.coin;
+        let mut minted = 0;
+
+        for action in actions {
+            assert_eq!(action.mint_number, minted);
+            assert_eq!(action.mint_total, 10);
+
+            let mut spends = Spends::new(alice.puzzle_hash);
+            spends.add(coin);
+
+            let deltas = spends.apply(&mut ctx, &[Action::MintNfts(action)])?;
+
+            let outputs = spends.finish_with_keys(
+                &mut ctx,
+                &deltas,
+                Relation::None,
+                &indexmap! { alice.puzzle_hash => alice.pk },
+            )?;
+
+            let coin_spends = ctx.take();
+            assert!(spend_bundle_cost(&coin_spends)? <= max_cost);
+            sim.spend_coins(coin_spends, slice::from_ref(&alice.sk))?;
+
+            minted += outputs.nfts.len();
+            coin = outputs
+                .xch
+                .iter()
+                .find(|coin| coin.puzzle_hash == alice.puzzle_hash)
+                .copied()
+                .expect("missing change coin");
+        }
+
+        assert_eq!(minted, 10);
+        assert_eq!(sim.unspent_coins(alice.puzzle_hash, true).len(), 11);
+
+        Ok(())
+    }
+}
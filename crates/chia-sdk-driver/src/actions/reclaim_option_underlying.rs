@@ -0,0 +1,175 @@
+use clvm_utils::ToTreeHash;
+
+use crate::{
+    Deltas, DriverError, OptionUnderlying, SpendAction, SpendContext, SpendableAsset, Spends,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReclaimOptionUnderlyingAction {
+    pub underlying: OptionUnderlying,
+    pub asset: SpendableAsset,
+}
+
+impl ReclaimOptionUnderlyingAction {
+    pub fn new(underlying: OptionUnderlying, asset: SpendableAsset) -> Self {
+        Self { underlying, asset }
+    }
+}
+
+impl SpendAction for ReclaimOptionUnderlyingAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(self.asset.id()).input += self.asset.coin().amount;
+    }
+
+    fn spend(
+        &self,
+        _ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let p2_puzzle_hash = self.asset.p2_puzzle_hash();
+
+        if p2_puzzle_hash != self.underlying.tree_hash().into() {
+            return Err(DriverError::WrongPuzzleHash);
+        }
+
+        // The creator's spend is wrapped when the transaction is finished, and asserts that the option has expired.
+        spends.add_ephemeral(self.asset);
+        spends
+            .option_underlyings
+            .insert(p2_puzzle_hash, self.underlying);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_protocol::Bytes32;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+    use rstest::rstest;
+
+    use crate::{Action, Id, OptionType, Relation};
+
+    use super::*;
+
+    #[rstest]
+    fn test_action_reclaim_option_underlying(
+        #[values(None, Some(Bytes32::default()))] hidden_puzzle_hash: Option<Bytes32>,
+        #[values(false, true)] expired: bool,
+    ) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(hidden_puzzle_hash, 1),
+                Action::mint_option(
+                    alice.puzzle_hash,
+                    10,
+                    Id::New(0),
+                    1,
+                    OptionType::Xch { amount: 1 },
+                    1,
+                ),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let underlying_cat = outputs.cats[&Id::New(0)][0];
+        let option = outputs.options[&Id::New(1)];
+        let id = Id::Existing(underlying_cat.info.asset_id);
+
+        let underlying = OptionUnderlying::new(
+            option.info.launcher_id,
+            alice.puzzle_hash,
+            10,
+            1,
+            OptionType::Xch { amount: 1 },
+        );
+
+        if expired {
+            sim.set_next_timestamp(10)?;
+        }
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::reclaim_option_underlying(
+                underlying,
+                SpendableAsset::Cat(underlying_cat),
+            )],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        let result = sim.spend_coins(ctx.take(), &[alice.sk]);
+
+        if !expired {
+            assert!(result.is_err());
+            return Ok(());
+        }
+
+        result?;
+
+        let cat = outputs.cats[&id][0];
+        assert_eq!(cat.info.p2_puzzle_hash, alice.puzzle_hash);
+        assert_eq!(cat.coin.amount, 1);
+        assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_reclaim_option_underlying_wrong_puzzle_hash() {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let underlying = OptionUnderlying::new(
+            Bytes32::default(),
+            alice.puzzle_hash,
+            10,
+            1,
+            OptionType::Xch { amount: 1 },
+        );
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        assert!(matches!(
+            spends.apply(
+                &mut ctx,
+                &[Action::reclaim_option_underlying(
+                    underlying,
+                    SpendableAsset::Xch(alice.coin),
+                )],
+            ),
+            Err(DriverError::WrongPuzzleHash)
+        ));
+    }
+}
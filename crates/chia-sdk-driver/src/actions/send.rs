@@ -95,6 +95,8 @@ impl SpendAction for SendAction {
 
 #[cfg(test)]
 mod tests {
+    use std::slice;
+
     use anyhow::Result;
     use chia_protocol::Coin;
     use chia_puzzle_types::standard::StandardArgs;
@@ -102,7 +104,9 @@ mod tests {
     use indexmap::indexmap;
     use rstest::rstest;
 
-    use crate::{Action, Cat, Relation};
+    use clvm_traits::ToClvm;
+
+    use crate::{Action, Cat, OptionContract, OptionType, Puzzle, Relation};
 
     use super::*;
 
@@ -371,4 +375,89 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_action_transfer_option() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+        let bob = sim.bls(0);
+        let bob_hint = ctx.hint(bob.puzzle_hash)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(None, 1),
+                Action::mint_option(
+                    alice.puzzle_hash,
+                    10,
+                    Id::New(0),
+                    1,
+                    OptionType::Xch { amount: 1 },
+                    1,
+                ),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let option = outputs.options[&Id::New(1)];
+        let launcher_id = option.info.launcher_id;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(option);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::transfer_option(
+                Id::Existing(launcher_id),
+                bob.puzzle_hash,
+                1,
+                bob_hint,
+            )],
+        )?;
+
+        spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let parent_spend = sim
+            .coin_spend(option.coin.coin_id())
+            .expect("missing option spend");
+        let parent_puzzle = parent_spend.puzzle_reveal.to_clvm(&mut ctx)?;
+        let parent_puzzle = Puzzle::parse(&ctx, parent_puzzle);
+        let parent_solution = parent_spend.solution.to_clvm(&mut ctx)?;
+
+        let child =
+            OptionContract::parse_child(&mut ctx, option.coin, parent_puzzle, parent_solution)?
+                .expect("missing option child");
+
+        assert_eq!(child.info.launcher_id, launcher_id);
+        assert_eq!(child.info.p2_puzzle_hash, bob.puzzle_hash);
+        assert_eq!(child.coin.amount, 1);
+        assert_eq!(
+            sim.coin_state(child.coin.coin_id())
+                .expect("missing coin")
+                .spent_height,
+            None
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,186 @@
+use chia_protocol::Bytes32;
+use clvm_traits::clvm_list;
+use clvm_utils::ToTreeHash;
+
+use crate::{ClawbackV2, Deltas, DriverError, Id, SendAction, SpendAction, SpendContext, Spends};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SendWithClawbackAction {
+    pub id: Id,
+    pub receiver_puzzle_hash: Bytes32,
+    pub seconds: u64,
+    pub amount: u64,
+}
+
+impl SendWithClawbackAction {
+    pub fn new(id: Id, receiver_puzzle_hash: Bytes32, seconds: u64, amount: u64) -> Self {
+        Self {
+            id,
+            receiver_puzzle_hash,
+            seconds,
+            amount,
+        }
+    }
+}
+
+impl SpendAction for SendWithClawbackAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(self.id).output += self.amount;
+        deltas.set_needed(self.id);
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        index: usize,
+    ) -> Result<(), DriverError> {
+        // The change puzzle hash is used as the sender, so that a claw back returns the funds to the wallet.
+        // Everything other than XCH is hinted, so that the receiver can find the coin if it's pushed through.
+        let clawback = ClawbackV2::new(
+            spends.change_puzzle_hash,
+            self.receiver_puzzle_hash,
+            self.seconds,
+            self.amount,
+            !matches!(self.id, Id::Xch),
+        );
+
+        let memos = ctx.memos(&clvm_list!(self.receiver_puzzle_hash, clawback.memo()))?;
+
+        SendAction::new(self.id, clawback.tree_hash().into(), self.amount, memos)
+            .spend(ctx, spends, index)?;
+
+        spends.outputs.clawbacks.push(clawback);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+    use rstest::rstest;
+
+    use crate::{Action, Relation};
+
+    use super::*;
+
+    #[test]
+    fn test_action_send_xch_with_clawback() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send_with_clawback(Id::Xch, bob.puzzle_hash, 100, 1)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let clawback = outputs.clawbacks[0];
+        assert_eq!(clawback.sender_puzzle_hash, alice.puzzle_hash);
+        assert_eq!(clawback.receiver_puzzle_hash, bob.puzzle_hash);
+        assert!(!clawback.hinted);
+
+        let coin = outputs.xch[0];
+        assert_eq!(coin.puzzle_hash, clawback.tree_hash().into());
+        assert_ne!(sim.coin_state(coin.coin_id()), None);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::normal(None)]
+    #[case::revocable(Some(Bytes32::default()))]
+    fn test_action_send_cat_with_clawback(
+        #[case] hidden_puzzle_hash: Option<Bytes32>,
+    ) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(hidden_puzzle_hash, 1),
+                Action::send_with_clawback(Id::New(0), bob.puzzle_hash, 100, 1),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let clawback = outputs.clawbacks[0];
+        assert!(clawback.hinted);
+
+        let cat = outputs.cats[&Id::New(0)][0];
+        assert_eq!(cat.info.p2_puzzle_hash, clawback.tree_hash().into());
+        assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+        assert_eq!(sim.hinted_coins(bob.puzzle_hash), vec![cat.coin.coin_id()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_send_nft_with_clawback() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::mint_empty_nft(),
+                Action::send_with_clawback(Id::New(0), bob.puzzle_hash, 100, 1),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let clawback = outputs.clawbacks[0];
+        let nft = outputs.nfts[&Id::New(0)];
+        assert_eq!(nft.info.p2_puzzle_hash, clawback.tree_hash().into());
+        assert_ne!(sim.coin_state(nft.coin.coin_id()), None);
+        assert_eq!(sim.hinted_coins(bob.puzzle_hash), vec![nft.coin.coin_id()]);
+
+        Ok(())
+    }
+}
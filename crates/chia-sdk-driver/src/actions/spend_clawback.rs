@@ -0,0 +1,354 @@
+use chia_sdk_types::conditions::AssertBeforeSecondsAbsolute;
+use clvm_utils::ToTreeHash;
+
+use crate::{
+    ClawbackInfo, ClawbackPath, ClawbackV2, Deltas, DriverError, SpendAction, SpendContext,
+    SpendableAsset, Spends,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpendClawbackAction {
+    pub clawback: ClawbackV2,
+    pub path: ClawbackPath,
+    pub asset: SpendableAsset,
+}
+
+impl SpendClawbackAction {
+    pub fn new(clawback: ClawbackV2, path: ClawbackPath, asset: SpendableAsset) -> Self {
+        Self {
+            clawback,
+            path,
+            asset,
+        }
+    }
+}
+
+impl SpendAction for SpendClawbackAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(self.asset.id()).input += self.asset.coin().amount;
+    }
+
+    fn spend(
+        &self,
+        _ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let p2_puzzle_hash = self.asset.p2_puzzle_hash();
+
+        if p2_puzzle_hash != self.clawback.tree_hash().into() {
+            return Err(DriverError::WrongPuzzleHash);
+        }
+
+        match self.path {
+            // The sender path expires, so the rest of the transaction must expire along with it.
+            ClawbackPath::Sender => {
+                spends
+                    .conditions
+                    .required
+                    .push(AssertBeforeSecondsAbsolute::new(self.clawback.seconds));
+            }
+            ClawbackPath::Receiver => {}
+            // The push through path has fixed output, so it can't be used as a source for other actions.
+            ClawbackPath::PushThrough => return Err(DriverError::CannotEmitConditions),
+        }
+
+        // The asset is accounted for as an input in the deltas, so it's ephemeral here.
+        spends.add_ephemeral(self.asset);
+
+        spends
+            .clawbacks
+            .insert(p2_puzzle_hash, ClawbackInfo::new(self.clawback, self.path));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_protocol::Bytes32;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+    use rstest::rstest;
+
+    use crate::{Action, Id, Relation};
+
+    use super::*;
+
+    #[rstest]
+    fn test_action_claw_back_xch(#[values(false, true)] claim: bool) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send_with_clawback(Id::Xch, bob.puzzle_hash, 100, 1)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let clawback = outputs.clawbacks[0];
+        let coin = outputs.xch[0];
+
+        let (owner, action) = if claim {
+            sim.set_next_timestamp(100)?;
+            (
+                &bob,
+                Action::claim_clawback(clawback, SpendableAsset::Xch(coin)),
+            )
+        } else {
+            (
+                &alice,
+                Action::claw_back(clawback, SpendableAsset::Xch(coin)),
+            )
+        };
+
+        let mut spends = Spends::new(owner.puzzle_hash);
+        let deltas = spends.apply(&mut ctx, &[action])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { owner.puzzle_hash => owner.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&owner.sk))?;
+
+        let coin = outputs.xch[0];
+        assert_eq!(coin.puzzle_hash, owner.puzzle_hash);
+        assert_eq!(coin.amount, 1);
+        assert_ne!(sim.coin_state(coin.coin_id()), None);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_action_claw_back_cat(
+        #[values(None, Some(Bytes32::default()))] hidden_puzzle_hash: Option<Bytes32>,
+        #[values(false, true)] claim: bool,
+    ) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(hidden_puzzle_hash, 2),
+                Action::send_with_clawback(Id::New(0), bob.puzzle_hash, 100, 2),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let clawback = outputs.clawbacks[0];
+        let cat = outputs.cats[&Id::New(0)][0];
+        let id = Id::Existing(cat.info.asset_id);
+
+        let (owner, action) = if claim {
+            sim.set_next_timestamp(100)?;
+            (
+                &bob,
+                Action::claim_clawback(clawback, SpendableAsset::Cat(cat)),
+            )
+        } else {
+            (
+                &alice,
+                Action::claw_back(clawback, SpendableAsset::Cat(cat)),
+            )
+        };
+
+        // Part of the clawed back amount is sent elsewhere, and the rest is change.
+        let mut spends = Spends::new(owner.puzzle_hash);
+        let hint = ctx.hint(owner.puzzle_hash)?;
+        let deltas = spends.apply(
+            &mut ctx,
+            &[action, Action::send(id, owner.puzzle_hash, 1, hint)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { owner.puzzle_hash => owner.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&owner.sk))?;
+
+        let cats = &outputs.cats[&id];
+        assert_eq!(cats.len(), 2);
+
+        for cat in cats {
+            assert_eq!(cat.info.p2_puzzle_hash, owner.puzzle_hash);
+            assert_eq!(cat.coin.amount, 1);
+            assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_action_claw_back_nft(#[values(false, true)] claim: bool) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::mint_empty_nft(),
+                Action::send_with_clawback(Id::New(0), bob.puzzle_hash, 100, 1),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let clawback = outputs.clawbacks[0];
+        let nft = outputs.nfts[&Id::New(0)];
+        let id = Id::Existing(nft.info.launcher_id);
+
+        let (owner, action) = if claim {
+            sim.set_next_timestamp(100)?;
+            (
+                &bob,
+                Action::claim_clawback(clawback, SpendableAsset::Nft(nft)),
+            )
+        } else {
+            (
+                &alice,
+                Action::claw_back(clawback, SpendableAsset::Nft(nft)),
+            )
+        };
+
+        let mut spends = Spends::new(owner.puzzle_hash);
+        let deltas = spends.apply(&mut ctx, &[action])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { owner.puzzle_hash => owner.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&owner.sk))?;
+
+        let nft = outputs.nfts[&id];
+        assert_eq!(nft.info.p2_puzzle_hash, owner.puzzle_hash);
+        assert_ne!(sim.coin_state(nft.coin.coin_id()), None);
+        assert!(
+            sim.hinted_coins(owner.puzzle_hash)
+                .contains(&nft.coin.coin_id())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_claw_back_expired() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::send_with_clawback(Id::Xch, bob.puzzle_hash, 100, 1)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let clawback = outputs.clawbacks[0];
+        let coin = outputs.xch[0];
+
+        sim.set_next_timestamp(101)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::claw_back(clawback, SpendableAsset::Xch(coin))],
+        )?;
+
+        spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        assert!(sim.spend_coins(ctx.take(), &[alice.sk]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_claw_back_wrong_puzzle_hash() {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+
+        let clawback = ClawbackV2::new(alice.puzzle_hash, alice.puzzle_hash, 100, 1, false);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+
+        assert!(matches!(
+            spends.apply(
+                &mut ctx,
+                &[Action::claw_back(clawback, SpendableAsset::Xch(alice.coin))],
+            ),
+            Err(DriverError::WrongPuzzleHash)
+        ));
+    }
+}
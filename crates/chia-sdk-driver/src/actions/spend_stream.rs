@@ -0,0 +1,454 @@
+use chia_consensus::make_aggsig_final_message::u64_to_bytes;
+use chia_protocol::{Bytes, Bytes32, Coin};
+use chia_puzzle_types::LineageProof;
+use chia_sdk_types::Conditions;
+
+use crate::{
+    Cat, CatInfo, Deltas, DriverError, Id, SpendAction, SpendContext, SpendKind, SpendableAsset,
+    Spends, StreamSpend, StreamedAsset,
+};
+
+#[derive(Debug, Clone)]
+pub struct SpendStreamAction {
+    pub stream: StreamedAsset,
+    pub payment_time: u64,
+    pub clawback: bool,
+}
+
+impl SpendStreamAction {
+    pub fn new(stream: StreamedAsset, payment_time: u64, clawback: bool) -> Self {
+        Self {
+            stream,
+            payment_time,
+            clawback,
+        }
+    }
+
+    fn id(&self) -> Id {
+        self.stream.asset_id.map_or(Id::Xch, Id::Existing)
+    }
+
+    /// The stream can't pay out more than it has, so the payment time is capped at the end time.
+    fn capped_payment_time(&self) -> u64 {
+        self.payment_time.clamp(
+            self.stream.info.last_payment_time,
+            self.stream.info.end_time,
+        )
+    }
+
+    /// The puzzle hash that must authorize the spend, and the one that receives the coin we can spend.
+    fn owner_puzzle_hash(&self) -> Result<Bytes32, DriverError> {
+        if self.clawback {
+            self.stream
+                .info
+                .clawback_ph
+                .ok_or(DriverError::MissingStreamClawback)
+        } else {
+            Ok(self.stream.info.recipient)
+        }
+    }
+
+    /// The amount that is received by the owner puzzle hash.
+    fn received_amount(&self) -> u64 {
+        let paid = self
+            .stream
+            .info
+            .amount_to_be_paid(self.stream.coin.amount, self.capped_payment_time());
+
+        if self.clawback {
+            self.stream.coin.amount - paid
+        } else {
+            paid
+        }
+    }
+}
+
+impl SpendAction for SpendStreamAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        let id = self.id();
+        let amount = self.received_amount();
+
+        deltas.update(id).input += amount;
+
+        // The message authorizing the spend is sent from an XCH coin owned by the recipient, which
+        // may need to be selected. The received coin can't send it, even if the stream is XCH.
+        deltas.set_needed(Id::Xch);
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let owner_puzzle_hash = self.owner_puzzle_hash()?;
+        let payment_time = self.capped_payment_time();
+        let amount = self.received_amount();
+
+        // The stream only releases funds if the owner sends it a message with the payment time.
+        // The coin it pays out can't send the message, since it's created by the stream spend, so it's
+        // only added afterward.
+        let index = if let Some(index) = spends.xch.items.iter().position(|item| {
+            !item.ephemeral
+                && item.asset.puzzle_hash == owner_puzzle_hash
+                && item.kind.is_conditions()
+        }) {
+            index
+        } else {
+            spends
+                .xch
+                .intermediate_conditions_source(ctx, owner_puzzle_hash)?
+                .ok_or(DriverError::NoSourceForOutput)?
+        };
+
+        let coin_id = ctx.alloc(&self.stream.coin.coin_id())?;
+
+        match &mut spends.xch.items[index].kind {
+            SpendKind::Conditions(spend) => {
+                spend.add_conditions(Conditions::new().send_message(
+                    23,
+                    Bytes::new(u64_to_bytes(payment_time)),
+                    vec![coin_id],
+                ));
+            }
+            SpendKind::Settlement(_) => return Err(DriverError::CannotEmitConditions),
+        }
+
+        // The received coin is created by the stream, so it's ephemeral and can fund other actions.
+        if amount > 0 {
+            if let Some(asset_id) = self.stream.asset_id {
+                let cat = Cat::new(
+                    Coin::new(
+                        self.stream.coin.coin_id(),
+                        CatInfo::new(asset_id, None, owner_puzzle_hash)
+                            .puzzle_hash()
+                            .into(),
+                        amount,
+                    ),
+                    Some(LineageProof {
+                        parent_parent_coin_info: self.stream.coin.parent_coin_info,
+                        parent_inner_puzzle_hash: self.stream.info.inner_puzzle_hash().into(),
+                        parent_amount: self.stream.coin.amount,
+                    }),
+                    CatInfo::new(asset_id, None, owner_puzzle_hash),
+                );
+
+                spends.add_ephemeral(SpendableAsset::Cat(cat));
+            } else {
+                spends.add_ephemeral(SpendableAsset::Xch(Coin::new(
+                    self.stream.coin.coin_id(),
+                    owner_puzzle_hash,
+                    amount,
+                )));
+            }
+        }
+
+        if !self.clawback
+            && let Some(child) = self.stream.child(payment_time)
+        {
+            spends.outputs.streams.push(child);
+        }
+
+        spends.streams.push(StreamSpend::new(
+            self.stream.clone(),
+            payment_time,
+            self.clawback,
+        ));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+    use rstest::rstest;
+
+    use crate::{Action, Relation};
+
+    use super::*;
+
+    fn create_stream(
+        sim: &mut Simulator,
+        ctx: &mut SpendContext,
+        cat: bool,
+        recipient: Bytes32,
+        clawback_puzzle_hash: Option<Bytes32>,
+    ) -> Result<StreamedAsset> {
+        let alice = sim.bls(1000);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let mut actions = Vec::new();
+
+        let id = if cat {
+            actions.push(Action::single_issue_cat(None, 1000));
+            Id::New(0)
+        } else {
+            Id::Xch
+        };
+
+        actions.push(Action::create_stream(
+            id,
+            recipient,
+            clawback_puzzle_hash,
+            0,
+            1000,
+            1000,
+        ));
+
+        let deltas = spends.apply(ctx, &actions)?;
+
+        let outputs = spends.finish_with_keys(
+            ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        Ok(outputs.streams[0].clone())
+    }
+
+    #[rstest]
+    fn test_action_claim_stream(#[values(false, true)] cat: bool) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let bob = sim.bls(1);
+        let stream = create_stream(
+            &mut sim,
+            &mut ctx,
+            cat,
+            bob.puzzle_hash,
+            Some(Bytes32::default()),
+        )?;
+        let id = stream.asset_id.map_or(Id::Xch, Id::Existing);
+
+        sim.set_next_timestamp(250)?;
+
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(bob.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::claim_stream(stream, 250), Action::fee(1)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&bob.sk))?;
+
+        let child = &outputs.streams[0];
+        assert_eq!(child.coin.amount, 750);
+        assert_eq!(child.info.last_payment_time, 250);
+        assert_ne!(sim.coin_state(child.coin.coin_id()), None);
+
+        if cat {
+            let cat = outputs.cats[&id][0];
+            assert_eq!(cat.coin.amount, 250);
+            assert_eq!(cat.info.p2_puzzle_hash, bob.puzzle_hash);
+            assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+        } else {
+            let coin = outputs.xch[0];
+            assert_eq!(coin.amount, 250);
+            assert_eq!(coin.puzzle_hash, bob.puzzle_hash);
+            assert_ne!(sim.coin_state(coin.coin_id()), None);
+        }
+
+        // The remaining stream can be claimed in full once it has ended.
+        sim.set_next_timestamp(2000)?;
+
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(sim.new_coin(bob.puzzle_hash, 0));
+
+        let deltas = spends.apply(&mut ctx, &[Action::claim_stream(child.clone(), 2000)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[bob.sk])?;
+
+        assert!(outputs.streams.is_empty());
+
+        let amount = if cat {
+            outputs.cats[&id][0].coin.amount
+        } else {
+            outputs.xch[0].amount
+        };
+        assert_eq!(amount, 750);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_action_claw_back_stream(#[values(false, true)] cat: bool) -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+        let stream = create_stream(
+            &mut sim,
+            &mut ctx,
+            cat,
+            bob.puzzle_hash,
+            Some(alice.puzzle_hash),
+        )?;
+        let id = stream.asset_id.map_or(Id::Xch, Id::Existing);
+
+        sim.set_next_timestamp(400)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::claw_back_stream(stream, 500)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        assert!(outputs.streams.is_empty());
+
+        if cat {
+            let cat = outputs.cats[&id][0];
+            assert_eq!(cat.coin.amount, 500);
+            assert_eq!(cat.info.p2_puzzle_hash, alice.puzzle_hash);
+            assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+        } else {
+            let coin = outputs.xch[0];
+            assert_eq!(coin.amount, 501);
+            assert_eq!(coin.puzzle_hash, alice.puzzle_hash);
+            assert_ne!(sim.coin_state(coin.coin_id()), None);
+        }
+
+        // The vested portion is still paid to the recipient.
+        assert!(
+            sim.unspent_coins(bob.puzzle_hash, true)
+                .iter()
+                .any(|coin| coin.amount == 500)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_claim_stream_without_coin() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let bob = sim.bls(0);
+        let stream = create_stream(
+            &mut sim,
+            &mut ctx,
+            false,
+            bob.puzzle_hash,
+            Some(Bytes32::default()),
+        )?;
+
+        sim.set_next_timestamp(250)?;
+
+        // The coin paid out by the stream can't authorize the stream spend that creates it
+        let mut spends = Spends::new(bob.puzzle_hash);
+
+        let result = spends.apply(&mut ctx, &[Action::claim_stream(stream, 250)]);
+
+        assert!(matches!(result, Err(DriverError::NoSourceForOutput)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_action_claim_stream_with_source() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let bob = sim.bls(1);
+        let stream = create_stream(
+            &mut sim,
+            &mut ctx,
+            false,
+            bob.puzzle_hash,
+            Some(Bytes32::default()),
+        )?;
+
+        sim.set_next_timestamp(250)?;
+
+        // The coin that authorizes the stream spend is selected, even though the stream pays out XCH
+        let mut spends = Spends::new(bob.puzzle_hash);
+
+        let deltas = spends
+            .apply_with_source(
+                &mut ctx,
+                &[Action::claim_stream(stream, 250)],
+                &sim,
+                bob.puzzle_hash,
+            )
+            .await?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[bob.sk])?;
+
+        assert!(
+            sim.coin_state(bob.coin.coin_id())
+                .and_then(|state| state.spent_height)
+                .is_some()
+        );
+        assert_eq!(
+            outputs
+                .xch
+                .iter()
+                .filter(|coin| coin.puzzle_hash == bob.puzzle_hash)
+                .map(|coin| coin.amount)
+                .sum::<u64>(),
+            251
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_claw_back_stream_without_clawback() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+        let stream = create_stream(&mut sim, &mut ctx, false, bob.puzzle_hash, None)?;
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let result = spends.apply(&mut ctx, &[Action::claw_back_stream(stream, 500)]);
+
+        assert!(matches!(result, Err(DriverError::MissingStreamClawback)));
+
+        Ok(())
+    }
+}
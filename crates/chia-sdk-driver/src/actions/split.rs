@@ -0,0 +1,285 @@
+use chia_protocol::Bytes32;
+use chia_sdk_types::conditions::CreateCoin;
+
+use crate::{
+    Batch, Deltas, DriverError, FungibleAsset, FungibleSpends, Id, Output, SpendAction,
+    SpendContext, Spends, build_batches,
+};
+
+/// Creates a number of XCH or CAT coins of the same amount, so that they can be spent in parallel
+/// without conflicting with each other.
+///
+/// Since a coin can't create more than one child with the same puzzle hash and amount, intermediate
+/// coins are created as needed to be the parents of the outputs.
+#[derive(Debug, Clone, Copy)]
+pub struct SplitAction {
+    pub id: Id,
+    pub puzzle_hash: Bytes32,
+    pub amount: u64,
+    pub count: usize,
+}
+
+impl SplitAction {
+    pub fn new(id: Id, puzzle_hash: Bytes32, amount: u64, count: usize) -> Self {
+        Self {
+            id,
+            puzzle_hash,
+            amount,
+            count,
+        }
+    }
+
+    /// Splits the outputs into batches of up to `max_outputs`, each of which is built into its own
+    /// spend bundle with the `build` function. See [`build_batches`] for how the cost is limited.
+    ///
+    /// Each spend bundle must spend different coins, so that they can be submitted at the same time.
+    pub fn build_batches<T, F>(
+        self,
+        ctx: &mut SpendContext,
+        max_outputs: usize,
+        max_cost: u64,
+        mut build: F,
+    ) -> Result<Vec<Batch<T>>, DriverError>
+    where
+        F: FnMut(&mut SpendContext, Self) -> Result<T, DriverError>,
+    {
+        build_batches(
+            ctx,
+            vec![self.amount; self.count],
+            max_outputs,
+            max_cost,
+            |ctx, outputs| {
+                build(
+                    ctx,
+                    Self::new(self.id, self.puzzle_hash, self.amount, outputs.len()),
+                )
+            },
+        )
+    }
+
+    fn total(&self) -> u64 {
+        self.amount * self.count as u64
+    }
+}
+
+impl SpendAction for SplitAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(self.id).output += self.total();
+        deltas.set_needed(self.id);
+    }
+
+    fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        if matches!(self.id, Id::Xch) {
+            let coins = split(
+                ctx,
+                &mut spends.xch,
+                self.puzzle_hash,
+                self.amount,
+                self.count,
+            )?;
+            spends.outputs.xch.extend(coins);
+        } else if let Some(cat) = spends.cats.get_mut(&self.id) {
+            let cats = split(ctx, cat, self.puzzle_hash, self.amount, self.count)?;
+            spends.outputs.cats.entry(self.id).or_default().extend(cats);
+        } else {
+            return Err(DriverError::InvalidAssetId);
+        }
+
+        Ok(())
+    }
+}
+
+fn split<A>(
+    ctx: &mut SpendContext,
+    spends: &mut FungibleSpends<A>,
+    puzzle_hash: Bytes32,
+    amount: u64,
+    count: usize,
+) -> Result<Vec<A>, DriverError>
+where
+    A: FungibleAsset,
+{
+    let output = Output::new(puzzle_hash, amount);
+    let mut children = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        let source = spends.output_source(ctx, &output)?;
+        let parent = &mut spends.items[source];
+        let parent_puzzle_hash = parent.asset.full_puzzle_hash();
+
+        let create_coin = CreateCoin::new(
+            puzzle_hash,
+            amount,
+            parent.asset.child_memos(ctx, puzzle_hash)?,
+        );
+
+        parent.kind.create_coin_with_assertion(
+            ctx,
+            parent_puzzle_hash,
+            &mut spends.payment_assertions,
+            create_coin,
+        );
+
+        children.push(parent.asset.make_child(puzzle_hash, amount));
+    }
+
+    Ok(children)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, slice};
+
+    use anyhow::Result;
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+
+    use crate::{Action, Relation, spend_bundle_cost};
+
+    use super::*;
+
+    #[test]
+    fn test_action_split_xch() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1000);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::split(Id::Xch, alice.puzzle_hash, 100, 5),
+                Action::fee(50),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let coins = sim.unspent_coins(alice.puzzle_hash, false);
+        assert_eq!(coins.len(), 6);
+        assert_eq!(coins.iter().filter(|coin| coin.amount == 100).count(), 5);
+        assert_eq!(coins.iter().map(|coin| coin.amount).sum::<u64>(), 950);
+
+        for coin in outputs.xch {
+            assert!(coins.contains(&coin));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_split_cat() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(10);
+        let bob = sim.bls(0);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::single_issue_cat(None, 10),
+                Action::split(Id::New(0), bob.puzzle_hash, 3, 3),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let cats = &outputs.cats[&Id::New(0)];
+        let bob_cats: Vec<_> = cats
+            .iter()
+            .filter(|cat| cat.info.p2_puzzle_hash == bob.puzzle_hash)
+            .collect();
+
+        assert_eq!(bob_cats.len(), 3);
+
+        for cat in bob_cats {
+            assert_eq!(cat.coin.amount, 3);
+            assert_ne!(sim.coin_state(cat.coin.coin_id()), None);
+        }
+
+        assert!(
+            cats.iter()
+                .any(|cat| cat.info.p2_puzzle_hash == alice.puzzle_hash && cat.coin.amount == 1)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batches() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(0);
+
+        let mut coins: Vec<_> = (0..10)
+            .map(|_| sim.new_coin(alice.puzzle_hash, 100))
+            .collect();
+
+        let max_cost = 60_000_000;
+
+        let batches = SplitAction::new(Id::Xch, alice.puzzle_hash, 1, 30).build_batches(
+            &mut ctx,
+            10,
+            max_cost,
+            |ctx, action| {
+                let mut spends = Spends::new(alice.puzzle_hash);
+                spends.add(coins.pop().expect("no more coins"));
+
+                let deltas = spends.apply(ctx, &[Action::Split(action)])?;
+
+                spends.finish_with_keys(
+                    ctx,
+                    &deltas,
+                    Relation::None,
+                    &indexmap! { alice.puzzle_hash => alice.pk },
+                )
+            },
+        )?;
+
+        assert!(batches.len() > 3);
+
+        let mut spent = HashSet::new();
+
+        for batch in batches {
+            assert!(spend_bundle_cost(&batch.coin_spends)? <= max_cost);
+
+            for coin_spend in &batch.coin_spends {
+                assert!(spent.insert(coin_spend.coin.coin_id()));
+            }
+
+            sim.spend_coins(batch.coin_spends, slice::from_ref(&alice.sk))?;
+        }
+
+        let coins = sim.unspent_coins(alice.puzzle_hash, false);
+        assert_eq!(coins.iter().filter(|coin| coin.amount == 1).count(), 30);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use chia_protocol::Bytes32;
+
+use crate::{
+    DataStoreMetadata, DelegatedPuzzle, Deltas, DriverError, Id, SpendAction, SpendContext, Spends,
+};
+
+#[derive(Debug, Clone)]
+pub struct UpdateDataStoreAction {
+    pub id: Id,
+    pub new_metadata: Option<DataStoreMetadata>,
+    pub new_owner_puzzle_hash: Option<Bytes32>,
+    pub new_delegated_puzzles: Option<Vec<DelegatedPuzzle>>,
+}
+
+impl UpdateDataStoreAction {
+    pub fn new(
+        id: Id,
+        new_metadata: Option<DataStoreMetadata>,
+        new_owner_puzzle_hash: Option<Bytes32>,
+        new_delegated_puzzles: Option<Vec<DelegatedPuzzle>>,
+    ) -> Self {
+        Self {
+            id,
+            new_metadata,
+            new_owner_puzzle_hash,
+            new_delegated_puzzles,
+        }
+    }
+}
+
+impl SpendAction for UpdateDataStoreAction {
+    fn calculate_delta(&self, deltas: &mut Deltas, _index: usize) {
+        deltas.update(self.id).input += 1;
+        deltas.update(self.id).output += 1;
+        deltas.set_needed(self.id);
+    }
+
+    fn spend(
+        &self,
+        _ctx: &mut SpendContext,
+        spends: &mut Spends,
+        _index: usize,
+    ) -> Result<(), DriverError> {
+        let datastore = spends
+            .datastores
+            .get_mut(&self.id)
+            .ok_or(DriverError::InvalidAssetId)?;
+
+        if let Some(new_metadata) = &self.new_metadata {
+            datastore.child_info.metadata.clone_from(new_metadata);
+        }
+
+        if let Some(new_owner_puzzle_hash) = self.new_owner_puzzle_hash {
+            datastore.child_info.owner_puzzle_hash = Some(new_owner_puzzle_hash);
+        }
+
+        if let Some(new_delegated_puzzles) = &self.new_delegated_puzzles {
+            datastore
+                .child_info
+                .delegated_puzzles
+                .clone_from(new_delegated_puzzles);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_sdk_test::{BlsPairWithCoin, Simulator};
+    use clvm_utils::TreeHash;
+    use indexmap::indexmap;
+
+    use crate::{Action, DataStore, Relation};
+
+    use super::*;
+
+    fn mint_datastore(
+        sim: &mut Simulator,
+        ctx: &mut SpendContext,
+        alice: &BlsPairWithCoin,
+    ) -> Result<DataStore> {
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            ctx,
+            &[Action::mint_datastore(DataStoreMetadata::default(), vec![])],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        Ok(outputs.datastores[&Id::New(0)].clone())
+    }
+
+    #[test]
+    fn test_action_update_datastore() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(2);
+        let datastore = mint_datastore(&mut sim, &mut ctx, &alice)?;
+        let id = Id::Existing(datastore.info.launcher_id);
+
+        let metadata = DataStoreMetadata {
+            root_hash: Bytes32::new([1; 32]),
+            bytes: Some(1024),
+            ..Default::default()
+        };
+
+        let delegated_puzzles = vec![
+            DelegatedPuzzle::Admin(TreeHash::new([2; 32])),
+            DelegatedPuzzle::Writer(TreeHash::new([3; 32])),
+        ];
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(sim.unspent_coins(alice.puzzle_hash, false)[0]);
+        spends.add(datastore);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::update_datastore(
+                    id,
+                    Some(metadata.clone()),
+                    Some(delegated_puzzles.clone()),
+                ),
+                Action::fee(1),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::AssertConcurrent,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        let coin_spends = ctx.take();
+        sim.spend_coins(coin_spends.clone(), &[alice.sk])?;
+
+        let datastore = outputs.datastores[&id].clone();
+        assert_eq!(datastore.info.metadata, metadata);
+        assert_eq!(datastore.info.owner_puzzle_hash, alice.puzzle_hash);
+        assert_eq!(datastore.info.delegated_puzzles, delegated_puzzles);
+        assert_ne!(sim.coin_state(datastore.coin.coin_id()), None);
+
+        let parent_spend = coin_spends
+            .iter()
+            .find(|cs| cs.coin.coin_id() == datastore.coin.parent_coin_info)
+            .expect("missing parent spend");
+        let parsed = DataStore::<DataStoreMetadata>::from_spend(&mut ctx, parent_spend, &[])?;
+        assert_eq!(parsed, Some(datastore));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_action_transfer_datastore() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(0);
+        let datastore = mint_datastore(&mut sim, &mut ctx, &alice)?;
+        let id = Id::Existing(datastore.info.launcher_id);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(datastore);
+
+        let deltas = spends.apply(&mut ctx, &[Action::transfer_datastore(id, bob.puzzle_hash)])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        let datastore = outputs.datastores[&id].clone();
+        assert_eq!(datastore.info.owner_puzzle_hash, bob.puzzle_hash);
+        assert_ne!(sim.coin_state(datastore.coin.coin_id()), None);
+
+        // The new owner can spend the store.
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(datastore);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::update_datastore(
+                id,
+                Some(DataStoreMetadata {
+                    root_hash: Bytes32::new([1; 32]),
+                    ..Default::default()
+                }),
+                None,
+            )],
+        )?;
+
+        spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), &[bob.sk])?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::{array::TryFromSliceError, num::TryFromIntError};
 
+use chia_protocol::Bytes32;
 use chia_sdk_signer::SignerError;
 use clvm_traits::{FromClvmError, ToClvmError};
 use clvmr::error::EvalErr;
@@ -131,6 +132,12 @@ pub enum DriverError {
     #[error("conflicting inputs in offers")]
     ConflictingOfferInputs,
 
+    #[error("royalty exceeds the trade price")]
+    InvalidRoyalty,
+
+    #[error("offer has expired")]
+    OfferExpired,
+
     #[error("signer error: {0}")]
     Signer(#[from] SignerError),
 
@@ -152,9 +159,15 @@ pub enum DriverError {
     #[error("the transaction is not guaranteed to expire when its clawed back spends expire")]
     UnguaranteedClawBack,
 
+    #[error("stream has no clawback puzzle hash")]
+    MissingStreamClawback,
+
     #[error("the revocation layer of the child does not match the parent")]
     RevocableChild,
 
+    #[error("unsupported custody for puzzle hash {0}")]
+    UnsupportedCustody(Bytes32),
+
     #[error("conflicting vault launcher ids")]
     ConflictingVaultLauncherIds,
 
@@ -180,4 +193,19 @@ pub enum DriverError {
         "p2 conditions or singleton spend is missing AssertMyCoinId condition to prevent swapping the spend path"
     )]
     MissingP2ConditionsOrSingletonAssertion,
+
+    #[error("recovery list does not match the DID")]
+    InvalidRecoveryList,
+
+    #[error("an attestation is required from each DID in the recovery list")]
+    MissingAttestations,
+
+    #[error("coin selection error: {0}")]
+    CoinSelection(#[from] chia_sdk_utils::CoinSelectionError),
+
+    #[error("coin source error: {0}")]
+    CoinSource(String),
+
+    #[error("a single item exceeds the maximum cost of a spend bundle")]
+    ExceededMaxCost,
 }
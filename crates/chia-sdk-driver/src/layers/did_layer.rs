@@ -1,9 +1,7 @@
 use chia_protocol::Bytes32;
-use chia_puzzle_types::{
-    did::{DidArgs, DidSolution},
-    singleton::SingletonStruct,
-};
+use chia_puzzle_types::{did::DidArgs, singleton::SingletonStruct};
 use chia_puzzles::{DID_INNERPUZ_HASH, SINGLETON_LAUNCHER_HASH, SINGLETON_TOP_LAYER_V1_1_HASH};
+use chia_sdk_types::puzzles::DidSolution;
 use clvm_traits::{FromClvm, ToClvm};
 use clvm_utils::{ToTreeHash, TreeHash};
 use clvmr::{Allocator, NodePtr};
@@ -1,16 +1,22 @@
 mod asset_info;
+mod expiration;
 mod offer;
 mod offer_amounts;
 mod offer_coins;
 mod requested_payments;
 mod royalty;
+mod trade;
+mod validation;
 
 pub use asset_info::*;
+pub use expiration::*;
 pub use offer::*;
 pub use offer_amounts::*;
 pub use offer_coins::*;
 pub use requested_payments::*;
 pub use royalty::*;
+pub use trade::*;
+pub use validation::*;
 
 #[cfg(feature = "offer-compression")]
 mod compress;
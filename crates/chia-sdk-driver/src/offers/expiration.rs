@@ -0,0 +1,60 @@
+use chia_sdk_types::{Condition, Conditions};
+
+/// The timestamp and block height at which an offer can no longer be taken.
+///
+/// These are set with the `ASSERT_BEFORE_SECONDS_ABSOLUTE` and `ASSERT_BEFORE_HEIGHT_ABSOLUTE`
+/// conditions, which must be satisfied by the block that the offer is included in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OfferExpiration {
+    pub seconds: Option<u64>,
+    pub height: Option<u32>,
+}
+
+impl OfferExpiration {
+    pub fn new(seconds: Option<u64>, height: Option<u32>) -> Self {
+        Self { seconds, height }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.seconds.is_none() && self.height.is_none()
+    }
+
+    /// Returns whether the offer can't be included in a block with the given timestamp and height.
+    pub fn is_expired(&self, timestamp: u64, height: u32) -> bool {
+        self.seconds.is_some_and(|seconds| timestamp >= seconds)
+            || self.height.is_some_and(|expiration| height >= expiration)
+    }
+
+    /// Updates the expiration with a condition, keeping the earliest timestamp and height.
+    pub fn update<T>(&mut self, condition: &Condition<T>) {
+        match condition {
+            Condition::AssertBeforeSecondsAbsolute(condition) => {
+                self.seconds = Some(
+                    self.seconds
+                        .map_or(condition.seconds, |seconds| seconds.min(condition.seconds)),
+                );
+            }
+            Condition::AssertBeforeHeightAbsolute(condition) => {
+                self.height = Some(
+                    self.height
+                        .map_or(condition.height, |height| height.min(condition.height)),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    pub fn conditions(&self) -> Conditions {
+        let mut conditions = Conditions::new();
+
+        if let Some(seconds) = self.seconds {
+            conditions = conditions.assert_before_seconds_absolute(seconds);
+        }
+
+        if let Some(height) = self.height {
+            conditions = conditions.assert_before_height_absolute(height);
+        }
+
+        conditions
+    }
+}
@@ -11,8 +11,8 @@ use indexmap::IndexSet;
 
 use crate::{
     Arbitrage, AssetInfo, CatInfo, DriverError, Layer, NftInfo, OfferAmounts, OfferCoins,
-    OptionInfo, Puzzle, RequestedPayments, RoyaltyInfo, SingletonInfo, SpendContext,
-    calculate_royalty_amounts, calculate_trade_price_amounts,
+    OfferExpiration, OptionInfo, Puzzle, RequestedPayments, RoyaltyInfo, SingletonInfo,
+    SpendContext, calculate_royalty_amounts, calculate_trade_price_amounts,
 };
 
 #[derive(Debug, Clone)]
@@ -43,13 +43,7 @@ impl Offer {
         let mut created_coin_ids = HashSet::new();
 
         for coin_spend in &self.spend_bundle.coin_spends {
-            let puzzle = coin_spend.puzzle_reveal.to_clvm(&mut allocator)?;
-            let solution = coin_spend.solution.to_clvm(&mut allocator)?;
-
-            let output = run_puzzle(&mut allocator, puzzle, solution)?;
-            let conditions = Vec::<Condition>::from_clvm(&allocator, output)?;
-
-            for condition in conditions {
+            for condition in coin_spend_conditions(&mut allocator, coin_spend)? {
                 if let Some(create_coin) = condition.into_create_coin() {
                     created_coin_ids.insert(
                         Coin::new(
@@ -77,6 +71,29 @@ impl Offer {
             .collect())
     }
 
+    /// Returns the earliest expiration that is asserted by the offered coin spends.
+    pub fn expiration(&self) -> Result<OfferExpiration, DriverError> {
+        let mut allocator = Allocator::new();
+        let mut expiration = OfferExpiration::default();
+
+        for coin_spend in &self.spend_bundle.coin_spends {
+            for condition in coin_spend_conditions(&mut allocator, coin_spend)? {
+                expiration.update(&condition);
+            }
+        }
+
+        Ok(expiration)
+    }
+
+    /// Checks that the offer can still be included in a block with the given timestamp and height.
+    pub fn validate_expiration(&self, timestamp: u64, height: u32) -> Result<(), DriverError> {
+        if self.expiration()?.is_expired(timestamp, height) {
+            return Err(DriverError::OfferExpired);
+        }
+
+        Ok(())
+    }
+
     pub fn spend_bundle(&self) -> &SpendBundle {
         &self.spend_bundle
     }
@@ -408,6 +425,16 @@ impl Offer {
     }
 }
 
+pub(crate) fn coin_spend_conditions(
+    allocator: &mut Allocator,
+    coin_spend: &CoinSpend,
+) -> Result<Vec<Condition>, DriverError> {
+    let puzzle = coin_spend.puzzle_reveal.to_clvm(allocator)?;
+    let solution = coin_spend.solution.to_clvm(allocator)?;
+    let output = run_puzzle(allocator, puzzle, solution)?;
+    Ok(Vec::<Condition>::from_clvm(allocator, output)?)
+}
+
 #[cfg(test)]
 mod tests {
     use std::slice;
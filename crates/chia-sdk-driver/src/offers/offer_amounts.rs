@@ -36,7 +36,7 @@ impl ArbitrageSide {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct OfferAmounts {
     pub xch: u64,
     pub cats: IndexMap<Bytes32, u64>,
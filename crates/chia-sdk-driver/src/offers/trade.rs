@@ -0,0 +1,684 @@
+use chia_protocol::{Bytes32, SpendBundle};
+use chia_puzzle_types::{
+    Memos,
+    offer::{NotarizedPayment, Payment},
+};
+use chia_puzzles::SETTLEMENT_PAYMENT_HASH;
+use indexmap::IndexMap;
+
+use crate::{
+    Action, AssetInfo, CatAssetInfo, Deltas, DriverError, Id, NftAssetInfo, Offer, OfferAmounts,
+    OfferExpiration, OptionAssetInfo, RequestedPayments, RoyaltyInfo, SpendContext, Spends,
+    TransferNftById, calculate_royalty_amounts, calculate_royalty_payments,
+    calculate_trade_price_amounts, calculate_trade_prices,
+};
+
+/// An asset that is requested in exchange for the offered assets when making an offer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestedAsset {
+    Xch(u64),
+    Cat {
+        asset_id: Bytes32,
+        hidden_puzzle_hash: Option<Bytes32>,
+        amount: u64,
+    },
+    Nft {
+        launcher_id: Bytes32,
+        info: NftAssetInfo,
+    },
+    Option {
+        launcher_id: Bytes32,
+        info: OptionAssetInfo,
+    },
+}
+
+impl RequestedAsset {
+    pub fn xch(amount: u64) -> Self {
+        Self::Xch(amount)
+    }
+
+    pub fn cat(asset_id: Bytes32, hidden_puzzle_hash: Option<Bytes32>, amount: u64) -> Self {
+        Self::Cat {
+            asset_id,
+            hidden_puzzle_hash,
+            amount,
+        }
+    }
+
+    pub fn nft(launcher_id: Bytes32, info: NftAssetInfo) -> Self {
+        Self::Nft { launcher_id, info }
+    }
+
+    pub fn option(launcher_id: Bytes32, info: OptionAssetInfo) -> Self {
+        Self::Option { launcher_id, info }
+    }
+}
+
+/// Makes an offer for the requested assets, in exchange for the assets that the `offered` actions
+/// send to the settlement payments puzzle. The requested assets are paid to the change puzzle hash.
+///
+/// Royalties are handled on both sides of the trade:
+/// * Offered NFTs are transferred with the requested amounts as the trade price, so that the taker
+///   pays royalties on top of the requested payments.
+/// * Royalties for requested NFTs are sent to the settlement payments puzzle along with the offered
+///   amounts, so that the taker can pay them out when transferring the NFTs.
+///
+/// If an expiration is set, the offer can't be taken once it has been reached.
+///
+/// The `finish` function should finish the spends with [`Relation::AssertConcurrent`](crate::Relation)
+/// and sign the coin spends. A fee can be included in the offered actions.
+pub fn make_offer<F, E>(
+    ctx: &mut SpendContext,
+    mut spends: Spends,
+    offered: Vec<Action>,
+    requested: &[RequestedAsset],
+    expiration: OfferExpiration,
+    finish: F,
+) -> Result<Offer, E>
+where
+    F: FnOnce(&mut SpendContext, Spends, &Deltas) -> Result<SpendBundle, E>,
+    E: From<DriverError>,
+{
+    let nonce = Offer::nonce(spends.non_settlement_coin_ids());
+
+    let (requested_payments, asset_info) =
+        requested_payments(ctx, nonce, spends.change_puzzle_hash, requested)?;
+
+    let mut actions = Vec::new();
+
+    let offered_nfts = offered_nft_royalties(&spends, &offered)?;
+    let trade_prices = calculate_trade_prices(
+        &calculate_trade_price_amounts(&requested_payments.amounts(), offered_nfts.len()),
+        &asset_info,
+    );
+
+    if !trade_prices.is_empty() {
+        for &id in offered_nfts.keys() {
+            actions.push(Action::update_nft(
+                id,
+                vec![],
+                Some(TransferNftById::new(None, trade_prices.clone())),
+            ));
+        }
+    }
+
+    let requested_royalties: Vec<RoyaltyInfo> = requested
+        .iter()
+        .filter_map(|asset| match *asset {
+            RequestedAsset::Nft { launcher_id, info } => Some(RoyaltyInfo::new(
+                launcher_id,
+                info.royalty_puzzle_hash,
+                info.royalty_basis_points,
+            )),
+            _ => None,
+        })
+        .filter(|royalty| royalty.basis_points > 0)
+        .collect();
+
+    let royalty_amounts =
+        settled_royalty_amounts(&offered_amounts(&spends, &offered), &requested_royalties)?;
+
+    actions.extend(offered);
+
+    if royalty_amounts.xch > 0 {
+        actions.push(settlement_send(Id::Xch, royalty_amounts.xch));
+    }
+
+    for (asset_id, amount) in royalty_amounts.cats {
+        if amount > 0 {
+            actions.push(settlement_send(Id::Existing(asset_id), amount));
+        }
+    }
+
+    let deltas = spends.apply(ctx, &actions)?;
+
+    spends.conditions.required = spends
+        .conditions
+        .required
+        .extend(requested_payments.assertions(ctx, &asset_info)?)
+        .extend(expiration.conditions());
+
+    let spend_bundle = finish(ctx, spends, &deltas)?;
+
+    Ok(Offer::from_input_spend_bundle(
+        ctx,
+        spend_bundle,
+        requested_payments,
+        asset_info,
+    )?)
+}
+
+/// Takes an offer by paying the requested assets and royalties from the spends, and returns the
+/// completed spend bundle. The offered assets are sent to the change puzzle hash.
+///
+/// The requested NFTs are transferred with the offered amounts as the trade price, and their royalties
+/// are paid out of the offered coins. The royalties for the offered NFTs are paid in addition to the
+/// requested payments.
+///
+/// The `finish` function should finish the spends with [`Relation::AssertConcurrent`](crate::Relation)
+/// and sign the coin spends.
+pub fn take_offer<F, E>(
+    ctx: &mut SpendContext,
+    offer: Offer,
+    mut spends: Spends,
+    fee: u64,
+    finish: F,
+) -> Result<SpendBundle, E>
+where
+    F: FnOnce(&mut SpendContext, Spends, &Deltas) -> Result<SpendBundle, E>,
+    E: From<DriverError>,
+{
+    spends.add(offer.offered_coins().clone());
+
+    let mut actions = Vec::new();
+
+    let offered_royalties = offer.offered_royalties();
+    let trade_price_amounts =
+        calculate_trade_price_amounts(&offer.offered_coins().amounts(), offered_royalties.len());
+    let trade_prices = calculate_trade_prices(&trade_price_amounts, offer.asset_info());
+
+    if !trade_prices.is_empty() {
+        for royalty in &offered_royalties {
+            actions.push(Action::update_nft(
+                Id::Existing(royalty.launcher_id),
+                vec![],
+                Some(TransferNftById::new(None, trade_prices.clone())),
+            ));
+        }
+    }
+
+    actions.extend(
+        calculate_royalty_payments(ctx, &trade_price_amounts, &offered_royalties)?.actions(),
+    );
+
+    let requested_royalties = offer.requested_royalties();
+    let trade_price_amounts = calculate_trade_price_amounts(
+        &offer.requested_payments().amounts(),
+        requested_royalties.len(),
+    );
+
+    actions.extend(
+        calculate_royalty_payments(ctx, &trade_price_amounts, &requested_royalties)?.actions(),
+    );
+    actions.extend(offer.requested_payments().actions());
+    actions.push(Action::fee(fee));
+
+    let deltas = spends.apply(ctx, &actions)?;
+    let spend_bundle = finish(ctx, spends, &deltas)?;
+
+    Ok(offer.take(spend_bundle))
+}
+
+fn requested_payments(
+    ctx: &mut SpendContext,
+    nonce: Bytes32,
+    puzzle_hash: Bytes32,
+    requested: &[RequestedAsset],
+) -> Result<(RequestedPayments, AssetInfo), DriverError> {
+    let mut requested_payments = RequestedPayments::new();
+    let mut asset_info = AssetInfo::new();
+    let mut amounts = OfferAmounts::new();
+
+    for asset in requested {
+        match *asset {
+            RequestedAsset::Xch(amount) => {
+                amounts.xch += amount;
+            }
+            RequestedAsset::Cat {
+                asset_id,
+                hidden_puzzle_hash,
+                amount,
+            } => {
+                *amounts.cats.entry(asset_id).or_default() += amount;
+                asset_info.insert_cat(asset_id, CatAssetInfo::new(hidden_puzzle_hash))?;
+            }
+            RequestedAsset::Nft { launcher_id, info } => {
+                let payment = payment(ctx, nonce, puzzle_hash, 1)?;
+                requested_payments.nfts.insert(launcher_id, vec![payment]);
+                asset_info.insert_nft(launcher_id, info)?;
+            }
+            RequestedAsset::Option { launcher_id, info } => {
+                let payment = payment(ctx, nonce, puzzle_hash, 1)?;
+                requested_payments
+                    .options
+                    .insert(launcher_id, vec![payment]);
+                asset_info.insert_option(launcher_id, info)?;
+            }
+        }
+    }
+
+    if amounts.xch > 0 {
+        let payment = payment(ctx, nonce, puzzle_hash, amounts.xch)?;
+        requested_payments.xch.push(payment);
+    }
+
+    for (asset_id, amount) in amounts.cats {
+        let payment = payment(ctx, nonce, puzzle_hash, amount)?;
+        requested_payments.cats.insert(asset_id, vec![payment]);
+    }
+
+    Ok((requested_payments, asset_info))
+}
+
+fn payment(
+    ctx: &mut SpendContext,
+    nonce: Bytes32,
+    puzzle_hash: Bytes32,
+    amount: u64,
+) -> Result<NotarizedPayment, DriverError> {
+    let hint = ctx.hint(puzzle_hash)?;
+    Ok(NotarizedPayment::new(
+        nonce,
+        vec![Payment::new(puzzle_hash, amount, hint)],
+    ))
+}
+
+fn settlement_send(id: Id, amount: u64) -> Action {
+    Action::send(id, SETTLEMENT_PAYMENT_HASH.into(), amount, Memos::None)
+}
+
+/// Finds the NFTs with royalties that are sent to the settlement payments puzzle by the actions.
+fn offered_nft_royalties(
+    spends: &Spends,
+    actions: &[Action],
+) -> Result<IndexMap<Id, RoyaltyInfo>, DriverError> {
+    let mut royalties = IndexMap::new();
+
+    for action in actions {
+        let Action::Send(send) = action else {
+            continue;
+        };
+
+        if send.puzzle_hash != SETTLEMENT_PAYMENT_HASH.into() {
+            continue;
+        }
+
+        let Some(nft) = spends.nfts.get(&send.id) else {
+            continue;
+        };
+
+        let info = &nft.last()?.asset.info;
+
+        if info.royalty_basis_points > 0 {
+            royalties.insert(
+                send.id,
+                RoyaltyInfo::new(
+                    info.launcher_id,
+                    info.royalty_puzzle_hash,
+                    info.royalty_basis_points,
+                ),
+            );
+        }
+    }
+
+    Ok(royalties)
+}
+
+/// Calculates the fungible amounts that are sent to the settlement payments puzzle by the actions.
+fn offered_amounts(spends: &Spends, actions: &[Action]) -> OfferAmounts {
+    let mut amounts = OfferAmounts::new();
+
+    for action in actions {
+        let Action::Send(send) = action else {
+            continue;
+        };
+
+        if send.puzzle_hash != SETTLEMENT_PAYMENT_HASH.into() {
+            continue;
+        }
+
+        if matches!(send.id, Id::Xch) {
+            amounts.xch += send.amount;
+        } else if let Some(item) = spends.cats.get(&send.id).and_then(|cat| cat.items.first()) {
+            *amounts.cats.entry(item.asset.info.asset_id).or_default() += send.amount;
+        }
+    }
+
+    amounts
+}
+
+/// Calculates the royalties for the requested NFTs that need to be included in the offer.
+///
+/// The taker calculates the trade prices from the total offered amounts, including the royalties
+/// themselves. So the royalties are increased until they match the royalties on the total.
+fn settled_royalty_amounts(
+    offered_amounts: &OfferAmounts,
+    royalties: &[RoyaltyInfo],
+) -> Result<OfferAmounts, DriverError> {
+    if royalties
+        .iter()
+        .any(|royalty| royalty.basis_points >= 10_000)
+    {
+        return Err(DriverError::InvalidRoyalty);
+    }
+
+    let mut royalty_amounts = OfferAmounts::new();
+
+    loop {
+        let trade_prices =
+            calculate_trade_price_amounts(&(offered_amounts + &royalty_amounts), royalties.len());
+        let next = calculate_royalty_amounts(&trade_prices, royalties);
+
+        if next == royalty_amounts {
+            return Ok(royalty_amounts);
+        }
+
+        royalty_amounts = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use chia_sdk_test::Simulator;
+    use indexmap::indexmap;
+
+    use crate::{Relation, finish_with_key};
+
+    use super::*;
+
+    #[test]
+    fn test_offer_xch_for_royalty_nft() -> anyhow::Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1041);
+        let bob = sim.bls(0);
+        let carol = sim.bls(0);
+
+        let bob_hint = ctx.hint(bob.puzzle_hash)?;
+
+        // Mint an NFT with royalties for Bob
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[
+                Action::mint_empty_royalty_nft(carol.puzzle_hash, 300),
+                Action::send(Id::New(0), bob.puzzle_hash, 1, bob_hint),
+            ],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let nft = outputs.nfts[&Id::New(0)];
+        let alice_coin = outputs.xch[0];
+
+        // Make offer
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice_coin);
+
+        let offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![
+                Action::send(Id::Xch, SETTLEMENT_PAYMENT_HASH.into(), 1000, Memos::None),
+                Action::fee(10),
+            ],
+            &[RequestedAsset::nft(
+                nft.info.launcher_id,
+                NftAssetInfo::new(
+                    nft.info.metadata,
+                    nft.info.metadata_updater_puzzle_hash,
+                    nft.info.royalty_puzzle_hash,
+                    nft.info.royalty_basis_points,
+                ),
+            )],
+            OfferExpiration::default(),
+            |ctx, spends, deltas| finish_with_key(ctx, spends, deltas, &alice),
+        )?;
+
+        assert_eq!(offer.offered_coins().amounts().xch, 1030);
+        assert_eq!(offer.offered_royalty_amounts().xch, 30);
+
+        // Take offer
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(nft);
+
+        let spend_bundle = take_offer(&mut ctx, offer, spends, 0, |ctx, spends, deltas| {
+            finish_with_key(ctx, spends, deltas, &bob)
+        })?;
+
+        sim.new_transaction(spend_bundle)?;
+
+        let royalties = sim.unspent_coins(carol.puzzle_hash, false);
+        assert_eq!(royalties.iter().map(|coin| coin.amount).sum::<u64>(), 30);
+
+        let payments = sim.unspent_coins(bob.puzzle_hash, false);
+        assert_eq!(payments.iter().map(|coin| coin.amount).sum::<u64>(), 1000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offer_royalty_nft_for_hidden_puzzle_cat() -> anyhow::Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(1005);
+        let carol = sim.bls(0);
+
+        let hidden_puzzle_hash = Bytes32::new([1; 32]);
+
+        // Mint an NFT with royalties for Alice
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::mint_empty_royalty_nft(carol.puzzle_hash, 300)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let nft = outputs.nfts[&Id::New(0)];
+
+        // Issue a CAT with a hidden puzzle for Bob
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(bob.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::single_issue_cat(Some(hidden_puzzle_hash), 1000)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&bob.sk))?;
+
+        let cat = outputs.cats[&Id::New(0)][0];
+        let asset_id = cat.info.asset_id;
+        let bob_coin = outputs.xch[0];
+
+        // Make offer
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(nft);
+
+        let offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![Action::send(
+                Id::Existing(nft.info.launcher_id),
+                SETTLEMENT_PAYMENT_HASH.into(),
+                1,
+                Memos::None,
+            )],
+            &[RequestedAsset::cat(asset_id, Some(hidden_puzzle_hash), 900)],
+            OfferExpiration::default(),
+            |ctx, spends, deltas| finish_with_key(ctx, spends, deltas, &alice),
+        )?;
+
+        assert_eq!(offer.requested_royalty_amounts().cats[&asset_id], 27);
+
+        // Take offer
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(cat);
+        spends.add(bob_coin);
+
+        let spend_bundle = take_offer(&mut ctx, offer, spends, 5, |ctx, spends, deltas| {
+            finish_with_key(ctx, spends, deltas, &bob)
+        })?;
+
+        sim.new_transaction(spend_bundle)?;
+
+        let royalty_amount: u64 = sim
+            .hinted_coins(carol.puzzle_hash)
+            .into_iter()
+            .filter_map(|coin_id| sim.coin_state(coin_id))
+            .filter(|coin_state| coin_state.spent_height.is_none())
+            .map(|coin_state| coin_state.coin.amount)
+            .sum();
+        assert_eq!(royalty_amount, 27);
+
+        let payment_amount: u64 = sim
+            .hinted_coins(alice.puzzle_hash)
+            .into_iter()
+            .filter_map(|coin_id| sim.coin_state(coin_id))
+            .filter(|coin_state| coin_state.spent_height.is_none() && coin_state.coin.amount > 1)
+            .map(|coin_state| coin_state.coin.amount)
+            .sum();
+        assert_eq!(payment_amount, 900);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offer_expiration() -> anyhow::Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let alice = sim.bls(1000);
+        let bob = sim.bls(1);
+
+        // Mint an NFT for Bob
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(bob.coin);
+
+        let deltas = spends.apply(&mut ctx, &[Action::mint_empty_nft()])?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { bob.puzzle_hash => bob.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&bob.sk))?;
+
+        let nft = outputs.nfts[&Id::New(0)];
+
+        // Make offer
+        let expiration = OfferExpiration::new(Some(sim.next_timestamp() + 100), None);
+
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![Action::send(
+                Id::Xch,
+                SETTLEMENT_PAYMENT_HASH.into(),
+                1000,
+                Memos::None,
+            )],
+            &[RequestedAsset::nft(
+                nft.info.launcher_id,
+                NftAssetInfo::new(
+                    nft.info.metadata,
+                    nft.info.metadata_updater_puzzle_hash,
+                    nft.info.royalty_puzzle_hash,
+                    nft.info.royalty_basis_points,
+                ),
+            )],
+            expiration,
+            |ctx, spends, deltas| finish_with_key(ctx, spends, deltas, &alice),
+        )?;
+
+        let spend_bundle = offer.to_spend_bundle(&mut ctx)?;
+        let offer = Offer::from_spend_bundle(&mut ctx, &spend_bundle)?;
+
+        assert_eq!(offer.expiration()?, expiration);
+        offer.validate_expiration(sim.next_timestamp(), sim.height() + 1)?;
+        assert!(matches!(
+            offer.validate_expiration(sim.next_timestamp() + 100, sim.height() + 1),
+            Err(DriverError::OfferExpired)
+        ));
+
+        // Take offer after it has expired
+        sim.pass_time(101);
+
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(nft);
+
+        let spend_bundle = take_offer(&mut ctx, offer, spends, 0, |ctx, spends, deltas| {
+            finish_with_key(ctx, spends, deltas, &bob)
+        })?;
+
+        assert!(sim.new_transaction(spend_bundle).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_settled_royalty_amounts() -> anyhow::Result<()> {
+        let launcher_id = Bytes32::new([1; 32]);
+        let royalties = [RoyaltyInfo::new(launcher_id, Bytes32::default(), 300)];
+
+        let amounts = settled_royalty_amounts(
+            &OfferAmounts {
+                xch: 1_000_000,
+                cats: IndexMap::new(),
+            },
+            &royalties,
+        )?;
+
+        // The royalty is calculated on the total amount, including the royalty itself
+        assert_eq!(amounts.xch, 30_927);
+        assert_eq!(
+            calculate_royalty_amounts(
+                &calculate_trade_price_amounts(
+                    &OfferAmounts {
+                        xch: 1_000_000 + amounts.xch,
+                        cats: IndexMap::new(),
+                    },
+                    1,
+                ),
+                &royalties,
+            ),
+            amounts
+        );
+
+        assert!(matches!(
+            settled_royalty_amounts(
+                &OfferAmounts::new(),
+                &[RoyaltyInfo::new(launcher_id, Bytes32::default(), 10_000)]
+            ),
+            Err(DriverError::InvalidRoyalty)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,519 @@
+use std::{collections::HashSet, fmt::Display};
+
+use chia_bls::aggregate_verify;
+use chia_protocol::{Bytes32, Coin, CoinSpend};
+use chia_puzzle_types::offer::NotarizedPayment;
+use chia_sdk_signer::{AggSigConstants, RequiredSignature};
+use chia_sdk_types::{Condition, conditions::TradePrice, run_puzzle};
+use chia_sdk_utils::CoinSource;
+use clvm_traits::{FromClvm, ToClvm};
+use clvmr::Allocator;
+use indexmap::IndexMap;
+
+use crate::{
+    DriverError, Nft, Offer, OfferExpiration, Puzzle, calculate_trade_price_amounts,
+    calculate_trade_prices, offers::offer::coin_spend_conditions,
+};
+
+/// A problem that prevents an offer from being taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfferIssue {
+    /// An offered coin doesn't exist on the blockchain.
+    MissingCoin(Bytes32),
+    /// An offered coin has already been spent, so the offer has been taken or cancelled.
+    SpentCoin(Bytes32),
+    /// The spend of a coin in the offer failed to run.
+    InvalidSpend(Bytes32),
+    /// The aggregated signature doesn't match the signatures required by the coin spends.
+    InvalidSignature,
+    /// The offer can't be included in a block with the given timestamp and height.
+    Expired,
+    /// A requested payment has a different nonce than the one calculated from the offered coins.
+    InvalidNonce(Bytes32),
+    /// The requested payments for an NFT or option contract don't create it exactly once.
+    InvalidSingletonPayment(Bytes32),
+    /// There is no asset info for a requested NFT or option contract, or the transfer of an offered
+    /// NFT can't be found, so its royalties can't be checked.
+    MissingAssetInfo(Bytes32),
+    /// An offered NFT isn't transferred with trade prices that match the requested payments, or
+    /// its transfer can't be run, so its royalties won't be paid correctly.
+    RoyaltyMismatch(Bytes32),
+}
+
+/// The result of validating an offer, with every issue that was found.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OfferReport {
+    pub issues: Vec<OfferIssue>,
+}
+
+impl OfferReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Offer {
+    /// Checks whether the offer can be taken, by validating it against the current state of the
+    /// blockchain from a [`CoinSource`], and the timestamp and height of the next block.
+    ///
+    /// Only the maker's side of the offer is checked, so the signature is verified against the
+    /// signatures required by the offered coin spends. Signatures that are verified by the puzzles
+    /// themselves (such as secp signatures) are checked when the coin spends are run.
+    pub async fn validate<S>(
+        &self,
+        source: &S,
+        constants: &AggSigConstants,
+        timestamp: u64,
+        height: u32,
+    ) -> Result<OfferReport, DriverError>
+    where
+        S: CoinSource,
+        S::Error: Display,
+    {
+        let mut allocator = Allocator::new();
+        let mut report = OfferReport::default();
+
+        let coin_spends = &self.spend_bundle().coin_spends;
+        let mut expiration = OfferExpiration::default();
+        let mut created_coin_ids = HashSet::new();
+
+        for coin_spend in coin_spends {
+            let Ok(conditions) = coin_spend_conditions(&mut allocator, coin_spend) else {
+                report
+                    .issues
+                    .push(OfferIssue::InvalidSpend(coin_spend.coin.coin_id()));
+                continue;
+            };
+
+            for condition in conditions {
+                expiration.update(&condition);
+
+                if let Some(create_coin) = condition.into_create_coin() {
+                    created_coin_ids.insert(
+                        Coin::new(
+                            coin_spend.coin.coin_id(),
+                            create_coin.puzzle_hash,
+                            create_coin.amount,
+                        )
+                        .coin_id(),
+                    );
+                }
+            }
+        }
+
+        let offered_coin_ids: Vec<Bytes32> = coin_spends
+            .iter()
+            .map(|coin_spend| coin_spend.coin.coin_id())
+            .filter(|coin_id| !created_coin_ids.contains(coin_id))
+            .collect();
+
+        let coin_states: IndexMap<Bytes32, _> = source
+            .coin_states(offered_coin_ids.clone())
+            .await
+            .map_err(|error| DriverError::CoinSource(error.to_string()))?
+            .into_iter()
+            .map(|coin_state| (coin_state.coin.coin_id(), coin_state))
+            .collect();
+
+        for &coin_id in &offered_coin_ids {
+            match coin_states.get(&coin_id) {
+                None => report.issues.push(OfferIssue::MissingCoin(coin_id)),
+                Some(coin_state) if coin_state.spent_height.is_some() => {
+                    report.issues.push(OfferIssue::SpentCoin(coin_id));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if !self.is_signature_valid(&mut allocator, constants) {
+            report.issues.push(OfferIssue::InvalidSignature);
+        }
+
+        if expiration.is_expired(timestamp, height) {
+            report.issues.push(OfferIssue::Expired);
+        }
+
+        self.validate_requested_payments(&mut report, Offer::nonce(offered_coin_ids));
+        self.validate_royalties(&mut allocator, &mut report);
+
+        Ok(report)
+    }
+
+    fn is_signature_valid(&self, allocator: &mut Allocator, constants: &AggSigConstants) -> bool {
+        let Ok(required_signatures) = RequiredSignature::from_coin_spends(
+            allocator,
+            &self.spend_bundle().coin_spends,
+            constants,
+        ) else {
+            return false;
+        };
+
+        let data: Vec<_> = required_signatures
+            .into_iter()
+            .filter_map(|required| match required {
+                RequiredSignature::Bls(required) => Some((required.public_key, required.message())),
+                RequiredSignature::Secp(_) => None,
+            })
+            .collect();
+
+        aggregate_verify(
+            &self.spend_bundle().aggregated_signature,
+            data.iter()
+                .map(|(public_key, message)| (public_key, message.as_slice())),
+        )
+    }
+
+    fn validate_requested_payments(&self, report: &mut OfferReport, nonce: Bytes32) {
+        let requested_payments = self.requested_payments();
+
+        let notarized_payments = requested_payments
+            .xch
+            .iter()
+            .chain(requested_payments.cats.values().flatten())
+            .chain(requested_payments.nfts.values().flatten())
+            .chain(requested_payments.options.values().flatten());
+
+        for notarized_payment in notarized_payments {
+            if notarized_payment.nonce != nonce {
+                report
+                    .issues
+                    .push(OfferIssue::InvalidNonce(notarized_payment.nonce));
+            }
+        }
+
+        for (&launcher_id, notarized_payments) in &requested_payments.nfts {
+            if self.asset_info().nft(launcher_id).is_none() {
+                report
+                    .issues
+                    .push(OfferIssue::MissingAssetInfo(launcher_id));
+            }
+
+            if !is_singleton_payment(notarized_payments) {
+                report
+                    .issues
+                    .push(OfferIssue::InvalidSingletonPayment(launcher_id));
+            }
+        }
+
+        for (&launcher_id, notarized_payments) in &requested_payments.options {
+            if self.asset_info().option(launcher_id).is_none() {
+                report
+                    .issues
+                    .push(OfferIssue::MissingAssetInfo(launcher_id));
+            }
+
+            if !is_singleton_payment(notarized_payments) {
+                report
+                    .issues
+                    .push(OfferIssue::InvalidSingletonPayment(launcher_id));
+            }
+        }
+    }
+
+    fn validate_royalties(&self, allocator: &mut Allocator, report: &mut OfferReport) {
+        let royalties = self.requested_royalties();
+
+        let trade_prices = calculate_trade_prices(
+            &calculate_trade_price_amounts(&self.requested_payments().amounts(), royalties.len()),
+            self.asset_info(),
+        );
+
+        for royalty in royalties {
+            let parent_spend =
+                self.offered_coins()
+                    .nfts
+                    .get(&royalty.launcher_id)
+                    .and_then(|nft| {
+                        self.spend_bundle().coin_spends.iter().find(|coin_spend| {
+                            coin_spend.coin.coin_id() == nft.coin.parent_coin_info
+                        })
+                    });
+
+            let Some(parent_spend) = parent_spend else {
+                report
+                    .issues
+                    .push(OfferIssue::MissingAssetInfo(royalty.launcher_id));
+                continue;
+            };
+
+            // A transfer that can't be parsed or run doesn't pay the royalties we expect either.
+            let is_valid = nft_transfer_trade_prices(allocator, parent_spend).is_ok_and(
+                |transfer_trade_prices| is_same_trade_prices(&transfer_trade_prices, &trade_prices),
+            );
+
+            if !is_valid {
+                report
+                    .issues
+                    .push(OfferIssue::RoyaltyMismatch(royalty.launcher_id));
+            }
+        }
+    }
+}
+
+/// Singletons are created by a payment with an odd amount, and even amounts are paid out as XCH.
+fn is_singleton_payment(notarized_payments: &[NotarizedPayment]) -> bool {
+    notarized_payments
+        .iter()
+        .flat_map(|notarized_payment| &notarized_payment.payments)
+        .filter(|payment| payment.amount % 2 == 1)
+        .count()
+        == 1
+}
+
+/// Finds the trade prices that an NFT was transferred with, from the spend of its parent.
+fn nft_transfer_trade_prices(
+    allocator: &mut Allocator,
+    parent_spend: &CoinSpend,
+) -> Result<Vec<TradePrice>, DriverError> {
+    let puzzle = parent_spend.puzzle_reveal.to_clvm(allocator)?;
+    let puzzle = Puzzle::parse(allocator, puzzle);
+    let solution = parent_spend.solution.to_clvm(allocator)?;
+
+    let Some((_, p2_puzzle, p2_solution)) =
+        Nft::parse(allocator, parent_spend.coin, puzzle, solution)?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let output = run_puzzle(allocator, p2_puzzle.ptr(), p2_solution)?;
+    let conditions = Vec::<Condition>::from_clvm(allocator, output)?;
+
+    Ok(conditions
+        .into_iter()
+        .find_map(|condition| match condition {
+            Condition::TransferNft(transfer_nft) => Some(transfer_nft.trade_prices),
+            _ => None,
+        })
+        .unwrap_or_default())
+}
+
+fn is_same_trade_prices(actual: &[TradePrice], expected: &[TradePrice]) -> bool {
+    actual.len() == expected.len()
+        && expected
+            .iter()
+            .all(|trade_price| actual.contains(trade_price))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use anyhow::Result;
+    use chia_bls::Signature;
+    use chia_protocol::{Program, SpendBundle};
+    use chia_puzzle_types::Memos;
+    use chia_puzzles::SETTLEMENT_PAYMENT_HASH;
+    use chia_sdk_test::Simulator;
+    use chia_sdk_types::TESTNET11_CONSTANTS;
+    use indexmap::indexmap;
+
+    use crate::{
+        Action, Id, Relation, RequestedAsset, SpendContext, Spends, finish_with_key, make_offer,
+        take_offer,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_validate_royalty_nft_offer() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let constants = AggSigConstants::new(TESTNET11_CONSTANTS.agg_sig_me_additional_data);
+
+        let alice = sim.bls(1);
+        let bob = sim.bls(1030);
+        let carol = sim.bls(0);
+
+        // Mint an NFT with royalties for Alice
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let deltas = spends.apply(
+            &mut ctx,
+            &[Action::mint_empty_royalty_nft(carol.puzzle_hash, 300)],
+        )?;
+
+        let outputs = spends.finish_with_keys(
+            &mut ctx,
+            &deltas,
+            Relation::None,
+            &indexmap! { alice.puzzle_hash => alice.pk },
+        )?;
+
+        sim.spend_coins(ctx.take(), slice::from_ref(&alice.sk))?;
+
+        let nft = outputs.nfts[&Id::New(0)];
+
+        // Offer the NFT for XCH, with an expiration
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(nft);
+
+        let offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![Action::send(
+                Id::Existing(nft.info.launcher_id),
+                SETTLEMENT_PAYMENT_HASH.into(),
+                1,
+                Memos::None,
+            )],
+            &[RequestedAsset::xch(1000)],
+            OfferExpiration::new(Some(100), None),
+            |ctx, spends, deltas| finish_with_key(ctx, spends, deltas, &alice),
+        )?;
+
+        let report = offer.validate(&sim, &constants, 0, 0).await?;
+        assert!(report.is_valid(), "{report:?}");
+
+        // The offer can't be taken after it expires
+        let report = offer.validate(&sim, &constants, 100, 0).await?;
+        assert_eq!(report.issues, vec![OfferIssue::Expired]);
+
+        // The signature must match the offered coin spends
+        let tampered = Offer::new(
+            SpendBundle::new(
+                offer.spend_bundle().coin_spends.clone(),
+                Signature::default(),
+            ),
+            offer.offered_coins().clone(),
+            offer.requested_payments().clone(),
+            offer.asset_info().clone(),
+        );
+
+        let report = tampered.validate(&sim, &constants, 0, 0).await?;
+        assert_eq!(report.issues, vec![OfferIssue::InvalidSignature]);
+
+        // The royalties can't be checked without the transfer of the offered NFT
+        let launcher_id = nft.info.launcher_id;
+
+        let without_transfer = Offer::new(
+            SpendBundle::new(
+                offer
+                    .spend_bundle()
+                    .coin_spends
+                    .iter()
+                    .filter(|coin_spend| coin_spend.coin.coin_id() != nft.coin.coin_id())
+                    .cloned()
+                    .collect(),
+                offer.spend_bundle().aggregated_signature.clone(),
+            ),
+            offer.offered_coins().clone(),
+            offer.requested_payments().clone(),
+            offer.asset_info().clone(),
+        );
+
+        let report = without_transfer.validate(&sim, &constants, 0, 0).await?;
+        assert!(
+            report
+                .issues
+                .contains(&OfferIssue::MissingAssetInfo(launcher_id)),
+            "{report:?}"
+        );
+
+        // A malformed transfer of the offered NFT is reported instead of failing the validation
+        let malformed_transfer = Offer::new(
+            SpendBundle::new(
+                offer
+                    .spend_bundle()
+                    .coin_spends
+                    .iter()
+                    .map(|coin_spend| {
+                        if coin_spend.coin.coin_id() == nft.coin.coin_id() {
+                            CoinSpend::new(
+                                coin_spend.coin,
+                                coin_spend.puzzle_reveal.clone(),
+                                Program::from(vec![0x80]),
+                            )
+                        } else {
+                            coin_spend.clone()
+                        }
+                    })
+                    .collect(),
+                offer.spend_bundle().aggregated_signature.clone(),
+            ),
+            offer.offered_coins().clone(),
+            offer.requested_payments().clone(),
+            offer.asset_info().clone(),
+        );
+
+        let report = malformed_transfer.validate(&sim, &constants, 0, 0).await?;
+        assert!(
+            report
+                .issues
+                .contains(&OfferIssue::InvalidSpend(nft.coin.coin_id())),
+            "{report:?}"
+        );
+        assert!(
+            report
+                .issues
+                .contains(&OfferIssue::RoyaltyMismatch(launcher_id)),
+            "{report:?}"
+        );
+
+        // Take the offer, after which the offered NFT has been spent
+        let mut spends = Spends::new(bob.puzzle_hash);
+        spends.add(bob.coin);
+
+        let spend_bundle =
+            take_offer(&mut ctx, offer.clone(), spends, 0, |ctx, spends, deltas| {
+                finish_with_key(ctx, spends, deltas, &bob)
+            })?;
+
+        sim.new_transaction(spend_bundle)?;
+
+        let report = offer.validate(&sim, &constants, 0, 0).await?;
+        assert_eq!(
+            report.issues,
+            vec![OfferIssue::SpentCoin(nft.coin.coin_id())]
+        );
+
+        // The signature is checked even if there are other issues
+        let report = tampered.validate(&sim, &constants, 0, 0).await?;
+        assert_eq!(
+            report.issues,
+            vec![
+                OfferIssue::SpentCoin(nft.coin.coin_id()),
+                OfferIssue::InvalidSignature
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_missing_coins() -> Result<()> {
+        let mut sim = Simulator::new();
+        let mut ctx = SpendContext::new();
+
+        let constants = AggSigConstants::new(TESTNET11_CONSTANTS.agg_sig_me_additional_data);
+
+        let alice = sim.bls(1000);
+
+        // Make an offer, then validate it against a chain without the offered coin
+        let mut spends = Spends::new(alice.puzzle_hash);
+        spends.add(alice.coin);
+
+        let offer = make_offer(
+            &mut ctx,
+            spends,
+            vec![Action::send(
+                Id::Xch,
+                SETTLEMENT_PAYMENT_HASH.into(),
+                1000,
+                Memos::None,
+            )],
+            &[RequestedAsset::xch(500)],
+            OfferExpiration::default(),
+            |ctx, spends, deltas| finish_with_key(ctx, spends, deltas, &alice),
+        )?;
+
+        let report = offer.validate(&Simulator::new(), &constants, 0, 0).await?;
+        assert_eq!(
+            report.issues,
+            vec![OfferIssue::MissingCoin(alice.coin.coin_id())]
+        );
+
+        Ok(())
+    }
+}
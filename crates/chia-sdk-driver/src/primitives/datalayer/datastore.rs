@@ -111,6 +111,22 @@ where
             parent_amount: self.coin.amount,
         })
     }
+
+    /// Returns the child [`DataStore`] that is created when this one is spent with the given info.
+    pub fn child(
+        &self,
+        ctx: &mut SpendContext,
+        info: DataStoreInfo<M>,
+    ) -> Result<Self, DriverError> {
+        let puzzle_hash =
+            SingletonArgs::curry_tree_hash(info.launcher_id, info.inner_puzzle_hash(ctx)?);
+
+        Ok(Self::new(
+            Coin::new(self.coin.coin_id(), puzzle_hash.into(), self.coin.amount),
+            Proof::Lineage(self.child_lineage_proof(ctx)?),
+            info,
+        ))
+    }
 }
 
 #[derive(ToClvm, FromClvm, Debug, Clone, PartialEq, Eq)]
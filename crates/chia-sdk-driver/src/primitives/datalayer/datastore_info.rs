@@ -3,12 +3,10 @@ use crate::{
 };
 use chia_protocol::{Bytes, Bytes32};
 use chia_puzzle_types::nft::NftStateLayerArgs;
+use chia_puzzles::NFT_STATE_LAYER_HASH;
 use chia_sdk_types::{
     MerkleTree,
-    puzzles::{
-        DELEGATION_LAYER_PUZZLE_HASH, DL_METADATA_UPDATER_PUZZLE_HASH, DelegationLayerArgs,
-        WriterLayerArgs,
-    },
+    puzzles::{DL_METADATA_UPDATER_PUZZLE_HASH, DelegationLayerArgs, WriterLayerArgs},
 };
 use clvm_traits::{ClvmDecoder, ClvmEncoder, FromClvm, FromClvmError, Raw, ToClvm, ToClvmError};
 use clvm_utils::{CurriedProgram, ToTreeHash, TreeHash};
@@ -247,27 +245,27 @@ impl<M> DataStoreInfo<M> {
     {
         let metadata_ptr = ctx.alloc(&self.metadata)?;
 
-        if !self.delegated_puzzles.is_empty() {
-            return Ok(NftStateLayerArgs::curry_tree_hash(
-                ctx.tree_hash(metadata_ptr),
-                CurriedProgram {
-                    program: DELEGATION_LAYER_PUZZLE_HASH,
-                    args: DelegationLayerArgs {
-                        mod_hash: DELEGATION_LAYER_PUZZLE_HASH.into(),
-                        launcher_id: self.launcher_id,
-                        owner_puzzle_hash: self.owner_puzzle_hash,
-                        merkle_root: get_merkle_tree(ctx, self.delegated_puzzles.clone())?.root(),
-                    },
-                }
-                .tree_hash(),
-            ));
+        let inner_puzzle_hash = if self.delegated_puzzles.is_empty() {
+            self.owner_puzzle_hash.into()
+        } else {
+            DelegationLayerArgs::curry_tree_hash(
+                self.launcher_id,
+                self.owner_puzzle_hash,
+                get_merkle_tree(ctx, self.delegated_puzzles.clone())?.root(),
+            )
+        };
+
+        // Data stores use their own metadata updater, rather than the default one for NFTs.
+        Ok(CurriedProgram {
+            program: TreeHash::new(NFT_STATE_LAYER_HASH),
+            args: NftStateLayerArgs::<TreeHash, TreeHash> {
+                mod_hash: NFT_STATE_LAYER_HASH.into(),
+                metadata: ctx.tree_hash(metadata_ptr),
+                metadata_updater_puzzle_hash: DL_METADATA_UPDATER_PUZZLE_HASH.into(),
+                inner_puzzle: inner_puzzle_hash,
+            },
         }
-
-        let inner_ph_hash: TreeHash = self.owner_puzzle_hash.into();
-        Ok(NftStateLayerArgs::curry_tree_hash(
-            ctx.tree_hash(metadata_ptr),
-            inner_ph_hash,
-        ))
+        .tree_hash())
     }
 }
 
@@ -1,8 +1,8 @@
 use chia_protocol::{Bytes32, Coin};
 use chia_puzzle_types::{
-    LineageProof, Memos, Proof, did::DidSolution, singleton::SingletonSolution,
+    LineageProof, Memos, Proof, singleton::SingletonSolution, standard::StandardArgs,
 };
-use chia_sdk_types::{Condition, Conditions, run_puzzle};
+use chia_sdk_types::{Condition, Conditions, puzzles::DidSolution, run_puzzle};
 use clvm_traits::{FromClvm, ToClvm};
 use clvm_utils::{ToTreeHash, tree_hash};
 use clvmr::{Allocator, NodePtr};
@@ -14,8 +14,10 @@ use crate::{
 
 mod did_info;
 mod did_launcher;
+mod did_recovery;
 
 pub use did_info::*;
+pub use did_recovery::*;
 
 /// Contains all information needed to spend the outer puzzles of DID coins.
 /// The [`DidInfo`] is used to construct the puzzle, but the [`Proof`] is needed for the solution.
@@ -198,6 +200,9 @@ impl Did {
     ///
     /// This relies on the child being hinted and having the same metadata as the parent.
     /// If this is not the case, the DID cannot be parsed or spent without additional context.
+    ///
+    /// Recovery spends aren't hinted with the new p2 puzzle hash, so it's assumed to be the standard
+    /// puzzle of the public key that signed the recovery. If it isn't, [`DriverError::MissingHint`] is returned.
     pub fn parse_child(
         allocator: &mut Allocator,
         parent_coin: Coin,
@@ -227,27 +232,6 @@ impl Did {
         let singleton_solution =
             SingletonLayer::<Puzzle>::parse_solution(allocator, parent_solution)?;
 
-        let output = run_puzzle(
-            allocator,
-            singleton_layer.inner_puzzle.ptr(),
-            singleton_solution.inner_solution,
-        )?;
-        let conditions = Vec::<Condition>::from_clvm(allocator, output)?;
-
-        let Some(create_coin) = conditions
-            .into_iter()
-            .filter_map(Condition::into_create_coin)
-            .find(|create_coin| create_coin.amount % 2 == 1)
-        else {
-            return Err(DriverError::MissingChild);
-        };
-
-        let Memos::Some(memos) = create_coin.memos else {
-            return Err(DriverError::MissingHint);
-        };
-
-        let (hint, _) = <(Bytes32, NodePtr)>::from_clvm(allocator, memos)?;
-
         let metadata_ptr = did_layer.metadata.to_clvm(allocator)?;
         let metadata_hash = tree_hash(allocator, metadata_ptr);
         let did_layer_hashed = did_layer.with_metadata(metadata_hash);
@@ -256,7 +240,40 @@ impl Did {
         let layers = SingletonLayer::new(singleton_layer.launcher_id, did_layer);
 
         let mut info = DidInfo::from_layers(&layers);
-        info.p2_puzzle_hash = hint;
+
+        let did_solution =
+            DidSolution::<NodePtr>::from_clvm(allocator, singleton_solution.inner_solution)?;
+
+        if let DidSolution::Recover(recovery) = did_solution {
+            info.p2_puzzle_hash = StandardArgs::curry_tree_hash(recovery.public_key).into();
+
+            if info.inner_puzzle_hash() != recovery.new_inner_puzzle_hash.into() {
+                return Err(DriverError::MissingHint);
+            }
+        } else {
+            let output = run_puzzle(
+                allocator,
+                singleton_layer.inner_puzzle.ptr(),
+                singleton_solution.inner_solution,
+            )?;
+            let conditions = Vec::<Condition>::from_clvm(allocator, output)?;
+
+            let Some(create_coin) = conditions
+                .into_iter()
+                .filter_map(Condition::into_create_coin)
+                .find(|create_coin| create_coin.amount % 2 == 1)
+            else {
+                return Err(DriverError::MissingChild);
+            };
+
+            let Memos::Some(memos) = create_coin.memos else {
+                return Err(DriverError::MissingHint);
+            };
+
+            let (hint, _) = <(Bytes32, NodePtr)>::from_clvm(allocator, memos)?;
+
+            info.p2_puzzle_hash = hint;
+        }
 
         Ok(Some(Self {
             coin,
@@ -307,8 +324,10 @@ impl Did {
 
 #[cfg(test)]
 mod tests {
+    use std::slice;
+
     use chia_protocol::Bytes32;
-    use chia_sdk_test::Simulator;
+    use chia_sdk_test::{BlsPairWithCoin, Simulator};
     use clvm_traits::clvm_list;
     use rstest::rstest;
 
@@ -489,4 +508,171 @@ mod tests {
 
         Ok(())
     }
+
+    #[allow(clippy::type_complexity)]
+    fn create_recoverable_did(
+        sim: &mut Simulator,
+        ctx: &mut SpendContext,
+        num_verifications_required: u64,
+    ) -> anyhow::Result<(StandardLayer, Did, Vec<(BlsPairWithCoin, Did)>)> {
+        let mut recovery_dids = Vec::new();
+
+        for _ in 0..2 {
+            let owner = sim.bls(1);
+            let owner_p2 = StandardLayer::new(owner.pk);
+
+            let (create_did, did) =
+                Launcher::new(owner.coin.coin_id(), 1).create_simple_did(ctx, &owner_p2)?;
+            owner_p2.spend(ctx, owner.coin, create_did)?;
+            sim.spend_coins(ctx.take(), slice::from_ref(&owner.sk))?;
+
+            recovery_dids.push((owner, did));
+        }
+
+        let recovery_list = recovery_dids
+            .iter()
+            .map(|(_, did)| did.info.launcher_id)
+            .collect::<Vec<_>>();
+        let recovery_list_ptr = ctx.alloc(&recovery_list)?;
+        let recovery_list_hash = ctx.tree_hash(recovery_list_ptr).into();
+
+        let alice = sim.bls(1);
+        let alice_p2 = StandardLayer::new(alice.pk);
+
+        let (create_did, did) = Launcher::new(alice.coin.coin_id(), 1).create_did(
+            ctx,
+            Some(recovery_list_hash),
+            num_verifications_required,
+            HashedPtr::NIL,
+            &alice_p2,
+        )?;
+        alice_p2.spend(ctx, alice.coin, create_did)?;
+        sim.spend_coins(ctx.take(), &[alice.sk])?;
+
+        Ok((alice_p2, did, recovery_dids))
+    }
+
+    #[rstest]
+    fn test_recover_did(#[values(1, 2)] num_verifications_required: u64) -> anyhow::Result<()> {
+        let mut sim = Simulator::new();
+        let ctx = &mut SpendContext::new();
+
+        let (alice_p2, did, recovery_dids) =
+            create_recoverable_did(&mut sim, ctx, num_verifications_required)?;
+
+        // The DID is recovered to a new key, without a signature from the original owner.
+        let bob = sim.bls(0);
+        let attestation = did.recovery_attestation(bob.puzzle_hash, bob.pk);
+
+        let mut recovery_list = Vec::new();
+        let mut coin_proofs = Vec::new();
+        let mut keys = vec![bob.sk.clone()];
+
+        for (owner, recovery_did) in recovery_dids {
+            let owner_p2 = StandardLayer::new(owner.pk);
+            let (_, coin_proof) =
+                recovery_did.attest(ctx, &owner_p2, attestation, Conditions::new())?;
+
+            recovery_list.push(recovery_did.info.launcher_id);
+            coin_proofs.push(coin_proof);
+            keys.push(owner.sk);
+        }
+
+        let p2_puzzle = alice_p2.construct_puzzle(ctx)?;
+        let recovered_did = did.recover(
+            ctx,
+            p2_puzzle,
+            attestation,
+            bob.puzzle_hash,
+            recovery_list,
+            coin_proofs,
+        )?;
+
+        sim.spend_coins(ctx.take(), &keys)?;
+
+        assert_eq!(recovered_did.info.p2_puzzle_hash, bob.puzzle_hash);
+        assert_ne!(sim.coin_state(recovered_did.coin.coin_id()), None);
+
+        let mut allocator = Allocator::new();
+
+        let puzzle_reveal = sim
+            .puzzle_reveal(did.coin.coin_id())
+            .expect("missing puzzle")
+            .to_clvm(&mut allocator)?;
+
+        let solution = sim
+            .solution(did.coin.coin_id())
+            .expect("missing solution")
+            .to_clvm(&mut allocator)?;
+
+        let puzzle = Puzzle::parse(&allocator, puzzle_reveal);
+
+        let parsed_did = Did::parse_child(
+            &mut allocator,
+            did.coin,
+            puzzle,
+            solution,
+            recovered_did.coin,
+        )?
+        .expect("could not parse did");
+
+        assert_eq!(parsed_did, recovered_did);
+
+        // The new owner can spend the recovered DID.
+        let bob_p2 = StandardLayer::new(bob.pk);
+        let did = recovered_did.update(ctx, &bob_p2, Conditions::new())?;
+        assert_eq!(did.info.p2_puzzle_hash, bob.puzzle_hash);
+        sim.spend_coins(ctx.take(), &[bob.sk])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recover_did_invalid() -> anyhow::Result<()> {
+        let mut sim = Simulator::new();
+        let ctx = &mut SpendContext::new();
+
+        let (alice_p2, did, recovery_dids) = create_recoverable_did(&mut sim, ctx, 1)?;
+
+        let bob = sim.bls(0);
+        let attestation = did.recovery_attestation(bob.puzzle_hash, bob.pk);
+
+        let (owner, recovery_did) = &recovery_dids[0];
+        let owner_p2 = StandardLayer::new(owner.pk);
+        let (_, coin_proof) =
+            recovery_did.attest(ctx, &owner_p2, attestation, Conditions::new())?;
+
+        let recovery_list = recovery_dids
+            .iter()
+            .map(|(_, did)| did.info.launcher_id)
+            .collect::<Vec<_>>();
+        let p2_puzzle = alice_p2.construct_puzzle(ctx)?;
+
+        assert!(matches!(
+            did.recover(
+                ctx,
+                p2_puzzle,
+                attestation,
+                bob.puzzle_hash,
+                recovery_list[..1].to_vec(),
+                vec![coin_proof],
+            ),
+            Err(DriverError::InvalidRecoveryList)
+        ));
+
+        // Even though only one verification is required, each DID in the list must attest.
+        assert!(matches!(
+            did.recover(
+                ctx,
+                p2_puzzle,
+                attestation,
+                bob.puzzle_hash,
+                recovery_list,
+                vec![coin_proof],
+            ),
+            Err(DriverError::MissingAttestations)
+        ));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,163 @@
+use chia_bls::PublicKey;
+use chia_protocol::{Bytes32, Coin};
+use chia_puzzle_types::{CoinProof, Memos, singleton::SingletonSolution};
+use chia_sdk_types::{
+    Conditions,
+    puzzles::{DidRecoverySolution, DidSolution},
+};
+use clvm_traits::clvm_quote;
+use clvmr::NodePtr;
+
+use crate::{DriverError, Layer, SingletonInfo, Spend, SpendContext, SpendWithConditions};
+
+use super::{Did, DidInfo};
+
+/// An approval for a [`Did`] to be recovered to a new inner puzzle.
+///
+/// Each DID in the recovery list must create a message coin with this puzzle,
+/// which is then spent to announce its approval to the DID being recovered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DidAttestation {
+    /// The coin id of the DID being recovered.
+    pub coin_id: Bytes32,
+    /// The inner puzzle hash the DID will be recovered to.
+    pub new_inner_puzzle_hash: Bytes32,
+    /// The public key that must sign the new inner puzzle hash during the recovery.
+    pub public_key: PublicKey,
+}
+
+impl DidAttestation {
+    pub fn new(coin_id: Bytes32, new_inner_puzzle_hash: Bytes32, public_key: PublicKey) -> Self {
+        Self {
+            coin_id,
+            new_inner_puzzle_hash,
+            public_key,
+        }
+    }
+
+    /// Allocates the puzzle of the message coin, which is a quoted list of conditions.
+    pub fn message_puzzle(&self, ctx: &mut SpendContext) -> Result<NodePtr, DriverError> {
+        let conditions = Conditions::new()
+            .create_coin_announcement(self.coin_id.to_vec().into())
+            .agg_sig_unsafe(self.public_key, self.new_inner_puzzle_hash.to_vec().into());
+
+        ctx.alloc(&clvm_quote!(conditions))
+    }
+}
+
+impl Did {
+    /// Creates the [`DidAttestation`] that the recovery DIDs must approve, in order to recover
+    /// this DID to a new p2 puzzle hash. The new owner must sign with the given public key.
+    pub fn recovery_attestation(
+        &self,
+        p2_puzzle_hash: Bytes32,
+        public_key: PublicKey,
+    ) -> DidAttestation {
+        let new_inner_puzzle_hash = DidInfo {
+            p2_puzzle_hash,
+            ..self.info
+        }
+        .inner_puzzle_hash();
+
+        DidAttestation::new(
+            self.coin.coin_id(),
+            new_inner_puzzle_hash.into(),
+            public_key,
+        )
+    }
+
+    /// Attests to the recovery of another DID, by creating and spending a message coin.
+    ///
+    /// This returns the child of this DID, and the [`CoinProof`] that must be passed to
+    /// [`Did::recover`] to assert the attestation.
+    ///
+    /// This spend requires a [`Layer`] that supports [`SpendWithConditions`]. If it doesn't, you can
+    /// use [`Did::spend_with`] instead.
+    pub fn attest<I>(
+        self,
+        ctx: &mut SpendContext,
+        inner: &I,
+        attestation: DidAttestation,
+        extra_conditions: Conditions,
+    ) -> Result<(Did, CoinProof), DriverError>
+    where
+        I: SpendWithConditions,
+    {
+        let message_puzzle = attestation.message_puzzle(ctx)?;
+        let message_puzzle_hash = ctx.tree_hash(message_puzzle).into();
+
+        let did = self.update(
+            ctx,
+            inner,
+            extra_conditions.create_coin(message_puzzle_hash, 0, Memos::None),
+        )?;
+
+        let message_coin = Coin::new(self.coin.coin_id(), message_puzzle_hash, 0);
+        ctx.spend(message_coin, Spend::new(message_puzzle, NodePtr::NIL))?;
+
+        let coin_proof = CoinProof {
+            parent_coin_info: self.coin.parent_coin_info,
+            inner_puzzle_hash: self.info.inner_puzzle_hash().into(),
+            amount: self.coin.amount,
+        };
+
+        Ok((did, coin_proof))
+    }
+
+    /// Recovers this DID to a new p2 puzzle hash, using the attestations of its recovery DIDs.
+    ///
+    /// The recovery list must be revealed in full, and the coin proofs returned by [`Did::attest`]
+    /// must be provided in the same order. Although the puzzle only checks that enough DIDs attested,
+    /// it can't skip over DIDs in the list, so an attestation is required from each of them.
+    ///
+    /// The current p2 puzzle must be revealed, but isn't run. The new owner must sign the new inner
+    /// puzzle hash with the public key that was attested to.
+    pub fn recover(
+        self,
+        ctx: &mut SpendContext,
+        p2_puzzle: NodePtr,
+        attestation: DidAttestation,
+        p2_puzzle_hash: Bytes32,
+        recovery_list: Vec<Bytes32>,
+        coin_proofs: Vec<CoinProof>,
+    ) -> Result<Did, DriverError> {
+        let recovery_list_ptr = ctx.alloc(&recovery_list)?;
+        let recovery_list_hash = ctx.tree_hash(recovery_list_ptr).into();
+
+        if self.info.num_verifications_required == 0
+            || self.info.recovery_list_hash != Some(recovery_list_hash)
+        {
+            return Err(DriverError::InvalidRecoveryList);
+        }
+
+        if coin_proofs.len() != recovery_list.len() {
+            return Err(DriverError::MissingAttestations);
+        }
+
+        if attestation != self.recovery_attestation(p2_puzzle_hash, attestation.public_key) {
+            return Err(DriverError::WrongPuzzleHash);
+        }
+
+        let layers = self.info.into_layers(p2_puzzle);
+
+        let spend = layers.construct_spend(
+            ctx,
+            SingletonSolution {
+                lineage_proof: self.proof,
+                amount: self.coin.amount,
+                inner_solution: DidSolution::Recover(Box::new(DidRecoverySolution::new(
+                    self.coin.amount,
+                    attestation.new_inner_puzzle_hash,
+                    coin_proofs,
+                    attestation.public_key,
+                    recovery_list,
+                    self.coin.coin_id(),
+                ))),
+            },
+        )?;
+
+        ctx.spend(self.coin, spend)?;
+
+        Ok(self.child(p2_puzzle_hash, self.info.metadata, self.coin.amount))
+    }
+}
@@ -254,6 +254,39 @@ impl StreamedAsset {
         ctx.spend(self.coin, Spend::new(puzzle, solution))
     }
 
+    /// Creates the stream that remains after claiming the payment up to the given time, if any.
+    pub fn child(&self, payment_time: u64) -> Option<Self> {
+        let remaining_amount =
+            self.coin.amount - self.info.amount_to_be_paid(self.coin.amount, payment_time);
+
+        if remaining_amount == 0 {
+            return None;
+        }
+
+        let info = self.info.with_last_payment_time(payment_time);
+        let inner_puzzle_hash = info.inner_puzzle_hash();
+
+        let (puzzle_hash, proof) = if let Some(asset_id) = self.asset_id {
+            (
+                CatArgs::curry_tree_hash(asset_id, inner_puzzle_hash),
+                Some(LineageProof {
+                    parent_parent_coin_info: self.coin.parent_coin_info,
+                    parent_inner_puzzle_hash: self.info.inner_puzzle_hash().into(),
+                    parent_amount: self.coin.amount,
+                }),
+            )
+        } else {
+            (inner_puzzle_hash, None)
+        };
+
+        Some(Self {
+            coin: Coin::new(self.coin.coin_id(), puzzle_hash.into(), remaining_amount),
+            asset_id: self.asset_id,
+            proof,
+            info,
+        })
+    }
+
     // if clawback, 3rd arg = last paid amount
     pub fn from_parent_spend(
         ctx: &mut SpendContext,
@@ -2,10 +2,12 @@ mod p2_conditions_or_singleton;
 mod p2_singleton;
 mod vault_info;
 mod vault_launcher;
+mod vault_p2_puzzle;
 
 pub use p2_conditions_or_singleton::*;
 pub use p2_singleton::*;
 pub use vault_info::*;
+pub use vault_p2_puzzle::*;
 
 use chia_puzzle_types::singleton::{SingletonArgs, SingletonSolution};
 use clvm_utils::TreeHash;
@@ -0,0 +1,69 @@
+use chia_protocol::Bytes32;
+use clvm_utils::{ToTreeHash, TreeHash};
+
+use crate::{DriverError, P2ConditionsOrSingleton, P2Singleton, Spend, SpendContext};
+
+/// The p2 puzzle of a coin owned by a vault, which can be spent with a message from the vault singleton.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VaultP2Puzzle {
+    P2Singleton(P2Singleton),
+    P2ConditionsOrSingleton(P2ConditionsOrSingleton),
+}
+
+impl VaultP2Puzzle {
+    pub fn launcher_id(&self) -> Bytes32 {
+        match self {
+            Self::P2Singleton(p2_singleton) => p2_singleton.launcher_id,
+            Self::P2ConditionsOrSingleton(p2_conditions_or_singleton) => {
+                p2_conditions_or_singleton.launcher_id
+            }
+        }
+    }
+
+    pub fn spend(
+        &self,
+        ctx: &mut SpendContext,
+        singleton_inner_puzzle_hash: Bytes32,
+        singleton_amount: u64,
+        delegated_spend: Spend,
+    ) -> Result<Spend, DriverError> {
+        match self {
+            Self::P2Singleton(p2_singleton) => p2_singleton.spend(
+                ctx,
+                singleton_inner_puzzle_hash,
+                singleton_amount,
+                delegated_spend,
+            ),
+            Self::P2ConditionsOrSingleton(p2_conditions_or_singleton) => p2_conditions_or_singleton
+                .p2_singleton_spend(
+                    ctx,
+                    singleton_inner_puzzle_hash,
+                    singleton_amount,
+                    delegated_spend,
+                ),
+        }
+    }
+}
+
+impl ToTreeHash for VaultP2Puzzle {
+    fn tree_hash(&self) -> TreeHash {
+        match self {
+            Self::P2Singleton(p2_singleton) => p2_singleton.tree_hash(),
+            Self::P2ConditionsOrSingleton(p2_conditions_or_singleton) => {
+                p2_conditions_or_singleton.tree_hash()
+            }
+        }
+    }
+}
+
+impl From<P2Singleton> for VaultP2Puzzle {
+    fn from(value: P2Singleton) -> Self {
+        Self::P2Singleton(value)
+    }
+}
+
+impl From<P2ConditionsOrSingleton> for VaultP2Puzzle {
+    fn from(value: P2ConditionsOrSingleton) -> Self {
+        Self::P2ConditionsOrSingleton(value)
+    }
+}
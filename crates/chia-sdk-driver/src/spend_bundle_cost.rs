@@ -8,6 +8,10 @@ use crate::DriverError;
 
 const QUOTE_BYTES: usize = 2;
 
+pub(crate) const AGG_SIG_COST: u64 = 1_200_000;
+pub(crate) const CREATE_COIN_COST: u64 = 1_800_000;
+pub(crate) const COST_PER_BYTE: u64 = 12_000;
+
 pub fn spend_bundle_cost(coin_spends: &[CoinSpend]) -> Result<u64, DriverError> {
     let mut allocator = Allocator::new();
     let mut cost = 0;
@@ -22,15 +26,15 @@ pub fn spend_bundle_cost(coin_spends: &[CoinSpend]) -> Result<u64, DriverError>
 
         for condition in conditions {
             if condition.is_agg_sig() {
-                cost += 1_200_000;
+                cost += AGG_SIG_COST;
             } else if condition.is_create_coin() {
-                cost += 1_800_000;
+                cost += CREATE_COIN_COST;
             }
         }
     }
 
     let generator_length_without_quote = calculate_generator_length(coin_spends) - QUOTE_BYTES;
-    cost += generator_length_without_quote as u64 * 12_000;
+    cost += generator_length_without_quote as u64 * COST_PER_BYTE;
 
     Ok(cost)
 }
@@ -10,7 +10,7 @@ use chia_puzzle_types::{
     singleton::{SingletonArgs, SingletonStruct},
 };
 use chia_puzzles::SINGLETON_LAUNCHER_HASH;
-use chia_sdk_test::{Simulator, sign_transaction};
+use chia_sdk_test::{BlsPairWithCoin, Simulator, sign_transaction};
 use chia_sdk_types::{
     Condition, Conditions, MessageFlags, MessageSide, Mod,
     conditions::{CreateCoin, SendMessage},
@@ -20,6 +20,7 @@ use chia_sdk_utils::select_coins;
 use clvm_traits::{FromClvm, ToClvm};
 use clvm_utils::{ToTreeHash, TreeHash};
 use clvmr::{Allocator, NodePtr};
+use indexmap::indexmap;
 
 use crate::{
     Action, Cat, ClawbackV2, CurriedPuzzle, Deltas, Id, InnerPuzzleSpend, Launcher, Layer,
@@ -373,6 +374,27 @@ impl TestVault {
     }
 }
 
+/// Finishes the spends with the standard puzzle of the key, and signs them. The spends are finished with
+/// [`Relation::AssertConcurrent`], so that the result can be used to make or take an offer.
+pub fn finish_with_key(
+    ctx: &mut SpendContext,
+    spends: Spends,
+    deltas: &Deltas,
+    key: &BlsPairWithCoin,
+) -> Result<SpendBundle> {
+    spends.finish_with_keys(
+        ctx,
+        deltas,
+        Relation::AssertConcurrent,
+        &indexmap! { key.puzzle_hash => key.pk },
+    )?;
+
+    let coin_spends = ctx.take();
+    let signature = sign_transaction(&coin_spends, slice::from_ref(&key.sk))?;
+
+    Ok(SpendBundle::new(coin_spends, signature))
+}
+
 fn vault_custody_puzzle_hash(pk: PublicKey) -> TreeHash {
     mips_puzzle_hash(0, vec![], BlsMember::new(pk).curry_tree_hash(), true)
 }
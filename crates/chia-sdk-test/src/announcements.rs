@@ -18,20 +18,40 @@ pub struct Announcements {
     pub asserted_puzzle: Vec<Bytes32>,
 }
 
+/// A description of how the announcements created and asserted by a list of coin spends line up.
+#[derive(Debug, Default, Clone)]
+pub struct AnnouncementReport {
+    pub lines: Vec<String>,
+    pub is_valid: bool,
+}
+
 /// Print the announcements that are created and asserted by a list of coin spends.
 ///
 /// # Panics
 ///
 /// Panics if the announcements cannot be extracted from the coin spends.
 pub fn debug_announcements(coin_spends: &[CoinSpend]) {
-    let all_announcements: Vec<Announcements> = coin_spends
+    let report = announcement_report(coin_spends).expect("could not extract announcements");
+
+    for line in &report.lines {
+        println!("{line}");
+    }
+
+    assert!(
+        report.is_valid,
+        "asserted announcements do not match created announcements"
+    );
+}
+
+/// Match up the announcements that are created and asserted by a list of coin spends.
+pub fn announcement_report(coin_spends: &[CoinSpend]) -> anyhow::Result<AnnouncementReport> {
+    let all_announcements = coin_spends
         .iter()
-        .map(|coin_spend| {
-            announcements_for_spend(coin_spend).expect("could not extract announcements")
-        })
-        .collect();
+        .map(announcements_for_spend)
+        .collect::<anyhow::Result<Vec<Announcements>>>()?;
 
-    let mut should_panic = false;
+    let mut lines = Vec::new();
+    let mut is_valid = true;
 
     for (i, announcements) in all_announcements.iter().enumerate() {
         for &asserted_coin in &announcements.asserted_coin {
@@ -40,18 +60,18 @@ pub fn debug_announcements(coin_spends: &[CoinSpend]) {
                     asserted_coin == announcement_id(coin_spends[i].coin.coin_id(), message.clone())
                 })
             }) else {
-                println!(
+                lines.push(format!(
                     "spend {i} asserted unknown coin announcement {}",
                     hex::encode(&asserted_coin[0..4])
-                );
-                should_panic = true;
+                ));
+                is_valid = false;
                 continue;
             };
 
-            println!(
+            lines.push(format!(
                 "spend {i} asserted coin announcement created by spend {created_index}: {}",
                 hex::encode(&asserted_coin[0..4])
-            );
+            ));
         }
 
         for &asserted_puzzle in &announcements.asserted_puzzle {
@@ -61,57 +81,54 @@ pub fn debug_announcements(coin_spends: &[CoinSpend]) {
                         == announcement_id(coin_spends[i].coin.puzzle_hash, message.clone())
                 })
             }) else {
-                println!(
+                lines.push(format!(
                     "spend {i} asserted unknown puzzle announcement {}",
                     hex::encode(&asserted_puzzle[0..4])
-                );
-                should_panic = true;
+                ));
+                is_valid = false;
                 continue;
             };
 
-            println!(
+            lines.push(format!(
                 "spend {i} asserted puzzle announcement created by spend {created_index}: {}",
                 hex::encode(&asserted_puzzle[0..4])
-            );
+            ));
         }
 
         for created_coin in &announcements.created_coin {
-            let asserted = all_announcements.iter().enumerate().any(|(i, a)| {
+            let asserted = all_announcements.iter().any(|a| {
                 a.asserted_coin.iter().any(|&id| {
                     id == announcement_id(coin_spends[i].coin.coin_id(), created_coin.clone())
                 })
             });
 
             if !asserted {
-                println!(
+                lines.push(format!(
                     "spend {i} created coin announcement {} but it was not asserted",
                     hex::encode(&created_coin[0..4])
-                );
-                should_panic = true;
+                ));
+                is_valid = false;
             }
         }
 
         for created_puzzle in &announcements.created_puzzle {
-            let asserted = all_announcements.iter().enumerate().any(|(i, a)| {
+            let asserted = all_announcements.iter().any(|a| {
                 a.asserted_puzzle.iter().any(|&id| {
                     id == announcement_id(coin_spends[i].coin.puzzle_hash, created_puzzle.clone())
                 })
             });
 
             if !asserted {
-                println!(
+                lines.push(format!(
                     "spend {i} created puzzle announcement {} but it was not asserted",
                     hex::encode(&created_puzzle[0..4])
-                );
-                should_panic = true;
+                ));
+                is_valid = false;
             }
         }
     }
 
-    assert!(
-        !should_panic,
-        "asserted announcements do not match created announcements"
-    );
+    Ok(AnnouncementReport { lines, is_valid })
 }
 
 pub fn announcements_for_spend(coin_spend: &CoinSpend) -> anyhow::Result<Announcements> {
@@ -1,16 +1,19 @@
 use std::{net::SocketAddr, ops::Deref, sync::Arc};
 
-use chia_protocol::Message;
+use chia_protocol::{Bytes32, Coin, Message};
 use chia_sdk_client::{Peer, PeerOptions};
+use chia_ssl::ChiaCertificate;
+use indexmap::{IndexMap, IndexSet};
+use native_tls::{Identity, TlsAcceptor};
 use peer_map::PeerMap;
 use subscriptions::Subscriptions;
 use tokio::{
-    net::TcpListener,
+    net::{TcpListener, TcpStream},
     sync::{Mutex, mpsc},
     task::JoinHandle,
 };
 use tokio_tungstenite::connect_async;
-use ws_connection::ws_connection;
+use ws_connection::{broadcast_new_peak, peer_updates, ws_connection};
 
 use crate::Simulator;
 
@@ -29,6 +32,7 @@ pub struct PeerSimulator {
     addr: SocketAddr,
     simulator: Arc<Mutex<Simulator>>,
     subscriptions: Arc<Mutex<Subscriptions>>,
+    peer_map: PeerMap,
     join_handle: JoinHandle<()>,
 }
 
@@ -46,37 +50,50 @@ impl PeerSimulator {
     }
 
     pub async fn with_config(config: PeerSimulatorConfig) -> Result<Self, PeerSimulatorError> {
+        Self::start(config, None).await
+    }
+
+    /// Starts a simulator which accepts TLS websocket connections, the same way a full node does.
+    /// Peers are expected to send a handshake before any other message.
+    pub async fn with_tls(
+        config: PeerSimulatorConfig,
+        cert: &ChiaCertificate,
+    ) -> Result<Self, PeerSimulatorError> {
+        let identity = Identity::from_pkcs8(cert.cert_pem.as_bytes(), cert.key_pem.as_bytes())?;
+        let acceptor = tokio_native_tls::TlsAcceptor::from(TlsAcceptor::new(identity)?);
+        Self::start(config, Some(acceptor)).await
+    }
+
+    async fn start(
+        config: PeerSimulatorConfig,
+        tls: Option<tokio_native_tls::TlsAcceptor>,
+    ) -> Result<Self, PeerSimulatorError> {
         tracing::info!("starting simulator");
 
-        let addr = "127.0.0.1:0";
         let peer_map = PeerMap::default();
-        let listener = TcpListener::bind(addr).await?;
+        let listener = TcpListener::bind(config.bind_addr).await?;
         let addr = listener.local_addr()?;
         let simulator = Arc::new(Mutex::new(Simulator::default()));
         let subscriptions = Arc::new(Mutex::new(Subscriptions::default()));
         let config = Arc::new(config);
 
+        let peer_map_clone = peer_map.clone();
         let simulator_clone = simulator.clone();
         let subscriptions_clone = subscriptions.clone();
         let config_clone = config.clone();
 
         let join_handle = tokio::spawn(async move {
+            let peer_map = peer_map_clone;
             let simulator = simulator_clone;
             let subscriptions = subscriptions_clone;
             let config = config_clone;
 
             while let Ok((stream, addr)) = listener.accept().await {
-                let stream = match tokio_tungstenite::accept_async(stream).await {
-                    Ok(stream) => stream,
-                    Err(error) => {
-                        tracing::error!("error accepting websocket connection: {}", error);
-                        continue;
-                    }
-                };
-                tokio::spawn(ws_connection(
-                    peer_map.clone(),
+                tokio::spawn(accept_connection(
                     stream,
                     addr,
+                    tls.clone(),
+                    peer_map.clone(),
                     config.clone(),
                     simulator.clone(),
                     subscriptions.clone(),
@@ -89,10 +106,15 @@ impl PeerSimulator {
             addr,
             simulator,
             subscriptions,
+            peer_map,
             join_handle,
         })
     }
 
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
     pub fn config(&self) -> &PeerSimulatorConfig {
         &self.config
     }
@@ -136,6 +158,101 @@ impl PeerSimulator {
         *self.subscriptions.lock().await = Subscriptions::default();
         Ok(())
     }
+
+    /// Creates a new coin in its own block, and notifies subscribed peers about it.
+    pub async fn new_coin(
+        &self,
+        puzzle_hash: Bytes32,
+        amount: u64,
+    ) -> Result<Coin, PeerSimulatorError> {
+        let mut simulator = self.simulator.lock().await;
+        let coin = simulator.new_coin(puzzle_hash, amount);
+        simulator.create_block();
+
+        let updates = peer_updates(
+            &simulator,
+            &*self.subscriptions.lock().await,
+            &IndexSet::from([coin.coin_id()]),
+        );
+        broadcast_new_peak(&self.peer_map, &simulator, updates).await?;
+
+        Ok(coin)
+    }
+
+    /// Advances the timestamp by the given number of seconds, and creates a block at that time.
+    pub async fn pass_time(&self, seconds: u64) -> Result<(), PeerSimulatorError> {
+        let mut simulator = self.simulator.lock().await;
+        simulator.pass_time(seconds);
+        simulator.create_block();
+        broadcast_new_peak(&self.peer_map, &simulator, IndexMap::new()).await
+    }
+
+    /// Creates an empty block, and notifies peers about the new peak.
+    pub async fn create_block(&self) -> Result<(), PeerSimulatorError> {
+        let mut simulator = self.simulator.lock().await;
+        simulator.create_block();
+        broadcast_new_peak(&self.peer_map, &simulator, IndexMap::new()).await
+    }
+}
+
+async fn accept_connection(
+    stream: TcpStream,
+    addr: SocketAddr,
+    tls: Option<tokio_native_tls::TlsAcceptor>,
+    peer_map: PeerMap,
+    config: Arc<PeerSimulatorConfig>,
+    simulator: Arc<Mutex<Simulator>>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+) {
+    let Some(tls) = tls else {
+        let stream = match tokio_tungstenite::accept_async(stream).await {
+            Ok(stream) => stream,
+            Err(error) => {
+                tracing::error!("error accepting websocket connection: {}", error);
+                return;
+            }
+        };
+
+        ws_connection(
+            peer_map,
+            stream,
+            addr,
+            config,
+            simulator,
+            subscriptions,
+            false,
+        )
+        .await;
+
+        return;
+    };
+
+    let stream = match tls.accept(stream).await {
+        Ok(stream) => stream,
+        Err(error) => {
+            tracing::error!("error accepting tls connection: {}", error);
+            return;
+        }
+    };
+
+    let stream = match tokio_tungstenite::accept_async(stream).await {
+        Ok(stream) => stream,
+        Err(error) => {
+            tracing::error!("error accepting websocket connection: {}", error);
+            return;
+        }
+    };
+
+    ws_connection(
+        peer_map,
+        stream,
+        addr,
+        config,
+        simulator,
+        subscriptions,
+        true,
+    )
+    .await;
 }
 
 impl Drop for PeerSimulator {
@@ -151,7 +268,9 @@ mod tests {
         Bytes, Bytes32, Coin, CoinSpend, CoinState, CoinStateFilters, CoinStateUpdate,
         ProtocolMessageTypes, RespondCoinState, RespondPuzzleState, SpendBundle, TransactionAck,
     };
+    use chia_sdk_client::{PeerCoinSource, connect_peer, create_native_tls_connector};
     use chia_sdk_types::conditions::{AggSigMe, CreateCoin, Memos, Remark};
+    use chia_sdk_utils::CoinSource;
     use chia_traits::Streamable;
     use clvmr::NodePtr;
 
@@ -929,4 +1048,120 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_peer_coin_source() -> anyhow::Result<()> {
+        let sim = PeerSimulator::new().await?;
+        let peer = sim.connect().await?;
+        let source = PeerCoinSource::new(peer.clone(), sim.config().genesis_challenge);
+
+        let (puzzle_hash, puzzle_reveal) = to_puzzle(1)?;
+        let hint = Bytes32::new([42; 32]);
+
+        let coin = sim.lock().await.new_coin(puzzle_hash, 0);
+        let hinted_coin = sim.lock().await.new_coin(puzzle_hash, 1);
+        sim.lock().await.hint_coin(hinted_coin.coin_id(), hint);
+
+        assert_eq!(
+            source.unspent_coins(puzzle_hash, false).await?,
+            vec![coin, hinted_coin]
+        );
+        assert_eq!(source.unspent_coins(hint, false).await?, vec![]);
+        assert_eq!(source.unspent_coins(hint, true).await?, vec![hinted_coin]);
+        assert_eq!(source.coin_spend(coin.coin_id()).await?, None);
+
+        let coin_spend = CoinSpend::new(coin, puzzle_reveal, to_program(())?);
+        let spend_bundle = SpendBundle::new(vec![coin_spend.clone()], Signature::default());
+
+        let ack = peer.send_transaction(spend_bundle).await?;
+        assert_eq!(ack.status, 1);
+
+        assert_eq!(
+            source.unspent_coins(puzzle_hash, false).await?,
+            vec![hinted_coin]
+        );
+        assert_eq!(source.coin_spend(coin.coin_id()).await?, Some(coin_spend));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_new_coin_update() -> anyhow::Result<()> {
+        let sim = PeerSimulator::new().await?;
+        let (peer, mut receiver) = sim.connect_split().await?;
+
+        let puzzle_hash = Bytes32::new([1; 32]);
+
+        let coin_states = peer
+            .register_for_ph_updates(vec![puzzle_hash], 0)
+            .await?
+            .coin_states;
+        assert_eq!(coin_states.len(), 0);
+
+        let coin = sim.new_coin(puzzle_hash, 1000).await?;
+
+        // Wait for a response, so that the update has been received.
+        peer.request_children(coin.coin_id()).await?;
+
+        let updates = coin_state_updates(&mut receiver);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].items, vec![CoinState::new(coin, None, Some(0))]);
+        assert_eq!(sim.lock().await.height(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pass_time() -> anyhow::Result<()> {
+        let sim = PeerSimulator::new().await?;
+
+        let timestamp = sim.lock().await.next_timestamp();
+        sim.pass_time(100).await?;
+        sim.create_block().await?;
+
+        assert_eq!(sim.lock().await.height(), 2);
+        assert_eq!(sim.lock().await.next_timestamp(), timestamp + 102);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tls_handshake() -> anyhow::Result<()> {
+        let sim = PeerSimulator::with_tls(
+            PeerSimulatorConfig::default(),
+            &ChiaCertificate::generate()?,
+        )
+        .await?;
+
+        let connector = create_native_tls_connector(&ChiaCertificate::generate()?)?;
+
+        let (peer, mut receiver) = connect_peer(
+            "simulator0".to_string(),
+            connector,
+            sim.addr(),
+            PeerOptions::default(),
+        )
+        .await?;
+
+        let message = receiver
+            .recv()
+            .await
+            .expect("expected NewPeakWallet message");
+        assert_eq!(message.msg_type, ProtocolMessageTypes::NewPeakWallet);
+
+        let coin = sim.new_coin(Bytes32::default(), 1000).await?;
+
+        let response = peer
+            .request_coin_state(
+                vec![coin.coin_id()],
+                None,
+                sim.config().genesis_challenge,
+                false,
+            )
+            .await?
+            .unwrap();
+        assert_eq!(response.coin_states.len(), 1);
+
+        Ok(())
+    }
 }
@@ -1,8 +1,11 @@
+use std::net::SocketAddr;
+
 use chia_protocol::Bytes32;
 use chia_sdk_types::TESTNET11_CONSTANTS;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PeerSimulatorConfig {
+    pub bind_addr: SocketAddr,
     pub genesis_challenge: Bytes32,
     pub max_subscriptions: usize,
     pub max_response_coins: usize,
@@ -12,6 +15,7 @@ pub struct PeerSimulatorConfig {
 impl Default for PeerSimulatorConfig {
     fn default() -> Self {
         Self {
+            bind_addr: SocketAddr::from(([127, 0, 0, 1], 0)),
             genesis_challenge: TESTNET11_CONSTANTS.genesis_challenge,
             max_subscriptions: 200_000,
             max_response_coins: 100_000,
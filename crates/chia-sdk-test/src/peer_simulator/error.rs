@@ -14,6 +14,9 @@ pub enum PeerSimulatorError {
     #[error("io error: {0}")]
     Io(#[from] io::Error),
 
+    #[error("tls error: {0}")]
+    Tls(#[from] native_tls::Error),
+
     #[error("websocket error: {0}")]
     WebSocket(#[from] tungstenite::Error),
 
@@ -37,4 +40,7 @@ pub enum PeerSimulatorError {
 
     #[error("unsupported protocol message type: {0:?}")]
     UnsupportedMessage(ProtocolMessageTypes),
+
+    #[error("expected handshake, but received {0:?}")]
+    ExpectedHandshake(ProtocolMessageTypes),
 }
@@ -7,7 +7,7 @@ use tokio_tungstenite::tungstenite::Message;
 pub(crate) type Ws = UnboundedSender<Message>;
 type Peers = HashMap<SocketAddr, Ws>;
 
-#[derive(Default, Clone)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct PeerMap(Arc<Mutex<Peers>>);
 
 impl PeerMap {
@@ -2,10 +2,10 @@ use std::{net::SocketAddr, sync::Arc};
 
 use chia_consensus::validation_error::{ErrorCode, ValidationErr};
 use chia_protocol::{
-    Bytes, Bytes32, CoinState, CoinStateUpdate, Message, NewPeakWallet, ProtocolMessageTypes,
-    PuzzleSolutionResponse, RegisterForCoinUpdates, RegisterForPhUpdates, RejectCoinState,
-    RejectPuzzleSolution, RejectPuzzleState, RejectStateReason, RequestChildren, RequestCoinState,
-    RequestPuzzleSolution, RequestPuzzleState, RequestRemoveCoinSubscriptions,
+    Bytes, Bytes32, CoinState, CoinStateUpdate, Handshake, Message, NewPeakWallet, NodeType,
+    ProtocolMessageTypes, PuzzleSolutionResponse, RegisterForCoinUpdates, RegisterForPhUpdates,
+    RejectCoinState, RejectPuzzleSolution, RejectPuzzleState, RejectStateReason, RequestChildren,
+    RequestCoinState, RequestPuzzleSolution, RequestPuzzleState, RequestRemoveCoinSubscriptions,
     RequestRemovePuzzleSubscriptions, RespondChildren, RespondCoinState, RespondPuzzleSolution,
     RespondPuzzleState, RespondRemoveCoinSubscriptions, RespondRemovePuzzleSubscriptions,
     RespondToCoinUpdates, RespondToPhUpdates, SendTransaction, SpendBundle, TransactionAck,
@@ -13,11 +13,11 @@ use chia_protocol::{
 use chia_traits::Streamable;
 use clvmr::NodePtr;
 use futures_channel::mpsc::{self, UnboundedSender};
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{SinkExt, StreamExt, stream::SplitStream};
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use tokio::{
-    net::TcpStream,
+    io::{AsyncRead, AsyncWrite},
     sync::{Mutex, MutexGuard},
 };
 use tokio_tungstenite::{
@@ -32,15 +32,24 @@ use super::{
     subscriptions::Subscriptions,
 };
 
-pub(crate) async fn ws_connection(
+pub(crate) async fn ws_connection<S>(
     peer_map: PeerMap,
-    ws: WebSocketStream<TcpStream>,
+    ws: WebSocketStream<S>,
     addr: SocketAddr,
     config: Arc<PeerSimulatorConfig>,
     simulator: Arc<Mutex<Simulator>>,
     subscriptions: Arc<Mutex<Subscriptions>>,
-) {
+    expect_handshake: bool,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
     let (mut tx, mut rx) = mpsc::unbounded();
+    let (mut sink, mut stream) = ws.split();
+
+    if expect_handshake && let Err(error) = handle_handshake(&mut tx, &mut stream, &config).await {
+        tracing::error!("error performing handshake: {}", error);
+        return;
+    }
 
     if let Err(error) = handle_initial_peak(&mut tx, &simulator).await {
         tracing::error!("error sending initial peak: {}", error);
@@ -49,8 +58,6 @@ pub(crate) async fn ws_connection(
 
     peer_map.insert(addr, tx.clone()).await;
 
-    let (mut sink, mut stream) = ws.split();
-
     tokio::spawn(async move {
         while let Some(message) = rx.next().await {
             if let Err(error) = sink.send(message).await {
@@ -87,6 +94,49 @@ pub(crate) async fn ws_connection(
     peer_map.remove(addr).await;
 }
 
+async fn handle_handshake<S>(
+    tx: &mut UnboundedSender<tungstenite::Message>,
+    stream: &mut SplitStream<WebSocketStream<S>>,
+    config: &PeerSimulatorConfig,
+) -> Result<(), PeerSimulatorError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let Some(message) = stream.next().await else {
+        return Err(tungstenite::Error::ConnectionClosed.into());
+    };
+
+    let message = Message::from_bytes(&message?.into_data())?;
+
+    if message.msg_type != ProtocolMessageTypes::Handshake {
+        return Err(PeerSimulatorError::ExpectedHandshake(message.msg_type));
+    }
+
+    let handshake = Handshake::from_bytes(&message.data)?;
+
+    tx.send(
+        Message {
+            msg_type: ProtocolMessageTypes::Handshake,
+            id: None,
+            data: Handshake {
+                network_id: handshake.network_id,
+                protocol_version: handshake.protocol_version,
+                software_version: "0.0.0".to_string(),
+                server_port: config.bind_addr.port(),
+                node_type: NodeType::FullNode,
+                capabilities: handshake.capabilities,
+            }
+            .to_bytes()?
+            .into(),
+        }
+        .to_bytes()?
+        .into(),
+    )
+    .await?;
+
+    Ok(())
+}
+
 async fn handle_initial_peak(
     tx: &mut UnboundedSender<tungstenite::Message>,
     sim: &Mutex<Simulator>,
@@ -207,6 +257,15 @@ fn new_transaction(
     spend_bundle: SpendBundle,
 ) -> Result<IndexMap<SocketAddr, IndexSet<CoinState>>, PeerSimulatorError> {
     let updates = simulator.new_transaction(spend_bundle)?;
+    let coin_ids = updates.keys().copied().collect();
+    Ok(peer_updates(simulator, subscriptions, &coin_ids))
+}
+
+pub(crate) fn peer_updates(
+    simulator: &Simulator,
+    subscriptions: &Subscriptions,
+    coin_ids: &IndexSet<Bytes32>,
+) -> IndexMap<SocketAddr, IndexSet<CoinState>> {
     let peers = subscriptions.peers();
 
     let mut peer_updates = IndexMap::new();
@@ -225,7 +284,7 @@ fn new_transaction(
             .cloned()
             .unwrap_or_default();
 
-        for &coin_id in updates.keys() {
+        for &coin_id in coin_ids {
             let Some(coin_state) = simulator.coin_state(coin_id) else {
                 continue;
             };
@@ -238,10 +297,10 @@ fn new_transaction(
         }
 
         for &hint in &puzzle_subscriptions {
-            let coin_ids = simulator.hinted_coins(hint);
+            let hinted_coin_ids = simulator.hinted_coins(hint);
 
-            for coin_id in coin_ids {
-                if updates.contains_key(&coin_id) {
+            for coin_id in hinted_coin_ids {
+                if coin_ids.contains(&coin_id) {
                     coin_states.extend(simulator.coin_state(coin_id));
                 }
             }
@@ -254,7 +313,7 @@ fn new_transaction(
         peer_updates.insert(peer, coin_states);
     }
 
-    Ok(peer_updates)
+    peer_updates
 }
 
 async fn send_transaction(
@@ -285,43 +344,7 @@ async fn send_transaction(
         }
     };
 
-    let header_hash = simulator.header_hash();
-
-    let new_peak = Message {
-        msg_type: ProtocolMessageTypes::NewPeakWallet,
-        id: None,
-        data: NewPeakWallet::new(header_hash, simulator.height(), 0, simulator.height())
-            .to_bytes()
-            .unwrap()
-            .into(),
-    }
-    .to_bytes()?;
-
-    // Send updates to peers.
-    for (addr, mut peer) in peer_map.peers().await {
-        peer.send(new_peak.clone().into()).await?;
-
-        let Some(peer_updates) = updates.get(&addr).cloned() else {
-            continue;
-        };
-
-        let update = Message {
-            msg_type: ProtocolMessageTypes::CoinStateUpdate,
-            id: None,
-            data: CoinStateUpdate::new(
-                simulator.height(),
-                simulator.height(),
-                header_hash,
-                peer_updates.into_iter().collect(),
-            )
-            .to_bytes()
-            .unwrap()
-            .into(),
-        }
-        .to_bytes()?;
-
-        peer.send(update.into()).await?;
-    }
+    broadcast_new_peak(&peer_map, &simulator, updates).await?;
 
     Ok(TransactionAck::new(transaction_id, 1, None)
         .to_bytes()?
@@ -608,3 +631,49 @@ fn request_remove_puzzle_subscriptions(
         .to_bytes()?
         .into())
 }
+
+pub(crate) async fn broadcast_new_peak(
+    peer_map: &PeerMap,
+    simulator: &Simulator,
+    updates: IndexMap<SocketAddr, IndexSet<CoinState>>,
+) -> Result<(), PeerSimulatorError> {
+    let header_hash = simulator.header_hash();
+
+    let new_peak = Message {
+        msg_type: ProtocolMessageTypes::NewPeakWallet,
+        id: None,
+        data: NewPeakWallet::new(header_hash, simulator.height(), 0, simulator.height())
+            .to_bytes()
+            .unwrap()
+            .into(),
+    }
+    .to_bytes()?;
+
+    // Send updates to peers.
+    for (addr, mut peer) in peer_map.peers().await {
+        peer.send(new_peak.clone().into()).await?;
+
+        let Some(peer_updates) = updates.get(&addr).cloned() else {
+            continue;
+        };
+
+        let update = Message {
+            msg_type: ProtocolMessageTypes::CoinStateUpdate,
+            id: None,
+            data: CoinStateUpdate::new(
+                simulator.height(),
+                simulator.height(),
+                header_hash,
+                peer_updates.into_iter().collect(),
+            )
+            .to_bytes()
+            .unwrap()
+            .into(),
+        }
+        .to_bytes()?;
+
+        peer.send(update.into()).await?;
+    }
+
+    Ok(())
+}
@@ -1,9 +1,10 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, convert::Infallible};
 
 use chia_bls::SecretKey;
 use chia_consensus::validation_error::ErrorCode;
 use chia_protocol::{Bytes32, Coin, CoinSpend, CoinState, Program, SpendBundle};
 use chia_sdk_types::TESTNET11_CONSTANTS;
+use chia_sdk_utils::CoinSource;
 use clvmr::ENABLE_KECCAK_OPS_OUTSIDE_GUARD;
 use indexmap::{IndexMap, IndexSet, indexset};
 use rand::{Rng, SeedableRng};
@@ -445,3 +446,23 @@ impl Simulator {
         self.data.next_timestamp += 1;
     }
 }
+
+impl CoinSource for Simulator {
+    type Error = Infallible;
+
+    async fn unspent_coins(
+        &self,
+        puzzle_hash: Bytes32,
+        include_hints: bool,
+    ) -> Result<Vec<Coin>, Self::Error> {
+        Ok(Simulator::unspent_coins(self, puzzle_hash, include_hints))
+    }
+
+    async fn coin_spend(&self, coin_id: Bytes32) -> Result<Option<CoinSpend>, Self::Error> {
+        Ok(Simulator::coin_spend(self, coin_id))
+    }
+
+    async fn coin_states(&self, coin_ids: Vec<Bytes32>) -> Result<Vec<CoinState>, Self::Error> {
+        Ok(self.lookup_coin_ids(&coin_ids.into_iter().collect()))
+    }
+}
@@ -1,4 +1,5 @@
 mod augmented_condition;
+mod did;
 mod everything_with_singleton;
 mod mips;
 mod mods;
@@ -11,6 +12,7 @@ mod p2_singleton;
 mod revocation;
 
 pub use augmented_condition::*;
+pub use did::*;
 pub use everything_with_singleton::*;
 pub use mips::*;
 pub use mods::*;
@@ -0,0 +1,49 @@
+use chia_bls::PublicKey;
+use chia_protocol::Bytes32;
+use chia_puzzle_types::CoinProof;
+use clvm_traits::{FromClvm, ToClvm};
+
+/// The solution to the DID inner puzzle.
+///
+/// This differs from [`chia_puzzle_types::did::DidSolution`] in that the recovery
+/// solution includes the coin id of the DID, which the puzzle asserts.
+#[derive(Debug, Clone, PartialEq, Eq, ToClvm, FromClvm)]
+#[clvm(list)]
+#[repr(u8)]
+pub enum DidSolution<I> {
+    Recover(#[clvm(rest)] Box<DidRecoverySolution>) = 0,
+    Spend(I) = 1,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ToClvm, FromClvm)]
+#[clvm(list)]
+pub struct DidRecoverySolution {
+    pub amount: u64,
+    pub new_inner_puzzle_hash: Bytes32,
+    /// The parent coin id, inner puzzle hash and amount of each DID coin that
+    /// attested to the recovery, in the same order as the recovery list.
+    pub recovery_coins: Vec<CoinProof>,
+    pub public_key: PublicKey,
+    pub recovery_list_reveal: Vec<Bytes32>,
+    pub coin_id: Bytes32,
+}
+
+impl DidRecoverySolution {
+    pub fn new(
+        amount: u64,
+        new_inner_puzzle_hash: Bytes32,
+        recovery_coins: Vec<CoinProof>,
+        public_key: PublicKey,
+        recovery_list_reveal: Vec<Bytes32>,
+        coin_id: Bytes32,
+    ) -> Self {
+        Self {
+            amount,
+            new_inner_puzzle_hash,
+            recovery_coins,
+            public_key,
+            recovery_list_reveal,
+            coin_id,
+        }
+    }
+}
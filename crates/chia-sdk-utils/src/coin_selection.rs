@@ -22,13 +22,16 @@ pub enum CoinSelectionError {
     ExceededMaxCoins,
 }
 
+/// The maximum number of coins that can be selected at once.
+pub const MAX_COINS: usize = 500;
+
 /// Uses the knapsack algorithm to select coins.
 pub fn select_coins(
     mut spendable_coins: Vec<Coin>,
     amount: u64,
 ) -> Result<Vec<Coin>, CoinSelectionError> {
     let amount = u128::from(amount);
-    let max_coins = 500;
+    let max_coins = MAX_COINS;
 
     // You cannot spend no coins.
     if spendable_coins.is_empty() {
@@ -0,0 +1,32 @@
+use std::future::Future;
+
+use chia_protocol::{Bytes32, Coin, CoinSpend, CoinState};
+
+/// A source of coins and their spends, such as a simulator, a full node peer, or an RPC client.
+///
+/// This is used to look up the coins that are available to spend, and to parse them into assets
+/// by looking at the spend of their parent coin.
+pub trait CoinSource {
+    type Error;
+
+    /// Fetches the unspent coins with the given puzzle hash. If `include_hints` is `true`,
+    /// coins that are hinted with the puzzle hash are included as well.
+    fn unspent_coins(
+        &self,
+        puzzle_hash: Bytes32,
+        include_hints: bool,
+    ) -> impl Future<Output = Result<Vec<Coin>, Self::Error>>;
+
+    /// Fetches the spend of a coin, or `None` if it hasn't been spent.
+    fn coin_spend(
+        &self,
+        coin_id: Bytes32,
+    ) -> impl Future<Output = Result<Option<CoinSpend>, Self::Error>>;
+
+    /// Fetches the current states of the coins with the given ids, including spent coins.
+    /// Coins that don't exist are omitted.
+    fn coin_states(
+        &self,
+        coin_ids: Vec<Bytes32>,
+    ) -> impl Future<Output = Result<Vec<CoinState>, Self::Error>>;
+}
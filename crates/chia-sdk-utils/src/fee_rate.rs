@@ -0,0 +1,56 @@
+/// A fee rate, expressed as an amount of mojos per a given amount of CLVM cost.
+///
+/// This is stored as a ratio rather than a float, so that fee rates such as the mempool minimum
+/// fees (which are reported in mojos per 5,000,000 cost) can be represented exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRate {
+    pub mojos: u64,
+    pub cost: u64,
+}
+
+impl FeeRate {
+    pub const ZERO: Self = Self::new(0, 1);
+
+    pub const fn new(mojos: u64, cost: u64) -> Self {
+        Self { mojos, cost }
+    }
+
+    /// A fee rate of a whole number of mojos per unit of cost.
+    pub const fn mojos_per_cost(mojos: u64) -> Self {
+        Self::new(mojos, 1)
+    }
+
+    /// Calculates the fee for a transaction with the given cost, rounded up to the nearest mojo.
+    pub fn fee(&self, cost: u64) -> u64 {
+        if self.cost == 0 {
+            return 0;
+        }
+
+        let fee = (u128::from(cost) * u128::from(self.mojos)).div_ceil(u128::from(self.cost));
+        fee.try_into().unwrap_or(u64::MAX)
+    }
+}
+
+impl Default for FeeRate {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rate() {
+        assert_eq!(FeeRate::ZERO.fee(1_000_000), 0);
+        assert_eq!(FeeRate::mojos_per_cost(5).fee(1_000_000), 5_000_000);
+        assert_eq!(FeeRate::new(1, 2).fee(3), 2);
+        assert_eq!(
+            FeeRate::new(10_000_000, 5_000_000).fee(1_234_567),
+            2_469_134
+        );
+        assert_eq!(FeeRate::new(1, 0).fee(100), 0);
+        assert_eq!(FeeRate::mojos_per_cost(u64::MAX).fee(2), u64::MAX);
+    }
+}
@@ -1,7 +1,11 @@
 mod bech32;
 mod coin_selection;
+mod coin_source;
+mod fee_rate;
 mod hex;
 
 pub use bech32::*;
 pub use coin_selection::*;
+pub use coin_source::*;
+pub use fee_rate::*;
 pub use hex::*;
@@ -29,4 +29,4 @@ pub use chia_sdk_types::{
     Compilation, Condition, Conditions, MAINNET_CONSTANTS, MerkleProof, MerkleTree, Mod,
     TESTNET11_CONSTANTS, compile_chialisp, compile_rue, conditions::*, run_puzzle,
 };
-pub use chia_sdk_utils::{Address, Bech32, parse_hex, select_coins};
+pub use chia_sdk_utils::{Address, Bech32, CoinSource, FeeRate, parse_hex, select_coins};